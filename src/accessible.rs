@@ -0,0 +1,75 @@
+use wayout_solver::{
+    bit::Bit,
+    board::{BoardAssignment, BoardDescription, Cell},
+    coords,
+    grid::Position,
+};
+
+/// Describes a single cell in words, including its modifiers.
+fn describe_cell(cell: &Option<Cell>) -> String {
+    let Some(cell) = cell else {
+        return "hole".to_string();
+    };
+    let mut words = vec![match cell.starting_value {
+        Bit::On => "lit",
+        Bit::Off => "unlit",
+    }
+    .to_string()];
+    for modifier in cell.modifiers().chars() {
+        words.push(
+            match modifier {
+                'H' => "not affecting up or down",
+                'V' => "not affecting left or right",
+                'O' => "rigid",
+                'u' => "not affecting up",
+                'd' => "not affecting down",
+                'l' => "not affecting left",
+                'r' => "not affecting right",
+                _ => continue,
+            }
+            .to_string(),
+        );
+    }
+    words.join(" ")
+}
+
+/// Describes `board` in words, one line per row, for use with a screen reader. Rows and columns are numbered by `coords`.
+pub fn describe_board(board: &BoardDescription) -> String {
+    let mut text = format!(
+        "Board with {} rows and {} columns.\n",
+        board.height(),
+        board.width()
+    );
+    for row in 0..board.height() {
+        let cells: Vec<String> = (0..board.width())
+            .map(|col| describe_cell(&board[Position::new(row, col)]))
+            .collect();
+        text.push_str(&format!(
+            "Row {}: {}.\n",
+            coords::row_number(row, board.height()),
+            cells.join(", ")
+        ));
+    }
+    text
+}
+
+/// Describes the presses of `assignment` as a spoken list of coordinates, for use with a screen reader.
+/// Rows and columns are numbered by `coords`.
+pub fn describe_assignment(assignment: &BoardAssignment) -> String {
+    let presses = assignment.presses();
+    let coordinates: Vec<String> = presses
+        .iter()
+        .map(|pos| {
+            format!(
+                "row {} column {}",
+                coords::row_number(pos.row, assignment.height()),
+                coords::col_number(pos.col)
+            )
+        })
+        .collect();
+    match presses.len() {
+        0 => "No presses needed.\n".to_string(),
+        1 => format!("1 press: {}.\n", coordinates[0]),
+        n => format!("{} presses: {}.\n", n, coordinates.join("; ")),
+    }
+}
@@ -0,0 +1,436 @@
+use crate::{
+    bit::Bit,
+    bitslice,
+    board::BoardDescription,
+    coords,
+    equations::Equations,
+    grid::Position,
+    matrix::Matrix,
+    report::Report,
+    solver::{self, Strategy, MAX_ENUMERATED_NULLITY},
+    table::{Table, TableStyle},
+};
+
+/// Summarizes the algebraic properties of a board.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    pub width: usize,
+    pub height: usize,
+    /// Number of cells that are not holes. This is the number of variables of the system.
+    pub cells: usize,
+    pub lit_cells: usize,
+    pub rank: usize,
+    /// Number of free variables.
+    pub nullity: usize,
+    /// Number of solutions, or `None` if it does not fit in a `u128`.
+    pub solution_count: Option<u128>,
+    /// Fewest presses of any solution. `None` if the board has no solutions, or too many free variables to search.
+    pub min_presses: Option<usize>,
+}
+
+impl Analysis {
+    pub fn new(board: &BoardDescription) -> Self {
+        let (matrix, _) = board.to_matrix();
+        let report = Report::new(&matrix);
+        let eqns = &report.equations;
+        let min_presses = if eqns.is_consistent() {
+            solver::par(board)
+        } else {
+            None
+        };
+        Analysis {
+            width: board.width(),
+            height: board.height(),
+            cells: eqns.var_count(),
+            lit_cells: board.count_ones(),
+            rank: eqns.dependent_count(),
+            nullity: eqns.free_var_count(),
+            solution_count: eqns.solution_count(),
+            min_presses,
+        }
+    }
+
+    /// Renders the number of solutions, falling back to a power of two when it is too large to compute.
+    pub fn solution_count_text(&self) -> String {
+        match self.solution_count {
+            Some(count) => count.to_string(),
+            None => format!("2^{}", self.nullity),
+        }
+    }
+
+    /// Renders the fewest presses, or `-` when unknown.
+    pub fn min_presses_text(&self) -> String {
+        self.min_presses.map_or("-".to_string(), |m| m.to_string())
+    }
+
+    /// Renders `self` as a table with one metric per row.
+    pub fn to_table(&self, style: TableStyle) -> Table {
+        let mut table = Table::new(&["Metric", "Value"]).with_style(style);
+        table.add_row(&["Width", &self.width.to_string()]);
+        table.add_row(&["Height", &self.height.to_string()]);
+        table.add_row(&["Cells", &self.cells.to_string()]);
+        table.add_row(&["Lit cells", &self.lit_cells.to_string()]);
+        table.add_row(&["Rank", &self.rank.to_string()]);
+        table.add_row(&["Nullity", &self.nullity.to_string()]);
+        table.add_row(&["Solutions", &self.solution_count_text()]);
+        table.add_row(&["Fewest presses", &self.min_presses_text()]);
+        table
+    }
+}
+
+/// Renders a table summarizing several analyses, one row per board, labeled with the names in `analyses`.
+pub fn summary_table(analyses: &[(String, Analysis)], style: TableStyle) -> Table {
+    let mut table = Table::new(&[
+        "Board",
+        "Size",
+        "Cells",
+        "Rank",
+        "Nullity",
+        "Solutions",
+        "Fewest presses",
+    ])
+    .with_style(style);
+    for (name, analysis) in analyses {
+        table.add_row(&[
+            name.clone(),
+            format!("{}x{}", analysis.width, analysis.height),
+            analysis.cells.to_string(),
+            analysis.rank.to_string(),
+            analysis.nullity.to_string(),
+            analysis.solution_count_text(),
+            analysis.min_presses_text(),
+        ]);
+    }
+    table
+}
+
+/// Lists the one-way influences of `board`: every pair of cells where pressing the first toggles the second, but pressing the
+/// second does not toggle the first. Pairs are in reading order of the pressed cell, then of the toggled one.
+///
+/// Plain cells affect each other both ways, so only `H` and `V` modifiers make such pairs. In games where modifiers are mutual, a
+/// pair usually means the modifier grid was entered wrongly. Rigid cells are one-way by design, so pairs with one are left out.
+pub fn asymmetric_pairs(board: &BoardDescription) -> Vec<(Position, Position)> {
+    let (matrix, indexed_locations) = board.to_matrix();
+    let vars = matrix.variable_cols();
+    let is_rigid = |var: &usize| board[indexed_locations[var]].is_some_and(|cell| cell.is_rigid);
+    let mut pairs = vec![];
+    for pressed in (0..vars).filter(|var| !is_rigid(var)) {
+        for affected in (0..vars).filter(|var| !is_rigid(var)) {
+            if matrix.get(affected, pressed) == Bit::On && matrix.get(pressed, affected) == Bit::Off
+            {
+                pairs.push((indexed_locations[&pressed], indexed_locations[&affected]));
+            }
+        }
+    }
+    pairs
+}
+
+/// How flipping the starting value of a single cell changes a board, as found by `sensitivity`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellSensitivity {
+    pub pos: Position,
+    /// Whether the board is solvable with the cell flipped.
+    pub solvable: bool,
+    /// Whether flipping the cell changes whether the board is solvable. A misread fragile cell turns a solvable board into an
+    /// unsolvable one, or the other way around.
+    pub fragile: bool,
+    /// Fewest presses with the cell flipped, as found by `solver::minimum_solution`. `None` if it is not solvable.
+    pub min_presses: Option<usize>,
+    /// Number of cells whose press differs between the solutions with the fewest presses before and after the flip. `None` unless
+    /// the board is solvable both ways.
+    pub changed_presses: Option<usize>,
+}
+
+/// Flips the starting value of every cell of `board` in turn, and checks how each flip changes its solvability and its solution
+/// with the fewest presses. Tells whether a cell that may have been read wrongly matters at all. Cells are in reading order.
+pub fn sensitivity(board: &BoardDescription) -> Vec<CellSensitivity> {
+    let solution = solver::minimum_solution(board);
+    Position::iter_row_major(board.width(), board.height())
+        .filter(|&pos| board[pos].is_some())
+        .map(|pos| {
+            let mut flipped = board.clone();
+            if let Some(cell) = &mut flipped[pos] {
+                cell.starting_value += Bit::On;
+            }
+            let flipped_solution = solver::minimum_solution(&flipped);
+            let changed_presses = solution
+                .as_ref()
+                .zip(flipped_solution.as_ref())
+                .map(|(a, b)| {
+                    let (a, b) = (a.presses(), b.presses());
+                    a.iter().filter(|pos| !b.contains(pos)).count()
+                        + b.iter().filter(|pos| !a.contains(pos)).count()
+                });
+            CellSensitivity {
+                pos,
+                solvable: flipped_solution.is_some(),
+                fragile: flipped_solution.is_some() != solution.is_some(),
+                min_presses: flipped_solution
+                    .as_ref()
+                    .map(|solution| solution.count_ones()),
+                changed_presses,
+            }
+        })
+        .collect()
+}
+
+/// Renders `board` with every fragile cell in `cells` marked `!`, every other cell `.`, and holes left blank.
+pub fn sensitivity_map(board: &BoardDescription, cells: &[CellSensitivity]) -> String {
+    let mut lines = vec![vec![' '; board.width()]; board.height()];
+    for cell in cells {
+        lines[cell.pos.row][cell.pos.col] = if cell.fragile { '!' } else { '.' };
+    }
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Renders a table of `cells` of `board`, one row per cell, numbered by `coords`.
+pub fn sensitivity_table(
+    board: &BoardDescription,
+    cells: &[CellSensitivity],
+    style: TableStyle,
+) -> Table {
+    let mut table = Table::new(&[
+        "Row",
+        "Column",
+        "Solvable",
+        "Fragile",
+        "Fewest presses",
+        "Presses changed",
+    ])
+    .with_style(style);
+    for cell in cells {
+        table.add_row(&[
+            coords::row_number(cell.pos.row, board.height()).to_string(),
+            coords::col_number(cell.pos.col).to_string(),
+            if cell.solvable { "yes" } else { "no" }.to_string(),
+            if cell.fragile { "yes" } else { "no" }.to_string(),
+            cell.min_presses.map_or("-".to_string(), |m| m.to_string()),
+            cell.changed_presses
+                .map_or("-".to_string(), |m| m.to_string()),
+        ]);
+    }
+    table
+}
+
+/// The notable states measured by `target_distances`, in order: every light on, every light off, the checkerboard with the top
+/// left cell lit, and the one with it unlit.
+pub const NOTABLE_TARGETS: [&str; 4] =
+    ["all on", "all off", "checkerboard", "inverted checkerboard"];
+
+/// How far a board is from one of the `NOTABLE_TARGETS`.
+#[derive(Clone, Debug)]
+pub struct TargetDistance {
+    pub target: &'static str,
+    /// Fewest presses that bring the board to the target. `None` if no presses do.
+    pub min_presses: Option<usize>,
+    /// Whether `min_presses` is exact, rather than greedily approximated on boards with more than `MAX_SEARCHED_NULLITY` free
+    /// variables.
+    pub exact: bool,
+}
+
+/// Finds the fewest presses that bring `board`, from its starting state, to each of the `NOTABLE_TARGETS`, ignoring its own target.
+/// The matrix is augmented with the constant column of every target and eliminated once, so this costs a single elimination.
+pub fn target_distances(board: &BoardDescription) -> Vec<TargetDistance> {
+    let (matrix, _) = board.to_matrix();
+    let vars = matrix.variable_cols();
+    let data = (0..matrix.rows)
+        .map(|row| matrix.row(row)[..vars].to_vec())
+        .collect();
+    let mut reduced = Matrix::new(data, matrix.rows, vars).expect("Could not form matrix");
+
+    let cells: Vec<(Position, Bit)> = Position::iter_row_major(board.width(), board.height())
+        .filter_map(|pos| board[pos].map(|cell| (pos, cell.starting_value)))
+        .collect();
+    let columns: Vec<Vec<Bit>> = (0..NOTABLE_TARGETS.len())
+        .map(|target| {
+            cells
+                .iter()
+                .map(|&(pos, start)| {
+                    let checkered = (pos.row + pos.col) % 2 == 0;
+                    let lit = match target {
+                        0 => true,
+                        1 => false,
+                        2 => checkered,
+                        _ => !checkered,
+                    };
+                    start + if lit { Bit::On } else { Bit::Off }
+                })
+                .collect()
+        })
+        .collect();
+    reduced.augment_columns(&columns);
+    reduced.eliminate();
+
+    NOTABLE_TARGETS
+        .iter()
+        .enumerate()
+        .map(|(index, &target)| {
+            let eqns = Equations::for_target(reduced.clone(), index);
+            let exact = Strategy::Auto.is_exact(eqns.free_var_count());
+            let bits = solver::auto_minimum(&eqns);
+            TargetDistance {
+                target,
+                min_presses: bits.map(|bits| bits.iter().filter(|&&b| b == Bit::On).count()),
+                exact,
+            }
+        })
+        .collect()
+}
+
+/// Renders a table of `distances`, one row per target. Approximated press counts are marked with `~`.
+pub fn target_table(distances: &[TargetDistance], style: TableStyle) -> Table {
+    let mut table = Table::new(&["Target", "Fewest presses"]).with_style(style);
+    for distance in distances {
+        table.add_row(&[
+            distance.target.to_string(),
+            match distance.min_presses {
+                Some(presses) if distance.exact => presses.to_string(),
+                Some(presses) => format!("~{}", presses),
+                None => "-".to_string(),
+            },
+        ]);
+    }
+    table
+}
+
+/// Summarizes one component of a board: a set of cells whose presses only affect each other. Every component is solved
+/// independently of the rest of the board, which is solvable only if all of its components are.
+#[derive(Clone, Debug)]
+pub struct ComponentAnalysis {
+    /// The cells of the component, in reading order.
+    pub cells: Vec<Position>,
+    pub solvable: bool,
+    /// Number of free variables of the component.
+    pub nullity: usize,
+    /// Fewest presses that solve the component. `None` if it has no solutions, or too many free variables to search.
+    pub min_presses: Option<usize>,
+}
+
+/// Splits `board` into its components and analyzes each of them, ordered by their first cell in reading order.
+pub fn components(board: &BoardDescription) -> Vec<ComponentAnalysis> {
+    let (matrix, indexed_locations) = board.to_matrix();
+    let vars = matrix.variable_cols();
+
+    let mut parents: Vec<usize> = (0..vars).collect();
+    // Rows past the cells pin a single press each, and join nothing.
+    for row in 0..vars {
+        for (col, &bit) in matrix.row(row)[..vars].iter().enumerate() {
+            if bit == Bit::On {
+                let (a, b) = (find_root(&mut parents, row), find_root(&mut parents, col));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+    }
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut group_of_root = vec![None; vars];
+    for var in 0..vars {
+        let root = find_root(&mut parents, var);
+        let group = *group_of_root[root].get_or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[group].push(var);
+    }
+
+    groups
+        .into_iter()
+        .map(|vars_in_group| {
+            let rows: Vec<usize> = vars_in_group
+                .iter()
+                .copied()
+                .chain((vars..matrix.rows).filter(|&row| {
+                    matrix
+                        .leading_column(row)
+                        .is_some_and(|col| vars_in_group.contains(&col))
+                }))
+                .collect();
+            let data = rows
+                .iter()
+                .map(|&row| {
+                    vars_in_group
+                        .iter()
+                        .map(|&col| matrix.get(row, col))
+                        .collect()
+                })
+                .collect();
+            let mut sub = Matrix::new(data, rows.len(), vars_in_group.len())
+                .expect("Components are never empty");
+            let needed: Vec<Bit> = rows.iter().map(|&row| matrix.get(row, vars)).collect();
+            sub.augment_column(&needed);
+            let eqns = Report::new(&sub).equations;
+            let nullity = eqns.free_var_count();
+            let min_presses = (nullity <= MAX_ENUMERATED_NULLITY)
+                .then(|| bitslice::minimum(&eqns))
+                .flatten()
+                .map(|bits| bits.iter().filter(|&&bit| bit == Bit::On).count());
+            ComponentAnalysis {
+                cells: vars_in_group
+                    .iter()
+                    .map(|var| indexed_locations[var])
+                    .collect(),
+                solvable: eqns.is_consistent(),
+                nullity,
+                min_presses,
+            }
+        })
+        .collect()
+}
+
+/// Finds the representative of the component of `var` in the union-find forest `parents`, halving the path to it on the way.
+fn find_root(parents: &mut [usize], mut var: usize) -> usize {
+    while parents[var] != var {
+        parents[var] = parents[parents[var]];
+        var = parents[var];
+    }
+    var
+}
+
+/// The label of the component at `index`: the letters `A` to `Z`, then `a` to `z`, then `*` for all the rest.
+pub fn component_label(index: usize) -> char {
+    match index {
+        0..26 => (b'A' + index as u8) as char,
+        26..52 => (b'a' + (index - 26) as u8) as char,
+        _ => '*',
+    }
+}
+
+/// Renders `board` with every cell replaced by the label of its component in `components`, and holes left blank.
+pub fn component_map(board: &BoardDescription, components: &[ComponentAnalysis]) -> String {
+    let mut lines = vec![vec![' '; board.width()]; board.height()];
+    for (index, component) in components.iter().enumerate() {
+        for pos in &component.cells {
+            lines[pos.row][pos.col] = component_label(index);
+        }
+    }
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Renders a table of `components`, one row per component, labeled as in `component_map`.
+pub fn component_table(components: &[ComponentAnalysis], style: TableStyle) -> Table {
+    let mut table = Table::new(&[
+        "Component",
+        "Cells",
+        "Solvable",
+        "Nullity",
+        "Fewest presses",
+    ])
+    .with_style(style);
+    for (index, component) in components.iter().enumerate() {
+        table.add_row(&[
+            component_label(index).to_string(),
+            component.cells.len().to_string(),
+            if component.solvable { "yes" } else { "no" }.to_string(),
+            component.nullity.to_string(),
+            component
+                .min_presses
+                .map_or("-".to_string(), |m| m.to_string()),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,316 @@
+use crate::{bit::Bit, equations::Equations, kernel, trace};
+
+/// The number of solutions evaluated together, one per bit of a word.
+pub const LANES: usize = u64::BITS as usize;
+
+/// The most solutions `tally` is used for by default. Evaluating 64 solutions per word operation makes this many affordable.
+pub const MAX_TALLIED_SOLUTIONS: u128 = 1 << 24;
+
+/// The patterns of the low index bits across the lanes of a block: bit `j` of `LANE_PATTERNS[s]` is bit `s` of `j`.
+const LANE_PATTERNS: [u64; 6] = [
+    0xAAAA_AAAA_AAAA_AAAA,
+    0xCCCC_CCCC_CCCC_CCCC,
+    0xF0F0_F0F0_F0F0_F0F0,
+    0xFF00_FF00_FF00_FF00,
+    0xFFFF_0000_FFFF_0000,
+    0xFFFF_FFFF_0000_0000,
+];
+
+/// Up to 64 consecutive solutions of a system, bit-sliced: bit `j` of `values[v]` is the value of variable `v` in the `j`th of them.
+#[derive(Clone, Debug)]
+pub struct Block {
+    /// The index of the first solution of the block, in the order of `Equations::enumerate_all_results`.
+    pub first: u128,
+    /// How many of the lanes hold a solution. Only the last block of a system can have fewer than `LANES`.
+    pub lanes: usize,
+    /// The values of every variable, one word per variable.
+    pub values: Vec<u64>,
+}
+
+impl Block {
+    /// The mask of the lanes holding a solution.
+    pub fn mask(&self) -> u64 {
+        if self.lanes == LANES {
+            u64::MAX
+        } else {
+            (1 << self.lanes) - 1
+        }
+    }
+
+    /// Extracts the solution in lane `lane`, where the `i`th bit is the value of the variable at index `i`.
+    pub fn lane(&self, lane: usize) -> Vec<Bit> {
+        self.values
+            .iter()
+            .map(|word| {
+                if word >> lane & 1 == 1 {
+                    Bit::On
+                } else {
+                    Bit::Off
+                }
+            })
+            .collect()
+    }
+
+    /// Counts the presses of the solution in every lane, by adding the words of all variables in a bit-sliced counter.
+    pub fn press_counts(&self) -> [usize; LANES] {
+        // `planes[p]` holds bit `p` of every lane's count.
+        let mut planes: Vec<u64> = vec![];
+        for &word in &self.values {
+            let mut carry = word;
+            for plane in planes.iter_mut() {
+                if carry == 0 {
+                    break;
+                }
+                let next = *plane & carry;
+                *plane ^= carry;
+                carry = next;
+            }
+            if carry != 0 {
+                planes.push(carry);
+            }
+        }
+        let mut counts = [0; LANES];
+        for (lane, count) in counts.iter_mut().enumerate() {
+            *count = (0..planes.len())
+                .filter(|&p| planes[p] >> lane & 1 == 1)
+                .map(|p| 1 << p)
+                .sum();
+        }
+        counts
+    }
+}
+
+/// Evaluates the solutions of a system in blocks of 64, in the order of `Equations::enumerate_all_results`. Built by `blocks`.
+///
+/// Every solution is the particular solution plus the kernel vectors of the free variables set in its index. The lowest six bits
+/// of the index vary across the lanes of a block, so each kernel vector costs one word operation per variable for all 64 lanes.
+pub struct Blocks {
+    /// The solution with every free variable `Off`.
+    particular: Vec<Bit>,
+    /// The variables set by each kernel vector, in the order of the free variables.
+    kernel: Vec<Vec<usize>>,
+    /// The index of the first solution of the next block.
+    next: u128,
+    /// The number of solutions.
+    total: u128,
+}
+
+/// Starts evaluating every solution of `eqns`. Returns `None` if `eqns` is inconsistent, or has too many solutions to count.
+pub fn blocks(eqns: &Equations) -> Option<Blocks> {
+    let total = eqns.solution_count().filter(|&total| total > 0)?;
+    let particular = eqns.particular_solution()?.to_bits(eqns.var_count())?;
+    let kernel = eqns
+        .kernel_basis()
+        .iter()
+        .map(|vector| {
+            (0..vector.len())
+                .filter(|&var| vector[var] == Bit::On)
+                .collect()
+        })
+        .collect();
+    Some(Blocks {
+        particular,
+        kernel,
+        next: 0,
+        total,
+    })
+}
+
+impl Iterator for Blocks {
+    type Item = Block;
+
+    fn next(&mut self) -> Option<Block> {
+        if self.next >= self.total {
+            return None;
+        }
+        let first = self.next;
+        let lanes = (self.total - first).min(LANES as u128) as usize;
+        self.next += lanes as u128;
+
+        let mut values: Vec<u64> = self
+            .particular
+            .iter()
+            .map(|&bit| if bit == Bit::On { u64::MAX } else { 0 })
+            .collect();
+        let count = self.kernel.len();
+        for (j, vars) in self.kernel.iter().enumerate() {
+            // The first free variable is the most significant bit of the index.
+            let shift = count - 1 - j;
+            let word = match LANE_PATTERNS.get(shift) {
+                Some(&pattern) => pattern,
+                None if first >> shift & 1 == 1 => u64::MAX,
+                None => 0,
+            };
+            if word == 0 {
+                continue;
+            }
+            for &var in vars {
+                values[var] ^= word;
+            }
+        }
+        Some(Block {
+            first,
+            lanes,
+            values,
+        })
+    }
+}
+
+/// Statistics over every solution of a system.
+#[derive(Clone, Debug)]
+pub struct Tally {
+    /// The number of solutions.
+    pub solutions: u128,
+    /// How many solutions press each variable, by variable index.
+    pub presses: Vec<u128>,
+    /// How many solutions have each number of presses, by number of presses.
+    pub press_counts: Vec<u128>,
+}
+
+/// Counts, across every solution of `eqns`, how often each variable is pressed and how many presses the solutions take.
+/// Returns `None` if `eqns` is inconsistent, or has too many solutions to count.
+pub fn tally(eqns: &Equations) -> Option<Tally> {
+    let _span = trace::span("enumerate");
+    let blocks = blocks(eqns)?;
+    let mut tally = Tally {
+        solutions: 0,
+        presses: vec![0; eqns.var_count()],
+        press_counts: vec![0; eqns.var_count() + 1],
+    };
+    for block in blocks {
+        let mask = block.mask();
+        for (presses, word) in tally.presses.iter_mut().zip(&block.values) {
+            *presses += (word & mask).count_ones() as u128;
+        }
+        for &count in &block.press_counts()[..block.lanes] {
+            tally.press_counts[count] += 1;
+        }
+        tally.solutions += block.lanes as u128;
+    }
+    Some(tally)
+}
+
+/// Finds every solution of `eqns` with the fewest presses, ordered by `kernel::cmp_presses`. Returns `None` if `eqns` is
+/// inconsistent, or has too many solutions to count.
+pub fn minimums(eqns: &Equations) -> Option<Vec<Vec<Bit>>> {
+    let _span = trace::span("enumerate");
+    let mut fewest = usize::MAX;
+    let mut found = vec![];
+    for block in blocks(eqns)? {
+        let counts = block.press_counts();
+        for (lane, &presses) in counts[..block.lanes].iter().enumerate() {
+            if presses < fewest {
+                fewest = presses;
+                found.clear();
+            }
+            if presses == fewest {
+                found.push(block.lane(lane));
+            }
+        }
+    }
+    found.sort_by(|a, b| kernel::cmp_presses(a, b));
+    Some(found)
+}
+
+/// Finds the solution of `eqns` with the fewest presses, breaking ties by `kernel::cmp_presses`. Returns `None` if `eqns` is
+/// inconsistent, or has too many solutions to count.
+pub fn minimum(eqns: &Equations) -> Option<Vec<Bit>> {
+    let _span = trace::span("enumerate");
+    let mut best: Option<(usize, Vec<Bit>)> = None;
+    for block in blocks(eqns)? {
+        let counts = block.press_counts();
+        let Some(&presses) = counts[..block.lanes].iter().min() else {
+            continue;
+        };
+        if best
+            .as_ref()
+            .is_some_and(|(best_presses, _)| presses > *best_presses)
+        {
+            continue;
+        }
+        // Narrows the lightest lanes down to the one whose first press comes earliest, a variable at a time.
+        let mut lanes = (0..block.lanes)
+            .filter(|&lane| counts[lane] == presses)
+            .fold(0u64, |mask, lane| mask | 1 << lane);
+        for &word in &block.values {
+            if lanes.is_power_of_two() {
+                break;
+            }
+            if lanes & word != 0 {
+                lanes &= word;
+            }
+        }
+        let candidate = block.lane(lanes.trailing_zeros() as usize);
+        if best.as_ref().is_none_or(|(_, best)| {
+            kernel::cmp_presses(&candidate, best) == std::cmp::Ordering::Less
+        }) {
+            best = Some((presses, candidate));
+        }
+    }
+    best.map(|(_, bits)| bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::parse_board_file, report::Report};
+
+    /// The equations of a plain `size` by `size` board with every light off.
+    fn plain(size: usize) -> Equations {
+        let text = vec!["0".repeat(size); size].join("\n");
+        Report::new(&parse_board_file(&text).unwrap().to_matrix().0).equations
+    }
+
+    /// Every solution of `eqns`, in order, found one at a time with `nth_solution`.
+    fn solutions(eqns: &Equations) -> Vec<Vec<Bit>> {
+        (0..)
+            .map_while(|index| eqns.nth_solution(index))
+            .map(|solution| solution.to_bits(eqns.var_count()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn blocks_hold_the_solutions_in_order() {
+        // 4, 16 and 256 solutions, so the 9x9 board spans several blocks.
+        for size in [5, 4, 9] {
+            let eqns = plain(size);
+            let expected = solutions(&eqns);
+            let mut found = vec![];
+            for block in blocks(&eqns).unwrap() {
+                assert_eq!(block.first, found.len() as u128);
+                for (lane, &count) in block.press_counts()[..block.lanes].iter().enumerate() {
+                    let solution = block.lane(lane);
+                    assert_eq!(count, solution.iter().filter(|&&b| b == Bit::On).count());
+                    found.push(solution);
+                }
+            }
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn tally_counts_every_solution() {
+        let eqns = plain(9);
+        let expected = solutions(&eqns);
+        let tally = tally(&eqns).unwrap();
+        assert_eq!(tally.solutions, expected.len() as u128);
+        for (var, &presses) in tally.presses.iter().enumerate() {
+            let pressing = expected.iter().filter(|solution| solution[var] == Bit::On);
+            assert_eq!(presses, pressing.count() as u128);
+        }
+        for (count, &solutions) in tally.press_counts.iter().enumerate() {
+            let with_count = expected
+                .iter()
+                .filter(|solution| solution.iter().filter(|&&b| b == Bit::On).count() == count);
+            assert_eq!(solutions, with_count.count() as u128);
+        }
+    }
+
+    #[test]
+    fn unsolvable_systems_have_no_blocks() {
+        let board = parse_board_file("10").unwrap();
+        let eqns = Report::new(&board.to_matrix().0).equations;
+        assert!(blocks(&eqns).is_none());
+        assert!(tally(&eqns).is_none());
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     fmt::Display,
     ops::{Index, IndexMut},
+    str::FromStr,
 };
 
 use nom::{
@@ -15,15 +16,77 @@ use nom::{
 
 use crate::{
     bit::Bit,
+    coords,
     equations::Assignment,
     grid::{Direction, Grid, Position},
     matrix::Matrix,
+    trace,
 };
 
+/// The characters accepted in a modifier grid, besides the space which marks an unmodified cell.
+pub const MODIFIERS: &[char] = &['H', 'V', 'O'];
+
+/// Guesses which of `MODIFIERS` a mistyped modifier `ch` was meant to be, by case and by look. Returns `None` if nothing is close.
+pub fn closest_modifier(ch: char) -> Option<char> {
+    match ch.to_ascii_uppercase() {
+        'H' | '-' | '_' | '=' | '#' => Some('H'),
+        'V' | '|' | '!' | 'Y' | 'U' => Some('V'),
+        'O' | '0' | 'Q' | 'D' | 'C' | '@' => Some('O'),
+        _ => None,
+    }
+}
+
 /// Describes the initial state of the board, along with any modifiers its cells might have
 #[derive(Clone, Debug)]
 pub struct BoardDescription {
     grid: Grid<Option<Cell>>,
+    rigid: RigidSemantics,
+}
+
+/// What being rigid (`O`) means for a cell. Games disagree on it, so every board picks one of these, `Shielded` by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RigidSemantics {
+    /// Presses on adjacent cells do not toggle the cell. Pressing it toggles it and its neighbors as usual.
+    #[default]
+    Shielded,
+    /// The cell never toggles, neither from its own press nor from presses on adjacent cells. Pressing it still toggles its
+    /// neighbors.
+    Frozen,
+    /// Pressing the cell does nothing. Presses on adjacent cells toggle it as usual.
+    Unpressable,
+    /// Pressing the cell only toggles the cell itself, without reaching its neighbors. Presses on adjacent cells toggle it as usual.
+    Inert,
+}
+
+impl RigidSemantics {
+    /// Every semantics, the default first.
+    pub const ALL: [RigidSemantics; 4] = [
+        RigidSemantics::Shielded,
+        RigidSemantics::Frozen,
+        RigidSemantics::Unpressable,
+        RigidSemantics::Inert,
+    ];
+
+    /// Finds the semantics for a name, `shielded`, `frozen`, `unpressable` or `inert`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "shielded" => Some(RigidSemantics::Shielded),
+            "frozen" => Some(RigidSemantics::Frozen),
+            "unpressable" => Some(RigidSemantics::Unpressable),
+            "inert" => Some(RigidSemantics::Inert),
+            _ => None,
+        }
+    }
+
+    /// The name of the semantics, as accepted by `from_name`.
+    pub fn name(self) -> &'static str {
+        match self {
+            RigidSemantics::Shielded => "shielded",
+            RigidSemantics::Frozen => "frozen",
+            RigidSemantics::Unpressable => "unpressable",
+            RigidSemantics::Inert => "inert",
+        }
+    }
 }
 
 /// Describes an assignment of bits on a board.
@@ -33,7 +96,9 @@ pub struct BoardAssignment {
 
 /// Encodes the intial value, and possible connections of a cell.
 ///
-/// `is_rigid` simply encodes if neighboring cells can affect the value of the center cell.
+/// `is_rigid` marks a rigid cell, whose behavior is given by the `RigidSemantics` of its board. By default, neighboring cells
+/// cannot affect its value.
+/// `target_value` is the value the cell must end up with for the puzzle to be solved, which is `On` unless stated otherwise.
 #[derive(Clone, Copy, Debug)]
 pub struct Cell {
     pub affects_up: bool,
@@ -42,6 +107,7 @@ pub struct Cell {
     pub affects_right: bool,
     pub is_rigid: bool,
     pub starting_value: Bit,
+    pub target_value: Bit,
 }
 
 impl Display for BoardAssignment {
@@ -72,13 +138,14 @@ impl Display for Cell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
             f,
-            "U:{} D:{} L:{} R:{} R:{} V:{}",
+            "U:{} D:{} L:{} R:{} R:{} V:{} T:{}",
             self.affects_up,
             self.affects_down,
             self.affects_left,
             self.affects_right,
             self.is_rigid,
-            self.starting_value
+            self.starting_value,
+            self.target_value
         )
     }
 }
@@ -112,6 +179,7 @@ impl Cell {
             affects_right,
             is_rigid,
             starting_value,
+            target_value: Bit::On,
         }
     }
 
@@ -119,6 +187,63 @@ impl Cell {
     pub fn new_basic(b: Bit) -> Self {
         Self::new(true, true, true, true, false, b)
     }
+
+    /// Applies the modifier encoded by `ch` to `self`. `H` stops the cell from affecting its vertical neighbors, `V` stops it from
+    /// affecting its horizontal neighbors, and `O` makes it rigid. Returns false if `ch` is not one of `MODIFIERS`.
+    pub fn apply_modifier(&mut self, ch: char) -> bool {
+        match ch {
+            'H' => {
+                self.affects_up = false;
+                self.affects_down = false;
+            }
+            'V' => {
+                self.affects_left = false;
+                self.affects_right = false;
+            }
+            'O' => self.is_rigid = true,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Renders the modifiers of `self` using the same letters as the modifier grid. An unmodified cell produces an empty string.
+    /// Connections that cannot be expressed with `H` and `V` are rendered individually as `u`, `d`, `l` and `r`.
+    pub fn modifiers(&self) -> String {
+        let mut res = String::new();
+        if !self.affects_up && !self.affects_down {
+            res.push('H');
+        } else {
+            if !self.affects_up {
+                res.push('u');
+            }
+            if !self.affects_down {
+                res.push('d');
+            }
+        }
+        if !self.affects_left && !self.affects_right {
+            res.push('V');
+        } else {
+            if !self.affects_left {
+                res.push('l');
+            }
+            if !self.affects_right {
+                res.push('r');
+            }
+        }
+        if self.is_rigid {
+            res.push('O');
+        }
+        res
+    }
+
+    /// Checks whether `self` and `other` have the same connections and rigidity, regardless of their starting values.
+    pub fn same_modifiers(&self, other: &Cell) -> bool {
+        self.affects_up == other.affects_up
+            && self.affects_down == other.affects_down
+            && self.affects_left == other.affects_left
+            && self.affects_right == other.affects_right
+            && self.is_rigid == other.is_rigid
+    }
 }
 
 impl Display for BoardDescription {
@@ -144,10 +269,87 @@ impl Display for BoardDescription {
 }
 
 impl BoardAssignment {
+    /// Creates an assignment from its rows, padding short rows with holes. Returns `None` if there are no rows, or they are all
+    /// empty.
+    pub fn from_rows(rows: Vec<Vec<Option<Bit>>>) -> Option<Self> {
+        Grid::new_partial_lines(rows, None).map(|grid| BoardAssignment { grid })
+    }
+
     /// Count the total number of `On` bits on the board. Corresponds to the number of button presses a board assignment uses.
     pub fn count_ones(&self) -> usize {
         self.grid.count(|oc| oc.is_some_and(|c| c == Bit::On))
     }
+
+    pub fn height(&self) -> usize {
+        self.grid.get_height()
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid.get_width()
+    }
+
+    /// Lists the positions of every `On` bit in row-major order. Corresponds to the buttons a board assignment presses.
+    pub fn presses(&self) -> Vec<Position> {
+        Position::iter_row_major(self.grid.get_width(), self.grid.get_height())
+            .filter(|&pos| self.grid[pos] == Some(Bit::On))
+            .collect()
+    }
+
+    /// Renders how many times to press each cell as a grid of digits, without a header, which is the format most other solvers
+    /// and guides use. Presses only matter modulo 2, so every digit is `0` or `1`. Holes are left blank.
+    pub fn to_press_grid(&self) -> String {
+        let mut text = String::new();
+        for row in self.grid.rows_iter() {
+            for col in self.grid.cols_iter() {
+                match self.grid[Position::new(row, col)] {
+                    Some(b) => text.push_str(&b.to_string()),
+                    None => text.push(' '),
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Renders the costs of the presses of `self` over its grid, where `costs` holds the cost of every cell in reading order. Pressed
+    /// cells show their cost, the others a `.`, and holes are left blank.
+    pub fn to_cost_overlay(&self, costs: &[u32]) -> String {
+        let mut costs = costs.iter();
+        let mut text = String::new();
+        for row in self.grid.rows_iter() {
+            for col in self.grid.cols_iter() {
+                match self.grid[Position::new(row, col)] {
+                    Some(b) => {
+                        let cost = costs.next().copied().unwrap_or(0);
+                        match b {
+                            Bit::On => text.push_str(&cost.to_string()),
+                            Bit::Off => text.push('.'),
+                        }
+                    }
+                    None => text.push(' '),
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl Index<Position> for BoardAssignment {
+    type Output = Option<Bit>;
+
+    fn index(&self, index: Position) -> &Self::Output {
+        &self.grid[index]
+    }
+}
+
+impl FromStr for BoardDescription {
+    type Err = String;
+
+    /// Parses the contents of a board file, like `parse_board_file`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_board_file(s)
+    }
 }
 
 impl Index<Position> for BoardDescription {
@@ -165,12 +367,82 @@ impl IndexMut<Position> for BoardDescription {
 }
 
 impl BoardDescription {
+    /// Creates a board from its rows, padding short rows with holes. Returns `None` if there are no rows, they are all empty, or
+    /// they hold nothing but holes.
+    pub fn from_rows(rows: Vec<Vec<Option<Cell>>>) -> Option<Self> {
+        Grid::new_partial_lines(rows, None)
+            .map(|grid| BoardDescription {
+                grid,
+                rigid: RigidSemantics::default(),
+            })
+            .filter(|board| board.cell_count() > 0)
+    }
+
+    /// Renders the starting state of `self` at half its size, each character standing for a square of four cells drawn with a
+    /// quadrant block. Lit cells are filled, and unlit cells and holes are left blank.
+    pub fn thumbnail(&self) -> Vec<String> {
+        const QUADRANTS: [char; 16] = [
+            ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+        ];
+        let lit = |row: usize, col: usize| {
+            self.grid
+                .get(Position::new(row, col))
+                .and_then(|cell| cell.as_ref())
+                .is_some_and(|cell| cell.starting_value == Bit::On)
+        };
+        (0..self.height().div_ceil(2))
+            .map(|line| {
+                (0..self.width().div_ceil(2))
+                    .map(|col| {
+                        let (row, col) = (2 * line, 2 * col);
+                        let index = usize::from(lit(row, col))
+                            | usize::from(lit(row, col + 1)) << 1
+                            | usize::from(lit(row + 1, col)) << 2
+                            | usize::from(lit(row + 1, col + 1)) << 3;
+                        QUADRANTS[index]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Trims the rows and columns made only of holes off the edges of `self`. Returns the trimmed board, and the position in `self`
+    /// of its top left corner. Holes inside the board are kept, as removing them would make the cells on either side adjacent.
+    pub fn cropped(&self) -> (BoardDescription, Position) {
+        let cells: Vec<Position> = Position::iter_row_major(self.width(), self.height())
+            .filter(|&pos| self.grid[pos].is_some())
+            .collect();
+        let (Some(top), Some(bottom)) = (cells.first(), cells.last()) else {
+            return (self.clone(), Position::new(0, 0));
+        };
+        let (top, bottom) = (top.row, bottom.row);
+        let left = cells.iter().map(|pos| pos.col).min().unwrap_or(0);
+        let right = cells.iter().map(|pos| pos.col).max().unwrap_or(0);
+        let rows = (top..=bottom)
+            .map(|row| {
+                (left..=right)
+                    .map(|col| self.grid[Position::new(row, col)])
+                    .collect()
+            })
+            .collect();
+        let mut board =
+            BoardDescription::from_rows(rows).expect("Cropping keeps every cell of the board");
+        board.rigid = self.rigid;
+        (board, Position::new(top, left))
+    }
+
     /// Computes the adjacency matrix of a board. Takes into account the modifiers a cell may have when computing it.
     ///
     /// Returns both the resulting matrix, and the enumeration of its cells, which is a map from indices to positions on the board.
     /// This enumeration also corresponds to the variables in the system of equations in the matrix. In effect, both of them encode which variable
     /// in the system of equations corresponds to which cell on the board.
+    ///
+    /// Row `i` is the equation of the cell of variable `i`. Under `RigidSemantics::Unpressable`, these are followed by one row
+    /// per rigid cell, in reading order, pinning its press to `Off`.
+    ///
+    /// Panics if `self` has no cells, which every way of building a board outside of this crate rules out.
     pub fn to_matrix(&self) -> (Matrix, HashMap<usize, Position>) {
+        let _span = trace::span("index");
         let mut indexed_locations: HashMap<usize, Position> = HashMap::new();
         let mut indexed_values: HashMap<usize, Cell> = HashMap::new();
         let mut index_of: HashMap<Position, usize> = HashMap::new();
@@ -189,7 +461,10 @@ impl BoardDescription {
         let mut matrix_data: Vec<Vec<Bit>> = vec![vec![Bit::Off; count]; count];
 
         for var in 0..count {
-            matrix_data[var][var] = Bit::On;
+            let this_cell = &indexed_values[&var];
+            if self.toggles(this_cell, this_cell, None) {
+                matrix_data[var][var] = Bit::On;
+            }
             let this_pos = indexed_locations[&var];
 
             for dir in [
@@ -201,11 +476,8 @@ impl BoardDescription {
                 if let Some(adjacent_pos) = this_pos.step_in_bounds(&self.grid, dir) {
                     if let Some(adjacent_index) = index_of.get(&adjacent_pos) {
                         if let Some(adjacent_cell) = indexed_values.get(adjacent_index) {
-                            let this_cell = indexed_values.get(&var).unwrap();
-                            if !this_cell.is_rigid {
-                                if adjacent_cell[dir.op()] {
-                                    matrix_data[var][*adjacent_index] = Bit::On;
-                                }
+                            if self.toggles(adjacent_cell, this_cell, Some(dir.op())) {
+                                matrix_data[var][*adjacent_index] = Bit::On;
                             }
                         }
                     }
@@ -213,27 +485,186 @@ impl BoardDescription {
             }
         }
 
-        let mut almost_matrix =
-            Matrix::new(matrix_data, count, count).expect("Could not form matrix");
+        // A cell that cannot be pressed affects no cell, so nothing would constrain its variable. Its press is pinned to `Off` by an
+        // extra row instead, rather than being left free.
+        let mut constant_column = self.constant_column();
+        for var in 0..count {
+            if !self.pressable(&indexed_values[&var]) {
+                let mut row = vec![Bit::Off; count];
+                row[var] = Bit::On;
+                matrix_data.push(row);
+                constant_column.push(Bit::Off);
+            }
+        }
+        let rows = matrix_data.len();
 
-        let constant_row = (0..count)
-            .map(|i| indexed_values[&i])
-            .map(|b| b.starting_value + Bit::On)
-            // We add On to everything as the constant row is equal to the current state,
-            // plus the target state, which is all `On`s.
-            .collect::<Vec<Bit>>();
+        let mut almost_matrix =
+            Matrix::new(matrix_data, rows, count).expect("Could not form matrix");
 
-        almost_matrix.augment_column(&constant_row);
+        almost_matrix.augment_column(&constant_column);
 
         (almost_matrix, indexed_locations)
     }
 
+    /// Computes the constant column of the system of equations of `self`, in the same order as the variables of `to_matrix`.
+    pub fn constant_column(&self) -> Vec<Bit> {
+        Position::iter_row_major(self.width(), self.height())
+            .filter_map(|pos| self.grid[pos])
+            .map(|c| c.starting_value + c.target_value)
+            // The constant row is equal to the current state, plus the target state.
+            .collect()
+    }
+
+    /// Produces a board with the holes and modifiers of `shape`, and the starting and target values of `self`.
+    /// Returns `None` if `self` and `shape` differ in size or in the placement of holes.
+    pub fn with_shape_of(&self, shape: &BoardDescription) -> Option<BoardDescription> {
+        if self.width() != shape.width() || self.height() != shape.height() {
+            return None;
+        }
+        let mut board = shape.clone();
+        for pos in Position::iter_row_major(self.width(), self.height()) {
+            match (&self.grid[pos], &mut board.grid[pos]) {
+                (Some(cell), Some(shaped)) => {
+                    shaped.starting_value = cell.starting_value;
+                    shaped.target_value = cell.target_value;
+                }
+                (None, None) => {}
+                _ => return None,
+            }
+        }
+        Some(board)
+    }
+
+    /// Checks whether `self` and `other` have the same holes and modifiers in the same places, regardless of starting values.
+    /// Boards with the same shape share the same adjacency matrix.
+    pub fn same_shape(&self, other: &BoardDescription) -> bool {
+        self.width() == other.width()
+            && self.height() == other.height()
+            && Position::iter_row_major(self.width(), self.height()).all(|pos| {
+                match (&self.grid[pos], &other.grid[pos]) {
+                    (Some(a), Some(b)) => a.same_modifiers(b),
+                    (None, None) => true,
+                    _ => false,
+                }
+            })
+    }
+
+    /// Simulates pressing the button at `pos`, toggling the cell itself and every neighbor it affects, as far as the rigid semantics
+    /// of `self` allow. Pressing a hole, or a position out of bounds, does nothing.
+    pub fn press(&mut self, pos: Position) {
+        let Some(Some(cell)) = self.grid.get(pos).copied() else {
+            return;
+        };
+        if self.toggles(&cell, &cell, None) {
+            self.grid[pos] = Some(Cell {
+                starting_value: cell.starting_value + Bit::On,
+                ..cell
+            });
+        }
+        for dir in [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ] {
+            if let Some(adjacent_pos) = pos.step_in_bounds(&self.grid, dir) {
+                let rigid = self.rigid;
+                if let Some(adjacent_cell) = &mut self.grid[adjacent_pos] {
+                    if toggles(rigid, &cell, adjacent_cell, Some(dir)) {
+                        adjacent_cell.starting_value += Bit::On;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Produces the board that results from pressing every button marked `On` in `assignment`, starting from the state of `self`.
+    pub fn apply_assignment(&self, assignment: &BoardAssignment) -> BoardDescription {
+        let mut board = self.clone();
+        for pos in assignment.presses() {
+            board.press(pos);
+        }
+        board
+    }
+
+    /// Checks whether every cell of `self` has reached its target value.
+    pub fn is_solved(&self) -> bool {
+        self.grid
+            .count(|oc| oc.is_some_and(|c| c.starting_value != c.target_value))
+            == 0
+    }
+
+    /// Checks whether the target of every cell of `self` is `On`, the usual goal of the puzzle. Board files can only express this target.
+    pub fn has_default_target(&self) -> bool {
+        self.has_uniform_target(Bit::On)
+    }
+
+    /// Checks whether the target of every cell of `self` is `value`.
+    pub fn has_uniform_target(&self, value: Bit) -> bool {
+        self.grid
+            .count(|oc| oc.is_some_and(|c| c.target_value != value))
+            == 0
+    }
+
+    /// Sets the target of every cell of `self` to `value`. Games where every light must end up off use `Bit::Off`.
+    pub fn set_uniform_target(&mut self, value: Bit) {
+        self.grid = self.grid.map(|oc| {
+            oc.map(|cell| Cell {
+                target_value: value,
+                ..cell
+            })
+        });
+    }
+
+    /// Renders `self` in the board file format read by `parse_board_file`. The modifier grid is only included if any cell has modifiers.
+    /// Board files cannot express targets, so the result always has the default target, as checked by `has_default_target`.
+    /// As the format holds a single modifier per cell, a cell with several modifiers only keeps the first of them.
+    /// Rigid semantics other than the default are written as a `# rigid: <name>` metadata line.
+    pub fn to_board_text(&self) -> String {
+        let mut board_text = String::new();
+        if self.rigid != RigidSemantics::default() {
+            board_text.push_str(&format!("# rigid: {}\n", self.rigid.name()));
+        }
+        let mut modifier_text = String::new();
+        for row in self.grid.rows_iter() {
+            for col in self.grid.cols_iter() {
+                match &self.grid[Position::new(row, col)] {
+                    Some(cell) => {
+                        board_text.push_str(&cell.starting_value.to_string());
+                        modifier_text.push(
+                            cell.modifiers()
+                                .chars()
+                                .find(|c| MODIFIERS.contains(c))
+                                .unwrap_or(' '),
+                        );
+                    }
+                    None => {
+                        board_text.push(' ');
+                        modifier_text.push(' ');
+                    }
+                }
+            }
+            board_text.push('\n');
+            modifier_text.push('\n');
+        }
+        if modifier_text.chars().any(|c| MODIFIERS.contains(&c)) {
+            board_text.push('\n');
+            board_text.push_str(&modifier_text);
+        }
+        board_text
+    }
+
     /// Counts the total number of `On` bits in the entire matrix.
     pub fn count_ones(&self) -> usize {
         self.grid
             .count(|oc| oc.is_some_and(|c| c.starting_value == Bit::On))
     }
 
+    /// Counts the cells that are not holes, which are the variables of the system of `self`.
+    pub fn cell_count(&self) -> usize {
+        self.grid.count(Option::is_some)
+    }
+
     /// Produces a `BoardAssignment` based on the shape of `self`, the total assignment in `assignment`,
     /// and the location of indices represented by `indexed_locations`.
     pub fn assign_assignment(
@@ -248,6 +679,183 @@ impl BoardDescription {
         BoardAssignment { grid: new_grid }
     }
 
+    /// Applies the modifier grid in `modifier_text` to `self`, one line per row. Spaces leave a cell unmodified.
+    ///
+    /// Fails if the grid contains a character other than a space or one of `MODIFIERS`, or places a modifier on an empty cell.
+    pub fn apply_modifier_text(&mut self, modifier_text: &str) -> Result<(), String> {
+        for (row, line) in (0..self.height()).zip(modifier_text.lines()) {
+            for (col, ch) in (0..self.width()).zip(line.chars()) {
+                if ch == ' ' {
+                    continue;
+                }
+                match &mut self.grid[Position::new(row, col)] {
+                    Some(cell) => {
+                        if !cell.apply_modifier(ch) {
+                            return Err(format!(
+                                "Unknown modifier '{}' at row {}, column {}.",
+                                ch,
+                                coords::row_number(row, self.height()),
+                                coords::col_number(col)
+                            ));
+                        }
+                    }
+                    None => {
+                        return Err(format!(
+                            "Modifier applied to empty cell at row {}, column {}.",
+                            coords::row_number(row, self.height()),
+                            coords::col_number(col)
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the target value of every cell of `self` from the rows of `target`, which must leave the same holes as `self`.
+    pub fn apply_target(&mut self, target: &[String]) -> Result<(), String> {
+        if target.len() > self.height() {
+            return Err("Target has more rows than the board.".to_string());
+        }
+        for row in 0..self.height() {
+            let line: Vec<char> = target
+                .get(row)
+                .map_or(vec![], |line| line.chars().collect());
+            if line.len() > self.width() {
+                return Err(format!(
+                    "Target row {} is longer than the board.",
+                    coords::row_number(row, self.height())
+                ));
+            }
+            for col in 0..self.width() {
+                let pos = Position::new(row, col);
+                let value = match line.get(col).copied().unwrap_or(' ') {
+                    '0' => Some(Bit::Off),
+                    '1' => Some(Bit::On),
+                    ' ' => None,
+                    c => return Err(format!("Invalid character '{}' in target.", c)),
+                };
+                match (&mut self.grid[pos], value) {
+                    (Some(cell), Some(value)) => cell.target_value = value,
+                    (None, None) => {}
+                    _ => {
+                        return Err(format!(
+                            "Target does not match the holes of the board at row {}, column {}.",
+                            coords::row_number(row, self.height()),
+                            coords::col_number(col)
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a region of `self` from the rows of `region`, where a `1` marks a cell in the region, and a `0` or a space one
+    /// outside of it. Returns the cells of the region in reading order. Fails if the region marks a hole.
+    pub fn parse_region(&self, region: &[String]) -> Result<Vec<Position>, String> {
+        if region.len() > self.height() {
+            return Err("Region has more rows than the board.".to_string());
+        }
+        let mut cells = vec![];
+        for (row, line) in region.iter().enumerate() {
+            if line.chars().count() > self.width() {
+                return Err(format!(
+                    "Region row {} is longer than the board.",
+                    coords::row_number(row, self.height())
+                ));
+            }
+            for (col, c) in line.chars().enumerate() {
+                let pos = Position::new(row, col);
+                match c {
+                    '0' | ' ' => {}
+                    '1' if self.grid[pos].is_some() => cells.push(pos),
+                    '1' => {
+                        return Err(format!(
+                            "Region marks the hole at row {}, column {}.",
+                            coords::row_number(row, self.height()),
+                            coords::col_number(col)
+                        ))
+                    }
+                    c => return Err(format!("Invalid character '{}' in region.", c)),
+                }
+            }
+        }
+        Ok(cells)
+    }
+
+    /// Reads the cost of pressing each cell of `self` from the rows of `costs`, a digit per cell with spaces over the holes. Returns
+    /// the costs in reading order, which is the order of the variables of `to_matrix`. Fails if a cell has no digit.
+    pub fn parse_costs(&self, costs: &[String]) -> Result<Vec<u32>, String> {
+        if costs.len() > self.height() {
+            return Err("Costs have more rows than the board.".to_string());
+        }
+        let mut parsed = vec![];
+        for row in 0..self.height() {
+            let line: Vec<char> = costs.get(row).map_or(vec![], |line| line.chars().collect());
+            if line.len() > self.width() {
+                return Err(format!(
+                    "Cost row {} is longer than the board.",
+                    coords::row_number(row, self.height())
+                ));
+            }
+            for col in 0..self.width() {
+                let c = line.get(col).copied().unwrap_or(' ');
+                match (&self.grid[Position::new(row, col)], c.to_digit(10)) {
+                    (Some(_), Some(cost)) => parsed.push(cost),
+                    (None, None) if c == ' ' => {}
+                    (Some(_), None) if c == ' ' => {
+                        return Err(format!(
+                            "Missing cost for the cell at row {}, column {}.",
+                            coords::row_number(row, self.height()),
+                            coords::col_number(col)
+                        ))
+                    }
+                    (None, Some(_)) => {
+                        return Err(format!(
+                            "Cost given for the hole at row {}, column {}.",
+                            coords::row_number(row, self.height()),
+                            coords::col_number(col)
+                        ))
+                    }
+                    _ => return Err(format!("Invalid character '{}' in costs.", c)),
+                }
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// What being rigid means for the cells of `self`.
+    pub fn rigid_semantics(&self) -> RigidSemantics {
+        self.rigid
+    }
+
+    /// Sets what being rigid means for the cells of `self`.
+    pub fn set_rigid_semantics(&mut self, rigid: RigidSemantics) {
+        self.rigid = rigid;
+    }
+
+    /// Whether `cell` can be pressed at all under the rigid semantics of `self`. Only rigid cells under `Unpressable` cannot.
+    fn pressable(&self, cell: &Cell) -> bool {
+        !(cell.is_rigid && self.rigid == RigidSemantics::Unpressable)
+    }
+
+    /// Whether there is a cell at `pos` that can be pressed. Holes, positions out of bounds, and rigid cells under
+    /// `RigidSemantics::Unpressable` cannot.
+    pub fn is_pressable(&self, pos: Position) -> bool {
+        matches!(self.grid.get(pos), Some(Some(cell)) if self.pressable(cell))
+    }
+
+    /// Whether pressing `pressed` toggles `affected` under the rigid semantics of `self`. See `toggles`.
+    fn toggles(&self, pressed: &Cell, affected: &Cell, dir: Option<Direction>) -> bool {
+        toggles(self.rigid, pressed, affected, dir)
+    }
+
+    /// Returns the cell at `pos`, or `None` if `pos` is out of bounds of `self`. An in-bounds hole is returned as `Some(None)`.
+    pub fn get(&self, pos: Position) -> Option<&Option<Cell>> {
+        self.grid.get(pos)
+    }
+
     pub fn height(&self) -> usize {
         self.grid.get_height()
     }
@@ -257,7 +865,25 @@ impl BoardDescription {
     }
 }
 
-pub fn parse_bit(input: &str) -> IResult<&str, Option<Bit>> {
+/// Whether pressing `pressed` toggles `affected` when rigid cells follow `rigid`. `dir` is the direction from `pressed` to
+/// `affected` if they are adjacent, and `None` if they are the same cell.
+fn toggles(rigid: RigidSemantics, pressed: &Cell, affected: &Cell, dir: Option<Direction>) -> bool {
+    match dir {
+        None => {
+            !(pressed.is_rigid
+                && matches!(rigid, RigidSemantics::Frozen | RigidSemantics::Unpressable))
+        }
+        Some(dir) => {
+            pressed[dir]
+                && !(affected.is_rigid
+                    && matches!(rigid, RigidSemantics::Shielded | RigidSemantics::Frozen))
+                && !(pressed.is_rigid
+                    && matches!(rigid, RigidSemantics::Unpressable | RigidSemantics::Inert))
+        }
+    }
+}
+
+pub(crate) fn parse_bit(input: &str) -> IResult<&str, Option<Bit>> {
     let (input, next) = one_of("01 ")(input)?;
     Ok((
         input,
@@ -270,26 +896,173 @@ pub fn parse_bit(input: &str) -> IResult<&str, Option<Bit>> {
     ))
 }
 
-pub fn parse_basic_cell(input: &str) -> IResult<&str, Option<Cell>> {
+pub(crate) fn parse_basic_cell(input: &str) -> IResult<&str, Option<Cell>> {
     let (input, ob) = parse_bit(input)?;
-    Ok((input, ob.map(|b| Cell::new_basic(b))))
+    Ok((input, ob.map(Cell::new_basic)))
 }
 
-pub fn parse_bit_line(input: &str) -> IResult<&str, Vec<Option<Bit>>> {
-    let (input, bits) = many1(parse_bit)(input)?;
-    Ok((input, bits))
-}
-
-pub fn parse_basic_cell_line(input: &str) -> IResult<&str, Vec<Option<Cell>>> {
+pub(crate) fn parse_basic_cell_line(input: &str) -> IResult<&str, Vec<Option<Cell>>> {
     let (input, cells) = many1(parse_basic_cell)(input)?;
     Ok((input, cells))
 }
 
-pub fn parse_board(input: &str) -> IResult<&str, BoardDescription> {
+/// Parses the rows of a board, without a modifier grid. Accepts a board of only holes, so that callers can reject it with a clearer
+/// message. Its result type comes from `nom`, so it stays inside the crate, and `parse_board_file` or `str::parse` are the way in
+/// from outside.
+pub(crate) fn parse_board_rows(input: &str) -> IResult<&str, BoardDescription> {
+    let _span = trace::span("parse");
     let (input, (lines, _)) = tuple((
         separated_list1(newline, parse_basic_cell_line),
         alt((map(eof, |_| ()), map(tuple((newline, eof)), |_| ()))),
     ))(input)?;
     let grid = Grid::new_partial_lines(lines, None).unwrap(); // unwrap is safe due to the parsers' guarantee of nonemptiness.
-    Ok((input, BoardDescription { grid }))
+    Ok((
+        input,
+        BoardDescription {
+            grid,
+            rigid: RigidSemantics::default(),
+        },
+    ))
+}
+
+/// Parses the contents of a board file. A board file contains a board, optionally followed by an empty line and a
+/// modifier grid of spaces, `H`s, `V`s and `O`s. Lines starting with `#` before the board hold metadata, and are skipped, except
+/// for `# rigid: <name>`, which sets the rigid semantics of the board. Fails if the board is all holes.
+pub fn parse_board_file(input: &str) -> Result<BoardDescription, String> {
+    let input = input.replace("\r\n", "\n");
+    let mut rigid = RigidSemantics::default();
+    for line in input.lines().take_while(|line| line.starts_with('#')) {
+        if let Some(("rigid", name)) = line[1..]
+            .split_once(':')
+            .map(|(key, value)| (key.trim(), value.trim()))
+        {
+            rigid = RigidSemantics::from_name(name)
+                .ok_or_else(|| format!("Unknown rigid semantics \"{}\".", name))?;
+        }
+    }
+    let input = strip_metadata(&input);
+    let (board_text, modifier_text) = match input.split_once("\n\n") {
+        Some((board_text, modifier_text)) => (board_text, Some(modifier_text)),
+        None => (input, None),
+    };
+
+    let mut board = match parse_board_rows(board_text) {
+        Ok((_, board)) => board,
+        Err(e) => return Err(format!("Could not parse board: {}", e)),
+    };
+
+    if board.cell_count() == 0 {
+        return Err("Board has no pressable cells.".to_string());
+    }
+    if let Some(modifier_text) = modifier_text {
+        board.apply_modifier_text(modifier_text)?;
+    }
+    board.rigid = rigid;
+
+    Ok(board)
+}
+
+/// Skips the metadata lines at the start of a board file.
+pub fn strip_metadata(input: &str) -> &str {
+    let mut rest = input;
+    while rest.starts_with('#') {
+        rest = rest.split_once('\n').map_or("", |(_, rest)| rest);
+    }
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Report;
+
+    /// Parses a one-row board whose middle cell is rigid under `rigid`.
+    fn strip(cells: &str, rigid: &str) -> BoardDescription {
+        parse_board_file(&format!("# rigid: {}\n{}\n\n O ", rigid, cells)).unwrap()
+    }
+
+    /// The matrix of `board`, and every solution of it as a string of presses in reading order.
+    fn solve(board: &BoardDescription) -> (String, Vec<String>) {
+        let (matrix, _) = board.to_matrix();
+        let eqns = Report::new(&matrix).equations;
+        let solutions = eqns
+            .results()
+            .map(|solution| solution.as_bitstring().unwrap())
+            .collect();
+        (matrix.to_string(), solutions)
+    }
+
+    #[test]
+    fn shielded_cell_ignores_its_neighbors() {
+        let (matrix, solutions) = solve(&strip("000", "shielded"));
+        assert_eq!(matrix, "110 | 1\n010 | 1\n011 | 1\n");
+        assert_eq!(solutions, ["010"]);
+    }
+
+    #[test]
+    fn frozen_cell_never_toggles() {
+        let (matrix, solutions) = solve(&strip("010", "frozen"));
+        assert_eq!(matrix, "110 | 1\n000 | 0\n011 | 1\n");
+        assert_eq!(solutions, ["010", "101"]);
+        assert!(solve(&strip("000", "frozen")).1.is_empty());
+    }
+
+    #[test]
+    fn unpressable_cell_is_never_pressed() {
+        let board = strip("010", "unpressable");
+        let (matrix, solutions) = solve(&board);
+        assert_eq!(matrix, "100 | 1\n101 | 0\n001 | 1\n010 | 0\n");
+        assert_eq!(solutions, ["101"]);
+
+        let mut pressed = board.clone();
+        pressed.press(Position::new(0, 1));
+        assert_eq!(pressed.to_string(), board.to_string());
+    }
+
+    #[test]
+    fn inert_cell_only_toggles_itself() {
+        let (matrix, solutions) = solve(&strip("000", "inert"));
+        assert_eq!(matrix, "100 | 1\n111 | 1\n001 | 1\n");
+        assert_eq!(solutions, ["111"]);
+    }
+
+    /// The first puzzle of the original 5x5 Lights Out, where every light must be switched, with the centre cell rigid under
+    /// `rigid` and starting at `centre`.
+    fn classic(rigid: &str, centre: char) -> BoardDescription {
+        let cells = format!("00000\n00000\n00{}00\n00000\n00000", centre);
+        let modifiers = "     \n     \n  O  \n     \n     ";
+        parse_board_file(&format!("# rigid: {}\n{}\n\n{}", rigid, cells, modifiers)).unwrap()
+    }
+
+    /// Counts the solutions of `board`, and the fewest presses among them. Checks every solution by pressing its cells with
+    /// `press`, which shares no code with the matrix.
+    fn tally(board: &BoardDescription) -> (usize, Option<usize>) {
+        let (matrix, indexed_locations) = board.to_matrix();
+        let solutions: Vec<BoardAssignment> = Report::new(&matrix)
+            .equations
+            .results()
+            .map(|solution| board.assign_assignment(solution, &indexed_locations))
+            .collect();
+        for solution in &solutions {
+            assert!(board.apply_assignment(solution).is_solved());
+        }
+        let fewest = solutions.iter().map(BoardAssignment::count_ones).min();
+        (solutions.len(), fewest)
+    }
+
+    #[test]
+    fn classic_lights_out_takes_fifteen_presses() {
+        let board = parse_board_file(&["00000"; 5].join("\n")).unwrap();
+        assert_eq!(tally(&board), (4, Some(15)));
+    }
+
+    #[test]
+    fn classic_lights_out_under_each_semantics() {
+        assert_eq!(tally(&classic("shielded", '0')), (4, Some(15)));
+        assert_eq!(tally(&classic("frozen", '1')), (8, Some(12)));
+        assert_eq!(tally(&classic("frozen", '0')), (0, None));
+        assert_eq!(tally(&classic("unpressable", '1')), (4, Some(12)));
+        assert_eq!(tally(&classic("unpressable", '0')), (0, None));
+        assert_eq!(tally(&classic("inert", '0')), (4, Some(13)));
+    }
 }
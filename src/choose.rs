@@ -0,0 +1,153 @@
+use std::{collections::HashMap, io::BufRead};
+
+use wayout_solver::{
+    bit::Bit, board::BoardDescription, equations::Assignment, grid::Position, i18n::say,
+    report::Report,
+};
+
+/// The label of the choice cell at `index`: the letters `a` to `z`, then `A` to `Z`, then `*` for all the rest, which can only be
+/// picked by number.
+pub fn choice_label(index: usize) -> char {
+    match index {
+        0..26 => (b'a' + index as u8) as char,
+        26..52 => (b'A' + (index - 26) as u8) as char,
+        _ => '*',
+    }
+}
+
+/// Renders `board` with every choice cell in `choices` replaced by its label, every other cell by `.`, and holes left blank.
+pub fn choice_map(board: &BoardDescription, choices: &[Position]) -> String {
+    let mut lines: Vec<Vec<char>> = (0..board.height())
+        .map(|row| {
+            (0..board.width())
+                .map(|col| match board[Position::new(row, col)] {
+                    Some(_) => '.',
+                    None => ' ',
+                })
+                .collect()
+        })
+        .collect();
+    for (index, pos) in choices.iter().enumerate() {
+        lines[pos.row][pos.col] = choice_label(index);
+    }
+    lines
+        .into_iter()
+        .map(|line| line.into_iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Lets the user browse the solutions of `board` one free variable at a time. Every free variable is a choice cell, whose value
+/// the user picks, and every change shows the solution the choices lead to. Runs until `quit` or the end of the input.
+pub fn run(
+    board: &BoardDescription,
+    report: &Report,
+    indexed_locations: &HashMap<usize, Position>,
+) {
+    let eqns = &report.equations;
+    if !eqns.is_consistent() {
+        say!("Board has no solutions.");
+        return;
+    }
+    let free_vars: Vec<usize> = eqns.free_vars().collect();
+    let choices: Vec<Position> = free_vars.iter().map(|var| indexed_locations[var]).collect();
+    let mut values = vec![Bit::Off; free_vars.len()];
+    let find_choice = |word: &str| {
+        let mut chars = word.chars();
+        match (chars.next(), chars.next()) {
+            (Some(label), None) if label.is_ascii_alphabetic() => {
+                (0..choices.len()).find(|&i| choice_label(i) == label)
+            }
+            _ => word
+                .parse::<usize>()
+                .ok()
+                .filter(|&number| (1..=choices.len()).contains(&number))
+                .map(|number| number - 1),
+        }
+    };
+    let preview = |values: &[Bit]| {
+        let valuation = free_vars
+            .iter()
+            .copied()
+            .zip(values.iter().copied())
+            .collect();
+        let solution =
+            board.assign_assignment(eqns.backfeed(Assignment(valuation)), indexed_locations);
+        let picked: Vec<String> = (0..choices.len())
+            .map(|i| format!("{}={}", choice_label(i), values[i]))
+            .collect();
+        if !picked.is_empty() {
+            println!("{}", picked.join(" "));
+        }
+        say!(
+            "Solution with {} button presses:\n{}",
+            solution.count_ones(),
+            solution
+        );
+    };
+
+    match choices.len() {
+        0 => say!("Board has a single solution, so there is nothing to choose."),
+        count => {
+            say!(
+                "Board has {} choice cells, marked with letters. Every other cell follows from them.",
+                count
+            );
+            print!("{}", choice_map(board, &choices));
+            say!("Type \"help\" for the commands, or \"quit\" to leave.");
+        }
+    }
+    preview(&values);
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => continue,
+            ["quit"] => return,
+            ["help"] => {
+                say!("flip <choice>: presses a choice cell if it is not pressed, and the other way around.");
+                say!("set <choice> <0|1>: picks whether to press a choice cell.");
+                say!("reset: presses no choice cell.");
+                say!("map: shows where the choice cells are.");
+                say!("quit: leaves.");
+                say!("Choice cells are named by their letter, or by their number counting from 1.");
+                continue;
+            }
+            ["map"] => {
+                print!("{}", choice_map(board, &choices));
+                continue;
+            }
+            ["reset"] => values.fill(Bit::Off),
+            ["flip", choice] => match find_choice(choice) {
+                Some(i) => values[i] += Bit::On,
+                None => {
+                    say!("There is no choice cell {}.", choice);
+                    continue;
+                }
+            },
+            ["set", choice, value] => {
+                let value = match *value {
+                    "0" => Bit::Off,
+                    "1" => Bit::On,
+                    _ => {
+                        say!("Values are 0 or 1.");
+                        continue;
+                    }
+                };
+                match find_choice(choice) {
+                    Some(i) => values[i] = value,
+                    None => {
+                        say!("There is no choice cell {}.", choice);
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                say!("Unknown command. Type \"help\" for the commands.");
+                continue;
+            }
+        }
+        preview(&values);
+    }
+}
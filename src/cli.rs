@@ -0,0 +1,318 @@
+use wayout_solver::{
+    coords::{self, Coordinates},
+    i18n::tr,
+    matrix::PivotStrategy,
+    memory,
+    solver::{Backend, Strategy},
+};
+
+use crate::spoiler::Spoiler;
+
+/// Options for the interactive solver, collected from command line flags.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// Path to a board file to solve, instead of entering the board at the prompts. Read in the TOML format if it ends in `.toml`.
+    pub input: Option<String>,
+    /// Text of a board to solve, instead of entering the board at the prompts.
+    pub board: Option<String>,
+    /// Path to write the board that results from applying the chosen solution.
+    pub final_board: Option<String>,
+    /// Path to write a standalone HTML report of the whole solving process.
+    pub report: Option<String>,
+    /// Path to write the solutions shown to, one per row, as CSV, or as TSV if the path ends in `.tsv`.
+    pub solutions_csv: Option<String>,
+    /// Show only the solution with this number, counting from 1 in enumeration order.
+    pub solution: Option<u128>,
+    /// Show this many uniformly random solutions.
+    pub random: Option<usize>,
+    /// Show this many solutions that differ from each other in as many presses as possible.
+    pub diverse: Option<usize>,
+    /// Seed for the random number generator. Seeded from the current time if absent.
+    pub seed: Option<u64>,
+    /// Show a reduced basis of the quiet patterns of the board.
+    pub kernel: bool,
+    /// Name of a saved shape template supplying the holes and modifiers of the board, so only the start state has to be entered.
+    pub template: Option<String>,
+    /// Trim the rows and columns of holes off the edges of the board before solving it.
+    pub crop: bool,
+    /// Name of a saved target supplying the state every cell must end up in, instead of asking whether it is all on or all off.
+    pub target_name: Option<String>,
+    /// Describe boards and solutions in words instead of symbol grids, for use with screen readers.
+    pub accessible: bool,
+    /// How to pick pivot columns during elimination, which decides the free variables of the board.
+    pub pivot: PivotStrategy,
+    /// The most memory to use for matrices and stored solutions, in bytes. Falls back to `memory::budget` if absent.
+    pub memory_budget: Option<usize>,
+    /// How to search for the solution with the fewest presses.
+    pub strategy: Strategy,
+    /// Label the columns of the printed matrices with the cells they stand for.
+    pub label_columns: bool,
+    /// Show the particular solution, with every free variable `Off`, instead of asking for all solutions or one with the
+    /// fewest presses.
+    pub any: bool,
+    /// In "min" mode, show every solution with the fewest presses instead of one.
+    pub all_minimums: bool,
+    /// The solver to find the solution with the fewest presses with.
+    pub backend: Backend,
+    /// Path to a file with the cost of pressing each cell, a digit per cell aligned with the board. In "min" mode, the solution with
+    /// the lowest total cost is shown instead of the one with the fewest presses.
+    pub costs: Option<String>,
+    /// Show every solution without asking, like answering "all".
+    pub all: bool,
+    /// Whether to print the usual text, or a single JSON document following `schema::RESULT_SCHEMA`.
+    pub output: OutputFormat,
+    /// How to print solutions, unless the accessible output mode is enabled.
+    pub format: SolutionFormat,
+    /// How to hide solutions. Either style collapses them in the HTML report, and `Rot13` also prints them as an encoded list of
+    /// presses.
+    pub spoiler: Option<Spoiler>,
+    /// Query the solved system interactively once the solutions are shown.
+    pub explore: bool,
+}
+
+/// The ways solutions can be printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolutionFormat {
+    /// The grid of presses after a header with the size of the board and the number of presses.
+    #[default]
+    Grid,
+    /// Only the number of times to press each cell, as a grid of digits.
+    Presses,
+}
+
+impl SolutionFormat {
+    /// Finds the format for a name, `grid` or `presses`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grid" => Some(SolutionFormat::Grid),
+            "presses" => Some(SolutionFormat::Presses),
+            _ => None,
+        }
+    }
+}
+
+/// The ways the result of solving a board can be printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The matrices, the solutions and the messages in between, as text.
+    #[default]
+    Text,
+    /// A single JSON document following `schema::RESULT_SCHEMA`, and nothing else. Needs a board from `--input` or `--board`, as
+    /// nothing is asked.
+    Json,
+}
+
+impl OutputFormat {
+    /// Finds the output format for a name, `text` or `json`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl Options {
+    /// Parses the flags in `args`. Fails on unknown flags, or flags missing their value.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => options.input = Some(next_value(&mut args, arg)?),
+                "--board" => options.board = Some(next_value(&mut args, arg)?),
+                "--final-board" => options.final_board = Some(next_value(&mut args, arg)?),
+                "--report" => options.report = Some(next_value(&mut args, arg)?),
+                "--solutions-csv" => options.solutions_csv = Some(next_value(&mut args, arg)?),
+                "--solution" => {
+                    let value = next_value(&mut args, arg)?;
+                    match value.parse::<u128>() {
+                        Ok(n) if n > 0 => options.solution = Some(n),
+                        _ => return Err(tr!("Invalid solution number \"{}\".", value)),
+                    }
+                }
+                "--kernel" => options.kernel = true,
+                "--explore" => options.explore = true,
+                "--all-minimums" => options.all_minimums = true,
+                "--any" => options.any = true,
+                "--all" => options.all = true,
+                "--crop" => options.crop = true,
+                "--costs" => options.costs = Some(next_value(&mut args, arg)?),
+                "--label-columns" => options.label_columns = true,
+                "--accessible" => options.accessible = true,
+                "--template" => options.template = Some(next_value(&mut args, arg)?),
+                "--target-name" => options.target_name = Some(next_value(&mut args, arg)?),
+                "--random" => options.random = Some(parse_value(&mut args, arg)?),
+                "--diverse" => options.diverse = Some(parse_value(&mut args, arg)?),
+                "--seed" => options.seed = Some(parse_value(&mut args, arg)?),
+                "--memory-budget" => {
+                    let value = next_value(&mut args, arg)?;
+                    options.memory_budget = Some(
+                        memory::parse_size(&value)
+                            .ok_or_else(|| tr!("Invalid memory budget \"{}\".", value))?,
+                    );
+                }
+                "--strategy" => {
+                    let value = next_value(&mut args, arg)?;
+                    options.strategy = Strategy::from_name(&value).ok_or_else(|| {
+                        tr!(
+                            "Unknown strategy \"{}\". Available strategies: auto, enumerate, coset, greedy, search.",
+                            value
+                        )
+                    })?;
+                }
+                "--backend" => {
+                    let value = next_value(&mut args, arg)?;
+                    options.backend = Backend::from_name(&value).ok_or_else(|| {
+                        tr!(
+                            "Unknown backend \"{}\". Available backends: gaussian, brute-force.",
+                            value
+                        )
+                    })?;
+                }
+                "--pivot" => {
+                    let value = next_value(&mut args, arg)?;
+                    options.pivot = PivotStrategy::from_name(&value).ok_or_else(|| {
+                        tr!(
+                            "Unknown pivot strategy \"{}\". Available strategies: first, sparsest, heaviest.",
+                            value
+                        )
+                    })?;
+                }
+                "--format" => {
+                    let value = next_value(&mut args, arg)?;
+                    options.format = SolutionFormat::from_name(&value).ok_or_else(|| {
+                        tr!(
+                            "Unknown solution format \"{}\". Available formats: grid, presses.",
+                            value
+                        )
+                    })?;
+                }
+                "--output" => {
+                    let value = next_value(&mut args, arg)?;
+                    options.output = OutputFormat::from_name(&value).ok_or_else(|| {
+                        tr!(
+                            "Unknown output format \"{}\". Available formats: text, json.",
+                            value
+                        )
+                    })?;
+                }
+                "--spoiler" => {
+                    let value = next_value(&mut args, arg)?;
+                    options.spoiler = Some(parse_spoiler(&value)?);
+                }
+                _ => return Err(tr!("Unknown option \"{}\".", arg)),
+            }
+        }
+        if options.all && options.any {
+            return Err(tr!(
+                "Options \"--all\" and \"--any\" cannot be used together."
+            ));
+        }
+        if options.input.is_some() && options.board.is_some() {
+            return Err(tr!(
+                "Options \"--input\" and \"--board\" cannot be used together."
+            ));
+        }
+        // The board prompts would end up in the JSON document.
+        if options.output == OutputFormat::Json
+            && options.input.is_none()
+            && options.board.is_none()
+        {
+            return Err(tr!(
+                "Option \"--output json\" needs a board from \"--input\" or \"--board\"."
+            ));
+        }
+        Ok(options)
+    }
+}
+
+/// Parses the spoiler style named `name`.
+pub fn parse_spoiler(name: &str) -> Result<Spoiler, String> {
+    Spoiler::from_name(name).ok_or_else(|| {
+        tr!(
+            "Unknown spoiler style \"{}\". Available styles: rot13, block.",
+            name
+        )
+    })
+}
+
+/// Takes the value following the flag `flag`.
+fn next_value<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<String, String> {
+    match args.next() {
+        Some(value) => Ok(value.clone()),
+        None => Err(tr!("Option \"{}\" requires a value.", flag)),
+    }
+}
+
+/// Takes the value following the flag `flag`, and parses it.
+fn parse_value<'a, T: std::str::FromStr>(
+    args: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> Result<T, String> {
+    let value = next_value(args, flag)?;
+    value
+        .parse()
+        .map_err(|_| tr!("Invalid value \"{}\" for option \"{}\".", value, flag))
+}
+
+/// Removes every occurrence of the flag `flag` from `args`, returning whether there was any.
+pub fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != len
+}
+
+/// Removes the flag `flag` and the value following it from `args`, returning the value if the flag was present.
+pub fn take_option(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(tr!("Option \"{}\" requires a value.", flag));
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value))
+}
+
+/// Removes the flag `flag` and the number following it from `args`, returning the number if the flag was present.
+pub fn take_number<T: std::str::FromStr>(
+    args: &mut Vec<String>,
+    flag: &str,
+) -> Result<Option<T>, String> {
+    take_option(args, flag)?
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| tr!("Invalid value \"{}\" for option \"{}\".", value, flag))
+        })
+        .transpose()
+}
+
+/// Removes the `--index` and `--origin` flags and their values from `args`, returning the numbering they choose on top of
+/// `Coordinates::from_env`.
+pub fn take_coordinates(args: &mut Vec<String>) -> Result<Coordinates, String> {
+    let mut coordinates = Coordinates::from_env();
+    if let Some(value) = take_option(args, "--index")? {
+        coordinates.base = coords::parse_base(&value).ok_or_else(|| {
+            tr!(
+                "Invalid index \"{}\". Rows and columns count from 0 or 1.",
+                value
+            )
+        })?;
+    }
+    if let Some(value) = take_option(args, "--origin")? {
+        coordinates.from_bottom = coords::parse_origin(&value).ok_or_else(|| {
+            tr!(
+                "Invalid origin \"{}\". Rows count from the top or the bottom.",
+                value
+            )
+        })?;
+    }
+    Ok(coordinates)
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+/// Finds the directory where the solver keeps user data, such as saved templates.
+///
+/// Uses `$WAYOUT_SOLVER_HOME` if set, then `$XDG_CONFIG_HOME/wayout-solver`, then `$HOME/.config/wayout-solver`.
+/// Returns `None` if none of these variables are set.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("WAYOUT_SOLVER_HOME") {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir).join("wayout-solver"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config").join("wayout-solver"))
+}
+
+/// Finds the subdirectory `name` of `config_dir`, creating it if needed.
+pub fn config_subdir(name: &str) -> Result<PathBuf, String> {
+    let Some(dir) = config_dir() else {
+        return Err(
+            "Could not find a configuration directory. Set WAYOUT_SOLVER_HOME to choose one."
+                .to_string(),
+        );
+    };
+    let dir = dir.join(name);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Could not create directory {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// Finds the path of the file called `name` with `extension` in the subdirectory `subdir` of `config_dir`, checking that the name
+/// is a plain file name. `kind` names what the file holds in the error.
+pub fn named_file(
+    kind: &str,
+    subdir: &str,
+    name: &str,
+    extension: &str,
+) -> Result<PathBuf, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid {} name \"{}\".", kind, name));
+    }
+    Ok(config_subdir(subdir)?.join(format!("{}.{}", name, extension)))
+}
+
+/// Lists the names of every file with `extension` in the subdirectory `subdir` of `config_dir`, in alphabetical order.
+pub fn list_named(subdir: &str, extension: &str) -> Result<Vec<String>, String> {
+    let dir = config_subdir(subdir)?;
+    let entries = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Could not read directory {}: {}", dir.display(), e))?;
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+        .filter_map(|path| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .collect();
+    names.sort();
+    Ok(names)
+}
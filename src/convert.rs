@@ -0,0 +1,331 @@
+use crate::{
+    bit::Bit,
+    board::{self, BoardAssignment, BoardDescription, RigidSemantics, MODIFIERS},
+    grid::Position,
+    json::{self, Value},
+    level::Level,
+    schema, sharecode,
+};
+
+#[cfg(feature = "toml")]
+use crate::toml_board;
+
+/// The error for the TOML format when the crate is built without the `toml` feature.
+#[cfg(not(feature = "toml"))]
+const NO_TOML: &str = "TOML board files are not supported by this build.";
+
+/// The formats boards can be converted between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardFormat {
+    /// Board files, with the par as metadata and an optional modifier grid. Cannot express targets.
+    Text,
+    /// TOML board files, as read by `toml_board::parse`. Only available with the `toml` feature.
+    Toml,
+    /// JSON documents following `schema::BOARD_SCHEMA`.
+    Json,
+    /// Share codes, as produced by `sharecode::encode`. Drop the par, which is computed again when reading them.
+    Code,
+}
+
+impl BoardFormat {
+    /// Finds the format for a name, `text`, `toml`, `json` or `code`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(BoardFormat::Text),
+            "toml" => Some(BoardFormat::Toml),
+            "json" => Some(BoardFormat::Json),
+            "code" => Some(BoardFormat::Code),
+            _ => None,
+        }
+    }
+
+    /// The extension of files in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            BoardFormat::Text => "txt",
+            BoardFormat::Toml => "toml",
+            BoardFormat::Json => "json",
+            BoardFormat::Code => "code",
+        }
+    }
+
+    /// Guesses the format of the file at `path` holding `text`, from its extension and then from its contents.
+    pub fn detect(path: &str, text: &str) -> Self {
+        let by_extension = path
+            .rsplit_once('.')
+            .and_then(|(_, extension)| match extension {
+                "toml" => Some(BoardFormat::Toml),
+                "json" => Some(BoardFormat::Json),
+                "code" => Some(BoardFormat::Code),
+                _ => None,
+            });
+        if let Some(format) = by_extension {
+            return format;
+        }
+        let text = text.trim();
+        if text.starts_with('{') {
+            BoardFormat::Json
+        } else if !text.contains('\n') && text.contains(':') && !text.starts_with('#') {
+            BoardFormat::Code
+        } else {
+            BoardFormat::Text
+        }
+    }
+}
+
+/// Parses a level written in `format`.
+pub fn parse(text: &str, format: BoardFormat) -> Result<Level, String> {
+    match format {
+        BoardFormat::Text => Level::parse(text),
+        #[cfg(feature = "toml")]
+        BoardFormat::Toml => toml_board::parse(text),
+        #[cfg(not(feature = "toml"))]
+        BoardFormat::Toml => Err(NO_TOML.to_string()),
+        BoardFormat::Json => parse_json(text),
+        BoardFormat::Code => sharecode::decode(text).map(Level::new),
+    }
+}
+
+/// Parses a file holding several levels: a JSON array of documents following `schema::BOARD_SCHEMA`, or board files separated by
+/// empty lines. An empty line also separates a board file from its modifier grid, so a block made only of modifiers and spaces is
+/// read as the modifier grid of the board before it. Returns the result of parsing each level, in order, and fails only if the
+/// file itself cannot be split into levels.
+pub fn parse_many(text: &str) -> Result<Vec<Result<Level, String>>, String> {
+    let text = text.replace("\r\n", "\n");
+    if text.trim_start().starts_with('[') {
+        let document = json::parse(&text)?;
+        let items = document.as_array().ok_or("Expected an array of boards.")?;
+        return Ok(items.iter().map(level_from_json).collect());
+    }
+    let mut blocks: Vec<String> = vec![];
+    for block in text
+        .split("\n\n")
+        .map(|block| block.trim_matches('\n'))
+        .filter(|block| !block.trim().is_empty())
+    {
+        let is_modifiers = block
+            .chars()
+            .all(|c| c == ' ' || c == '\n' || MODIFIERS.contains(&c));
+        match blocks.last_mut() {
+            Some(previous) if is_modifiers => {
+                previous.push_str("\n\n");
+                previous.push_str(block);
+            }
+            _ => blocks.push(block.to_string()),
+        }
+    }
+    Ok(blocks.iter().map(|block| Level::parse(block)).collect())
+}
+
+/// Renders `level` in `format`. Fails if the format cannot express the target of its board.
+pub fn render(level: &Level, format: BoardFormat) -> Result<String, String> {
+    let has_default_target = level.board.has_default_target();
+    match format {
+        BoardFormat::Text if has_default_target => Ok(level.to_text()),
+        #[cfg(feature = "toml")]
+        BoardFormat::Toml => Ok(toml_board::to_text(level)),
+        #[cfg(not(feature = "toml"))]
+        BoardFormat::Toml => Err(NO_TOML.to_string()),
+        BoardFormat::Json => Ok(format!("{}\n", to_json(level))),
+        BoardFormat::Code => Ok(format!("{}\n", sharecode::encode(&level.board))),
+        _ => Err("The chosen format cannot express this board.".to_string()),
+    }
+}
+
+/// Builds the JSON document of `level`. A target of every cell unlit is also recorded as the `off` target convention. The
+/// modifiers, target, rigid semantics and par are only included if they differ from the defaults.
+pub fn to_json(level: &Level) -> Value {
+    let board = &level.board;
+    let text = board.to_board_text();
+    let text = board::strip_metadata(&text);
+    let (rows, modifiers) = text.split_once("\n\n").unwrap_or((text, ""));
+    let lines = |text: &str| Value::Array(text.lines().map(Value::from).collect());
+
+    let mut members = vec![
+        (
+            "schema_version".to_string(),
+            Value::from(schema::SCHEMA_VERSION as usize),
+        ),
+        ("rows".to_string(), lines(rows)),
+    ];
+    if !modifiers.is_empty() {
+        members.push(("modifiers".to_string(), lines(modifiers)));
+    }
+    if !board.has_default_target() {
+        let target = (0..board.height())
+            .map(|row| {
+                let line: String = (0..board.width())
+                    .map(|col| match board[Position::new(row, col)] {
+                        Some(cell) if cell.target_value == Bit::On => '1',
+                        Some(_) => '0',
+                        None => ' ',
+                    })
+                    .collect();
+                Value::String(line)
+            })
+            .collect();
+        members.push(("target".to_string(), Value::Array(target)));
+        if board.has_uniform_target(Bit::Off) {
+            members.push(("target_convention".to_string(), Value::from("off")));
+        }
+    }
+    if board.rigid_semantics() != RigidSemantics::default() {
+        members.push((
+            "rigid".to_string(),
+            Value::from(board.rigid_semantics().name()),
+        ));
+    }
+    if let Some(par) = level.par {
+        members.push(("par".to_string(), Value::from(par)));
+    }
+    Value::Object(members)
+}
+
+/// Builds the JSON document of the result of solving `board`, following `schema::RESULT_SCHEMA`. `nullity` is the number of free
+/// variables, `solution_count` the number of solutions or `None` if it does not fit in a `u128`, and `solutions` the solutions to
+/// list. The board reached by pressing the first of them is included as `final_board`.
+pub fn result_to_json(
+    board: &BoardDescription,
+    rank: usize,
+    nullity: usize,
+    solution_count: Option<u128>,
+    solutions: &[BoardAssignment],
+) -> Value {
+    let final_board = solutions
+        .first()
+        .map(|solution| to_json(&Level::with_par(board.apply_assignment(solution), None)));
+    let solutions = solutions
+        .iter()
+        .map(|solution| {
+            let presses = solution
+                .presses()
+                .into_iter()
+                .map(|pos| {
+                    Value::Object(vec![
+                        ("row".to_string(), Value::from(pos.row)),
+                        ("col".to_string(), Value::from(pos.col)),
+                    ])
+                })
+                .collect();
+            Value::Object(vec![
+                (
+                    "press_count".to_string(),
+                    Value::from(solution.count_ones()),
+                ),
+                ("presses".to_string(), Value::Array(presses)),
+            ])
+        })
+        .collect();
+    let solution_count =
+        solution_count.map_or_else(|| power_of_two(nullity), |count| count.to_string());
+    let mut members = vec![
+        (
+            "schema_version".to_string(),
+            Value::from(schema::RESULT_SCHEMA_VERSION as usize),
+        ),
+        (
+            "board".to_string(),
+            to_json(&Level::with_par(board.clone(), None)),
+        ),
+        ("rank".to_string(), Value::from(rank)),
+        ("free_variables".to_string(), Value::from(nullity)),
+        ("solution_count".to_string(), Value::String(solution_count)),
+        ("solutions".to_string(), Value::Array(solutions)),
+    ];
+    if let Some(final_board) = final_board {
+        members.push(("final_board".to_string(), final_board));
+    }
+    Value::Object(members)
+}
+
+/// Writes `2^exponent` in decimal, however large it is.
+fn power_of_two(exponent: usize) -> String {
+    // Little-endian decimal digits, doubled once per power.
+    let mut digits = vec![1u8];
+    for _ in 0..exponent {
+        let mut carry = 0;
+        for digit in &mut digits {
+            let doubled = *digit * 2 + carry;
+            *digit = doubled % 10;
+            carry = doubled / 10;
+        }
+        if carry > 0 {
+            digits.push(carry);
+        }
+    }
+    digits
+        .iter()
+        .rev()
+        .map(|digit| char::from(b'0' + digit))
+        .collect()
+}
+
+/// Parses a JSON document following `schema::BOARD_SCHEMA`.
+fn parse_json(text: &str) -> Result<Level, String> {
+    level_from_json(&json::parse(text)?)
+}
+
+/// Reads a level out of a JSON document following `schema::BOARD_SCHEMA`. A missing par is computed from the board.
+fn level_from_json(document: &Value) -> Result<Level, String> {
+    let version = document.get("schema_version").and_then(Value::as_f64);
+    if version != Some(schema::SCHEMA_VERSION as f64) {
+        return Err(format!(
+            "Unsupported board schema version. Supported version: {}.",
+            schema::SCHEMA_VERSION
+        ));
+    }
+    let lines = |key: &str| -> Result<Option<Vec<String>>, String> {
+        let Some(value) = document.get(key) else {
+            return Ok(None);
+        };
+        value
+            .as_array()
+            .and_then(|items| {
+                items
+                    .iter()
+                    .map(|item| item.as_str().map(str::to_string))
+                    .collect::<Option<Vec<String>>>()
+            })
+            .map(Some)
+            .ok_or_else(|| format!("\"{}\" must be an array of strings.", key))
+    };
+
+    let rows = lines("rows")?.ok_or("Missing \"rows\".")?;
+    let mut board = match board::parse_board_rows(&rows.join("\n")) {
+        Ok((_, board)) => board,
+        Err(e) => return Err(format!("Could not parse rows: {}", e)),
+    };
+    if board.cell_count() == 0 {
+        return Err("Board has no pressable cells.".to_string());
+    }
+    if let Some(modifiers) = lines("modifiers")? {
+        board.apply_modifier_text(&modifiers.join("\n"))?;
+    }
+    if let Some(convention) = document.get("target_convention") {
+        let value = match convention.as_str() {
+            Some("on") => Bit::On,
+            Some("off") => Bit::Off,
+            _ => return Err("\"target_convention\" must be \"on\" or \"off\".".to_string()),
+        };
+        board.set_uniform_target(value);
+    }
+    if let Some(target) = lines("target")? {
+        board.apply_target(&target)?;
+    }
+    if let Some(rigid) = document.get("rigid") {
+        let rigid = rigid
+            .as_str()
+            .and_then(RigidSemantics::from_name)
+            .ok_or("\"rigid\" must name rigid semantics.")?;
+        board.set_rigid_semantics(rigid);
+    }
+    let par = match document.get("par") {
+        None => None,
+        Some(par) => Some(
+            par.as_f64()
+                .filter(|par| par.fract() == 0.0 && *par >= 0.0)
+                .ok_or("\"par\" must be a non-negative integer.")? as usize,
+        ),
+    };
+    Ok(Level::with_par(board, par))
+}
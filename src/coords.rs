@@ -0,0 +1,158 @@
+use std::sync::OnceLock;
+
+use crate::{grid::Position, i18n::tr};
+
+/// How rows and columns are numbered in every coordinate shown to or read from the user.
+///
+/// Positions inside the solver always count from 0 at the top left. Only the numbers the user sees go through `Coordinates`,
+/// so a game that numbers its rows from the bottom can be followed without converting by hand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Coordinates {
+    /// The number of the first row and the first column, 0 or 1.
+    pub base: usize,
+    /// Whether rows count up from the bottom of the board instead of down from the top.
+    pub from_bottom: bool,
+}
+
+static COORDINATES: OnceLock<Coordinates> = OnceLock::new();
+
+/// The rows and columns of holes cropped off the edges of the board being solved. Shown coordinates add them back, so they match
+/// the board as it was entered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Margins {
+    pub top: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+static MARGINS: OnceLock<Margins> = OnceLock::new();
+
+impl Default for Coordinates {
+    fn default() -> Self {
+        Coordinates {
+            base: 1,
+            from_bottom: false,
+        }
+    }
+}
+
+impl Coordinates {
+    /// Picks the numbering from `$WAYOUT_INDEX` (`0` or `1`) and `$WAYOUT_ORIGIN` (`top` or `bottom`), defaulting to rows and
+    /// columns counted from 1 at the top left. Invalid values are ignored.
+    pub fn from_env() -> Self {
+        let mut coordinates = Coordinates::default();
+        if let Some(base) = std::env::var("WAYOUT_INDEX")
+            .ok()
+            .and_then(|v| parse_base(&v))
+        {
+            coordinates.base = base;
+        }
+        if let Some(from_bottom) = std::env::var("WAYOUT_ORIGIN")
+            .ok()
+            .and_then(|v| parse_origin(&v))
+        {
+            coordinates.from_bottom = from_bottom;
+        }
+        coordinates
+    }
+}
+
+/// Parses the number of the first row and column, `0` or `1`.
+pub fn parse_base(text: &str) -> Option<usize> {
+    match text.trim() {
+        "0" => Some(0),
+        "1" => Some(1),
+        _ => None,
+    }
+}
+
+/// Parses where rows are counted from, `top` or `bottom`, into whether they count from the bottom.
+pub fn parse_origin(text: &str) -> Option<bool> {
+    match text.trim() {
+        "top" => Some(false),
+        "bottom" => Some(true),
+        _ => None,
+    }
+}
+
+/// Sets the numbering of every coordinate from now on. Only the first call has any effect.
+pub fn set_coordinates(coordinates: Coordinates) {
+    let _ = COORDINATES.set(coordinates);
+}
+
+/// The numbering coordinates are currently shown and read in.
+pub fn coordinates() -> Coordinates {
+    *COORDINATES.get_or_init(Coordinates::from_env)
+}
+
+/// Sets the margins cropped off the board being solved. Only the first call has any effect.
+pub fn set_margins(margins: Margins) {
+    let _ = MARGINS.set(margins);
+}
+
+/// The margins cropped off the board being solved, none unless `set_margins` was called.
+pub fn margins() -> Margins {
+    MARGINS.get().copied().unwrap_or_default()
+}
+
+/// The number shown for the row at index `row` of a board with `height` rows.
+pub fn row_number(row: usize, height: usize) -> usize {
+    let coordinates = coordinates();
+    let row = if coordinates.from_bottom {
+        height - 1 - row + margins().bottom
+    } else {
+        row + margins().top
+    };
+    row + coordinates.base
+}
+
+/// The number shown for the column at index `col`.
+pub fn col_number(col: usize) -> usize {
+    col + margins().left + coordinates().base
+}
+
+/// Labels the cell at `pos` of a board with `height` rows as `(row,column)`, numbered as they are shown.
+pub fn cell_label(pos: Position, height: usize) -> String {
+    format!("({},{})", row_number(pos.row, height), col_number(pos.col))
+}
+
+/// Finds the position a user entered as `row` and `col` on a board with `height` rows and `width` columns. Returns `None` if they
+/// are not numbers, or fall outside the board.
+pub fn parse_position(row: &str, col: &str, height: usize, width: usize) -> Option<Position> {
+    let (base, margins) = (coordinates().base, margins());
+    let row = row.trim().parse::<usize>().ok()?.checked_sub(base)?;
+    let col = col.trim().parse::<usize>().ok()?.checked_sub(base)?;
+    let row = if coordinates().from_bottom {
+        row.checked_sub(margins.bottom)?
+    } else {
+        row.checked_sub(margins.top)?
+    };
+    let col = col.checked_sub(margins.left)?;
+    if row >= height || col >= width {
+        return None;
+    }
+    let row = if coordinates().from_bottom {
+        height - 1 - row
+    } else {
+        row
+    };
+    Some(Position::new(row, col))
+}
+
+/// Describes the current numbering, such as "Rows count from 1 at the top, and columns from 1 at the left."
+pub fn describe() -> String {
+    let coordinates = coordinates();
+    if coordinates.from_bottom {
+        tr!(
+            "Rows count from {} at the bottom, and columns from {} at the left.",
+            coordinates.base,
+            coordinates.base
+        )
+    } else {
+        tr!(
+            "Rows count from {} at the top, and columns from {} at the left.",
+            coordinates.base,
+            coordinates.base
+        )
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeSet;
+
+use crate::{board::BoardDescription, grid::Position, report::Report};
+
+/// The largest number of cells `crosscheck` accepts. Brute force plays through all `2^n` sets of presses of a board with `n` cells.
+pub const MAX_CROSSCHECK_CELLS: usize = 20;
+
+/// A set of presses on which brute force and linear algebra disagree, in reading order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// Pressing these cells solves the board, but linear algebra does not give them as a solution.
+    Missed(Vec<Position>),
+    /// Linear algebra gives these cells as a solution, but pressing them does not solve the board.
+    Spurious(Vec<Position>),
+}
+
+/// Compares the solutions linear algebra finds for `board` with those found by pressing every set of its cells with
+/// `BoardDescription::press`, which shares none of the matrix code. Returns every disagreement, missed solutions first, so an
+/// empty result means both agree on the full solution set. Cells that cannot be pressed are left out of both.
+///
+/// Fails if `board` has more than `MAX_CROSSCHECK_CELLS` cells.
+pub fn crosscheck(board: &BoardDescription) -> Result<Vec<Discrepancy>, String> {
+    let cells: Vec<Position> = Position::iter_row_major(board.width(), board.height())
+        .filter(|&pos| board.is_pressable(pos))
+        .collect();
+    if cells.len() > MAX_CROSSCHECK_CELLS {
+        return Err(format!(
+            "The board has {} cells, but brute force is limited to {}.",
+            cells.len(),
+            MAX_CROSSCHECK_CELLS
+        ));
+    }
+    let presses_of = |mask: u32| -> Vec<Position> {
+        (0..cells.len())
+            .filter(|i| mask >> i & 1 == 1)
+            .map(|i| cells[i])
+            .collect()
+    };
+
+    let brute_force: BTreeSet<u32> = (0..1u32 << cells.len())
+        .filter(|&mask| {
+            let mut played = board.clone();
+            for pos in presses_of(mask) {
+                played.press(pos);
+            }
+            played.is_solved()
+        })
+        .collect();
+
+    let (matrix, indexed_locations) = board.to_matrix();
+    let algebra: BTreeSet<u32> = Report::new(&matrix)
+        .equations
+        .results()
+        .map(|assignment| {
+            board
+                .assign_assignment(assignment, &indexed_locations)
+                .presses()
+                .into_iter()
+                .filter_map(|pos| cells.iter().position(|&cell| cell == pos))
+                .fold(0, |mask, i| mask | 1 << i)
+        })
+        .collect();
+
+    let missed = brute_force
+        .difference(&algebra)
+        .map(|&mask| Discrepancy::Missed(presses_of(mask)));
+    let spurious = algebra
+        .difference(&brute_force)
+        .map(|&mask| Discrepancy::Spurious(presses_of(mask)));
+    Ok(missed.chain(spurious).collect())
+}
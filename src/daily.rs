@@ -0,0 +1,118 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use wayout_solver::{board::BoardDescription, generator, rng::Rng};
+
+/// The size of every daily puzzle.
+pub const DAILY_WIDTH: usize = 5;
+pub const DAILY_HEIGHT: usize = 5;
+/// Daily puzzles are scrambled with between `DAILY_MIN_PRESSES` and `DAILY_MAX_PRESSES` presses.
+pub const DAILY_MIN_PRESSES: usize = 5;
+pub const DAILY_MAX_PRESSES: usize = 10;
+
+/// Mixed into the day number, so that daily seeds differ from small seeds users pass to `--seed`.
+const DAILY_SALT: u64 = 0x5741_594f_5554_0001;
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// The current date in UTC, so that everyone gets the same puzzle on the same day wherever they are.
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Date::from_days((secs / 86400) as i64)
+    }
+
+    /// Parses a date written as `YYYY-MM-DD`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "Invalid date \"{}\". Dates are written as YYYY-MM-DD.",
+                text
+            )
+        };
+        let mut parts = text.trim().splitn(3, '-');
+        let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        let date = Date {
+            year: year.parse().map_err(|_| invalid())?,
+            month: month.parse().map_err(|_| invalid())?,
+            day: day.parse().map_err(|_| invalid())?,
+        };
+        if !(1..=12).contains(&date.month) || date.day == 0 || date.day > date.days_in_month() {
+            return Err(invalid());
+        }
+        Ok(date)
+    }
+
+    fn days_in_month(self) -> u32 {
+        let leap = self.year % 4 == 0 && (self.year % 100 != 0 || self.year % 400 == 0);
+        match self.month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// The number of days since 1970-01-01, which may be negative.
+    pub fn to_days(self) -> i64 {
+        // Howard Hinnant's days_from_civil, with years starting in March so that leap days come last.
+        let year = if self.month <= 2 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146097 + day_of_era - 719468
+    }
+
+    /// The date `days` days after 1970-01-01. The inverse of `to_days`.
+    pub fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date { year, month, day }
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Generates the puzzle of `date`. The same date always gives the same puzzle.
+pub fn daily_puzzle(date: Date) -> BoardDescription {
+    let mut rng = Rng::new(date.to_days() as u64 ^ DAILY_SALT);
+    let presses =
+        DAILY_MIN_PRESSES + rng.below((DAILY_MAX_PRESSES - DAILY_MIN_PRESSES + 1) as u64) as usize;
+    generator::generate(DAILY_WIDTH, DAILY_HEIGHT, presses, &mut rng).0
+}
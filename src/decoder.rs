@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+
+use crate::{
+    bit::Bit,
+    board::{parse_board_file, BoardAssignment, BoardDescription},
+    equations::Assignment,
+    grid::Position,
+    kernel,
+    matrix::{get_leading_column, Matrix},
+    trace,
+};
+
+/// The largest number of free variables `CosetDecoder` accepts, as a query may walk through every element of the kernel.
+pub const MAX_DECODER_NULLITY: usize = 20;
+
+/// The most variables the kernel may press for `CosetDecoder` to tabulate its coset leaders. The table has an entry for every
+/// coset of the kernel restricted to these variables, and building it visits every pattern on them.
+pub const MAX_LEADER_SUPPORT: usize = 20;
+
+/// Answers minimum-press queries for any start state of a fixed board shape, doing all of the elimination work up front.
+///
+/// Every solution for a start state is a particular solution plus an element of the kernel, so the minimum is the lightest element
+/// of that coset, its coset leader. The decoder stores the transformation that elimination applies to the constant column, which
+/// yields a particular solution with a single matrix-vector product, along with a basis of the kernel.
+///
+/// Outside of the variables some basis vector presses, the support, every element of a coset agrees, so the leader only depends
+/// on the particular solution at the pivot variables in the support. While the support has at most `MAX_LEADER_SUPPORT` variables,
+/// the decoder tabulates, for each of their values, which basis vectors turn the particular solution into its leader, and a query
+/// is a lookup. Larger supports are walked in Gray code order on every query instead.
+#[derive(Clone, Debug)]
+pub struct CosetDecoder {
+    shape: BoardDescription,
+    indexed_locations: HashMap<usize, Position>,
+    /// The reduced row-echelon form of the adjacency matrix augmented with the identity matrix. The right half of each row
+    /// records which constant terms it sums.
+    reduced: Matrix,
+    /// The right half of `reduced`, which takes the constant column to the constant column after elimination.
+    transform: Matrix,
+    /// The pivot column of each non-zero row of the left half of `reduced`.
+    pivots: Vec<usize>,
+    /// A basis of the kernel, one vector per free variable.
+    basis: Vec<Vec<Bit>>,
+    leaders: Leaders,
+}
+
+/// How `CosetDecoder` turns a particular solution into its coset leader.
+#[derive(Clone, Debug)]
+enum Leaders {
+    /// `keys` lists the pivot variables in the support of the kernel, in order. Bit `j` of the index into `table` is the value of
+    /// the particular solution at `keys[j]`, and bit `i` of the entry is whether `basis[i]` is added to reach the leader.
+    Table { keys: Vec<usize>, table: Vec<u32> },
+    /// Walks every element of the coset.
+    Walk,
+}
+
+impl CosetDecoder {
+    /// Precomputes the decoder for the shape of `board`. Returns `None` if the board has more than `MAX_DECODER_NULLITY` free variables.
+    pub fn new(board: &BoardDescription) -> Option<Self> {
+        let (matrix, indexed_locations) = board.to_matrix();
+        let mut reduced = matrix.with_identity(matrix.variable_cols());
+        reduced.eliminate();
+        Self::from_reduced(board, indexed_locations, reduced)
+    }
+
+    /// Builds the decoder out of the already eliminated `reduced` matrix of the shape of `board`.
+    fn from_reduced(
+        board: &BoardDescription,
+        indexed_locations: HashMap<usize, Position>,
+        reduced: Matrix,
+    ) -> Option<Self> {
+        let n = indexed_locations.len();
+        let pivots: Vec<usize> = (0..reduced.rows)
+            .map_while(|row| get_leading_column(&reduced.row(row)[..n]))
+            .collect();
+        let free_cols: Vec<usize> = (0..n).filter(|col| !pivots.contains(col)).collect();
+        if free_cols.len() > MAX_DECODER_NULLITY {
+            return None;
+        }
+
+        let basis: Vec<Vec<Bit>> = free_cols
+            .iter()
+            .map(|&free_col| {
+                let mut vector = vec![Bit::Off; n];
+                vector[free_col] = Bit::On;
+                for (row, &pivot) in pivots.iter().enumerate() {
+                    vector[pivot] = reduced.get(row, free_col);
+                }
+                vector
+            })
+            .collect();
+
+        let transform = Matrix::new(
+            (0..reduced.rows)
+                .map(|row| reduced.row(row)[n..].to_vec())
+                .collect(),
+            reduced.rows,
+            reduced.rows,
+        )?;
+        let leaders = tabulate_leaders(&basis, &free_cols);
+        Some(CosetDecoder {
+            shape: board.clone(),
+            indexed_locations,
+            reduced,
+            transform,
+            pivots,
+            basis,
+            leaders,
+        })
+    }
+
+    /// Renders `self` as text, in the format read by `from_text`. Only the shape and the eliminated matrix are stored, as the
+    /// rest of the decoder is derived from them without another elimination.
+    pub fn to_text(&self) -> String {
+        format!(
+            "[shape]\n{}[reduced]\n{}",
+            self.shape.to_board_text(),
+            self.reduced
+        )
+    }
+
+    /// Restores a decoder rendered with `to_text`, without redoing the elimination.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let text = text.replace("\r\n", "\n");
+        let Some(rest) = text.strip_prefix("[shape]\n") else {
+            return Err("Missing [shape] section.".to_string());
+        };
+        let Some((shape_text, reduced_text)) = rest.split_once("[reduced]\n") else {
+            return Err("Missing [reduced] section.".to_string());
+        };
+        let shape = parse_board_file(shape_text)?;
+        let (matrix, indexed_locations) = shape.to_matrix();
+        let (rows, n) = (matrix.rows, matrix.variable_cols());
+
+        let data: Vec<Vec<Bit>> = reduced_text
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|c| match c {
+                        '0' => Ok(Bit::Off),
+                        '1' => Ok(Bit::On),
+                        _ => Err(format!("Invalid character '{}' in [reduced] section.", c)),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        let Some(reduced) = Matrix::new(data, rows, n + rows) else {
+            return Err("The [reduced] section does not match the shape.".to_string());
+        };
+
+        match Self::from_reduced(&shape, indexed_locations, reduced) {
+            Some(decoder) => Ok(decoder),
+            None => Err(format!(
+                "Shape has more than {} free variables.",
+                MAX_DECODER_NULLITY
+            )),
+        }
+    }
+
+    /// The board the decoder was built for. Only its holes and modifiers are meaningful.
+    pub fn shape(&self) -> &BoardDescription {
+        &self.shape
+    }
+
+    /// Counts the free variables of the board shape.
+    pub fn nullity(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// Finds a solution with the fewest presses for the start state in `board`. Returns `Ok(None)` if `board` has no solutions.
+    ///
+    /// Fails if `board` does not have the same shape as the board the decoder was built for.
+    pub fn decode(&self, board: &BoardDescription) -> Result<Option<BoardAssignment>, String> {
+        if !board.same_shape(&self.shape) {
+            return Err("Board does not match the shape of the decoder.".to_string());
+        }
+        let _span = trace::span("enumerate");
+        let n = self.indexed_locations.len();
+        // Rows past the cells pin unpressable cells, and ask for nothing to change.
+        let mut constants = board.constant_column();
+        constants.resize(self.transform.cols, Bit::Off);
+
+        // The constant column after elimination.
+        let transformed = self.transform.mul_vector(&constants);
+        // Rows past the pivots are zero on the left, so they read `0 = constant`.
+        if transformed[self.pivots.len()..].contains(&Bit::On) {
+            return Ok(None);
+        }
+
+        let mut best = vec![Bit::Off; n];
+        for (row, &pivot) in self.pivots.iter().enumerate() {
+            best[pivot] = transformed[row];
+        }
+
+        match &self.leaders {
+            Leaders::Table { keys, table } => {
+                let key = keys
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &var)| best[var] == Bit::On)
+                    .fold(0, |key, (j, _)| key | 1 << j);
+                let mask = table[key];
+                for (i, vector) in self.basis.iter().enumerate() {
+                    if mask >> i & 1 == 1 {
+                        for (bit, &step) in best.iter_mut().zip(vector) {
+                            *bit += step;
+                        }
+                    }
+                }
+            }
+            Leaders::Walk => {
+                let mut current = best.clone();
+                for i in 1..(1usize << self.basis.len()) {
+                    // Consecutive Gray codes differ in the bit at the position of the lowest set bit of `i`.
+                    for (bit, &step) in current
+                        .iter_mut()
+                        .zip(&self.basis[i.trailing_zeros() as usize])
+                    {
+                        *bit += step;
+                    }
+                    if kernel::cmp_presses(&current, &best) == std::cmp::Ordering::Less {
+                        best.clone_from(&current);
+                    }
+                }
+            }
+        }
+
+        Ok(Some(board.assign_assignment(
+            Assignment::from_bits(&best),
+            &self.indexed_locations,
+        )))
+    }
+}
+
+/// Tabulates the coset leaders of the kernel spanned by `basis`, where `basis[i]` is the one with a single `On` among the free
+/// variables, at `free_cols[i]`. Falls back to `Leaders::Walk` if the basis presses more than `MAX_LEADER_SUPPORT` variables.
+fn tabulate_leaders(basis: &[Vec<Bit>], free_cols: &[usize]) -> Leaders {
+    let n = basis.first().map_or(0, Vec::len);
+    let support: Vec<usize> = (0..n)
+        .filter(|&var| basis.iter().any(|vector| vector[var] == Bit::On))
+        .collect();
+    if support.len() > MAX_LEADER_SUPPORT {
+        return Leaders::Walk;
+    }
+    // Restricted to the support, in order, every pattern fits in a word, and `cmp_presses` compares the lowest bits first.
+    let restrict = |vector: &[Bit]| {
+        support
+            .iter()
+            .enumerate()
+            .filter(|(_, &var)| vector[var] == Bit::On)
+            .fold(0u32, |word, (j, _)| word | 1 << j)
+    };
+    let vectors: Vec<u32> = basis.iter().map(|vector| restrict(vector)).collect();
+    let key_bits: Vec<u32> = (0..support.len())
+        .filter(|&j| !free_cols.contains(&support[j]))
+        .map(|j| 1 << j)
+        .collect();
+
+    let table = (0..1u32 << key_bits.len())
+        .map(|key| {
+            let start = key_bits
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| key >> j & 1 == 1)
+                .fold(0, |word, (_, &bit)| word | bit);
+            let (mut current, mut mask) = (start, 0u32);
+            let (mut best, mut best_mask) = (start, 0u32);
+            for i in 1..(1u32 << vectors.len()) {
+                // Consecutive Gray codes differ in the bit at the position of the lowest set bit of `i`.
+                let step = i.trailing_zeros();
+                current ^= vectors[step as usize];
+                mask ^= 1 << step;
+                let lighter = current.count_ones().cmp(&best.count_ones()).then_with(|| {
+                    // The lowest differing bit is pressed by the first pattern in order.
+                    let lowest = (current ^ best) & (current ^ best).wrapping_neg();
+                    if current & lowest != 0 {
+                        std::cmp::Ordering::Less
+                    } else {
+                        std::cmp::Ordering::Greater
+                    }
+                });
+                if lighter == std::cmp::Ordering::Less {
+                    (best, best_mask) = (current, mask);
+                }
+            }
+            best_mask
+        })
+        .collect();
+    Leaders::Table {
+        keys: key_bits
+            .iter()
+            .map(|bit| support[bit.trailing_zeros() as usize])
+            .collect(),
+        table,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bitslice, equations::Equations};
+
+    /// The `height` by `width` board whose cells start as the bits of `state`, row by row, between `header` and `modifiers`.
+    fn board(
+        (height, width): (usize, usize),
+        state: usize,
+        header: &str,
+        modifiers: &str,
+    ) -> BoardDescription {
+        let lines: Vec<String> = (0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|col| match state >> (row * width + col) & 1 {
+                        1 => '1',
+                        _ => '0',
+                    })
+                    .collect()
+            })
+            .collect();
+        parse_board_file(&format!("{}{}{}", header, lines.join("\n"), modifiers)).unwrap()
+    }
+
+    /// Checks the decoder against enumeration for every start state of a `height` by `width` board.
+    fn assert_decodes_every_state(size: (usize, usize), header: &str, modifiers: &str) {
+        let decoder = CosetDecoder::new(&board(size, 0, header, modifiers)).unwrap();
+        assert!(decoder.nullity() > 0);
+        let mut solvable = 0;
+        for state in 0..1 << (size.0 * size.1) {
+            let board = board(size, state, header, modifiers);
+            let decoded = decoder.decode(&board).unwrap();
+            let mut matrix = board.to_matrix().0;
+            matrix.eliminate();
+            let minimum = bitslice::minimum(&Equations::new(matrix));
+            assert_eq!(
+                decoded.as_ref().map(BoardAssignment::count_ones),
+                minimum.map(|bits| bits.iter().filter(|&&bit| bit == Bit::On).count())
+            );
+            if let Some(solution) = decoded {
+                assert!(board.apply_assignment(&solution).is_solved());
+                solvable += 1;
+            }
+        }
+        assert!(solvable > 0);
+    }
+
+    #[test]
+    fn decoder_finds_the_minimum_for_every_start_state() {
+        // A plain 4x4 board has 4 free variables, so only one start state in 16 is solvable.
+        assert_decodes_every_state((4, 4), "", "");
+    }
+
+    #[test]
+    fn decoder_finds_the_minimum_with_an_unpressable_cell() {
+        // The rigid centre cannot be pressed, so a row past the cells pins it, and the decoder pads its constant with `Off`. That
+        // leaves 3 free variables.
+        assert_decodes_every_state((3, 5), "# rigid: unpressable\n", "\n\n     \n  O  \n     ");
+    }
+}
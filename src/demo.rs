@@ -0,0 +1,102 @@
+use wayout_solver::{
+    board::{self, BoardAssignment},
+    coords,
+    i18n::say,
+    report::Report,
+};
+
+/// The board the demo solves. A 5x5 board has two free variables, so it shows several solutions without listing too many.
+const DEMO_BOARD: &str = "10001\n01010\n00100\n01010\n10001";
+
+/// Runs the built in demo board through every stage of the solver, explaining each one, and checks every solution by pressing
+/// its cells on a copy of the board.
+pub fn run() {
+    let board = board::parse_board_file(DEMO_BOARD).unwrap(); // The demo board is valid.
+
+    say!("Stage 1: the board.");
+    say!("Each row of the board is a line: 1 is a lit cell, 0 is an unlit cell. Pressing a cell toggles it and its four neighbors, and the goal is to light up every cell.");
+    print!("{}", board);
+    println!();
+
+    say!("Stage 2: the matrix.");
+    let (matrix, indexed_locations) = board.to_matrix();
+    let label = |col| coords::cell_label(indexed_locations[&col], board.height());
+    say!("Every cell is a variable, 1 if it is pressed. Variables number the cells in reading order, and each column is labeled with its cell.");
+    say!("Each row is the equation of one cell over GF(2), where 1 + 1 = 0: a 1 in a column means pressing that cell toggles this one, and the last column is 1 if the cell starts unlit.");
+    print!("{}", matrix.labeled(label));
+    println!();
+
+    say!("Stage 3: the reduced row echelon form.");
+    let report = Report::new(&matrix);
+    say!(
+        "Gauss-Jordan elimination adds rows to each other until every pivot column holds a single 1. The rank, the number of independent equations, is {} out of {}.",
+        report.rank,
+        matrix.variable_cols()
+    );
+    print!("{}", report.reduced.labeled(label));
+    println!();
+
+    say!("Stage 4: the equations.");
+    let eqns = &report.equations;
+    say!("Each nonzero row of the reduced matrix solves for its pivot variable. The variables without a pivot are free: any choice of them gives a solution.");
+    print!("{}", eqns);
+    if !eqns.is_consistent() {
+        say!("The board has no solutions, so the demo cannot go on.");
+        return;
+    }
+    println!();
+
+    say!("Stage 5: the solutions.");
+    let solutions: Vec<BoardAssignment> = eqns
+        .enumerate_all_results()
+        .into_iter()
+        .map(|assignment| board.assign_assignment(assignment, &indexed_locations))
+        .collect();
+    say!(
+        "With {} free variables there are 2^{} = {} solutions, one for every choice of the free variables. The presses of each one are marked with a 1:",
+        eqns.free_var_count(),
+        eqns.free_var_count(),
+        solutions.len()
+    );
+    for (index, solution) in solutions.iter().enumerate() {
+        say!(
+            "Solution #{}, {} button presses:\n{}",
+            index + 1,
+            solution.count_ones(),
+            solution
+        );
+    }
+    say!(
+        "Any two solutions differ by a quiet pattern, a set of presses that leaves the board unchanged. This board has {} independent quiet patterns.",
+        eqns.kernel_basis().len()
+    );
+    println!();
+
+    say!("Stage 6: verification.");
+    say!("Each solution is checked by pressing its cells one at a time on a copy of the board, without using the matrix at all.");
+    let mut passed = true;
+    for (index, solution) in solutions.iter().enumerate() {
+        let result = board.apply_assignment(solution);
+        if result.is_solved() {
+            say!("Solution #{} lights up every cell.", index + 1);
+        } else {
+            say!(
+                "Solution #{} does not solve the board! Pressing it gives:\n{}",
+                index + 1,
+                result
+            );
+            passed = false;
+        }
+    }
+    if let Some(minimum) = solutions.iter().min_by_key(|s| s.count_ones()) {
+        say!("The board after the solution with the fewest presses:");
+        print!("{}", board.apply_assignment(minimum));
+    }
+    println!();
+
+    if passed {
+        say!("Demo complete: every solution checks out.");
+    } else {
+        say!("Demo failed: some solutions do not solve the board.");
+    }
+}
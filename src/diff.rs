@@ -0,0 +1,174 @@
+use std::fmt::Display;
+
+use crate::{
+    board::{BoardDescription, Cell},
+    coords,
+    grid::{Grid, Position},
+};
+
+/// Describes how a single cell differs between two boards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellDiff {
+    /// The cell is a hole on exactly one of the boards. A position outside of a board counts as a hole.
+    pub hole: bool,
+    /// The cell has a different starting value on each board.
+    pub state: bool,
+    /// The cell has different connections or rigidity on each board.
+    pub modifiers: bool,
+}
+
+/// Cell-by-cell comparison of two boards. Its dimensions are the largest width and height of either board.
+pub struct BoardDiff<'a> {
+    before: &'a BoardDescription,
+    after: &'a BoardDescription,
+    grid: Grid<CellDiff>,
+    /// Whether changed cells are drawn in reverse video, which only terminals understand.
+    highlight: bool,
+}
+
+impl CellDiff {
+    /// Compares the cells at the same position of two boards.
+    pub fn new(before: Option<&Cell>, after: Option<&Cell>) -> Self {
+        match (before, after) {
+            (Some(b), Some(a)) => CellDiff {
+                hole: false,
+                state: b.starting_value != a.starting_value,
+                modifiers: !b.same_modifiers(a),
+            },
+            (None, None) => CellDiff {
+                hole: false,
+                state: false,
+                modifiers: false,
+            },
+            _ => CellDiff {
+                hole: true,
+                state: false,
+                modifiers: false,
+            },
+        }
+    }
+
+    /// Checks if the cell differs in any way.
+    pub fn is_changed(&self) -> bool {
+        self.hole || self.state || self.modifiers
+    }
+}
+
+impl<'a> BoardDiff<'a> {
+    /// Compares `before` and `after` cell by cell.
+    pub fn new(before: &'a BoardDescription, after: &'a BoardDescription) -> Self {
+        let width = before.width().max(after.width());
+        let height = before.height().max(after.height());
+        let lines = (0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|col| {
+                        let pos = Position::new(row, col);
+                        CellDiff::new(cell_at(before, pos), cell_at(after, pos))
+                    })
+                    .collect()
+            })
+            .collect();
+        let grid = Grid::new_full_lines(lines, width, height).unwrap(); // Boards are never empty, so neither is the diff.
+        BoardDiff {
+            before,
+            after,
+            grid,
+            highlight: false,
+        }
+    }
+
+    /// Draws the changed cells in reverse video if `highlight` is set. Only worth it when the output is a terminal.
+    pub fn with_highlight(mut self, highlight: bool) -> Self {
+        self.highlight = highlight;
+        self
+    }
+
+    /// Counts the cells that differ between both boards.
+    pub fn changed_count(&self) -> usize {
+        self.grid.count(CellDiff::is_changed)
+    }
+
+    /// Checks whether both boards are identical.
+    pub fn is_empty(&self) -> bool {
+        self.changed_count() == 0
+    }
+}
+
+/// Returns the cell at `pos`, treating out of bounds positions as holes.
+fn cell_at(board: &BoardDescription, pos: Position) -> Option<&Cell> {
+    board.get(pos).and_then(Option::as_ref)
+}
+
+/// Renders a single cell as its starting value, followed by its modifiers if it has any. Holes are rendered as `_`.
+fn describe_cell(cell: Option<&Cell>) -> String {
+    match cell {
+        Some(cell) => {
+            let modifiers = cell.modifiers();
+            if modifiers.is_empty() {
+                format!("{}", cell.starting_value)
+            } else {
+                format!("{}[{}]", cell.starting_value, modifiers)
+            }
+        }
+        None => "_".to_string(),
+    }
+}
+
+impl Display for BoardDiff<'_> {
+    /// Renders the `after` board, with every changed cell highlighted if asked for with `with_highlight`, followed by a list of the
+    /// changes.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "W: {} H: {} Changed: {}",
+            self.grid.get_width(),
+            self.grid.get_height(),
+            self.changed_count()
+        )?;
+        for row in self.grid.rows_iter() {
+            for col in self.grid.cols_iter() {
+                let pos = Position::new(row, col);
+                let ch = match cell_at(self.after, pos) {
+                    Some(cell) => cell.starting_value.to_string(),
+                    None => " ".to_string(),
+                };
+                if self.highlight && self.grid[pos].is_changed() {
+                    // Reverse video, so that changed holes are visible too.
+                    write!(f, "\x1b[7m{}\x1b[0m", ch)?;
+                } else {
+                    write!(f, "{}", ch)?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        for row in self.grid.rows_iter() {
+            for col in self.grid.cols_iter() {
+                let pos = Position::new(row, col);
+                let diff = self.grid[pos];
+                if !diff.is_changed() {
+                    continue;
+                }
+                let kinds = [
+                    (diff.hole, "hole"),
+                    (diff.state, "state"),
+                    (diff.modifiers, "modifiers"),
+                ]
+                .into_iter()
+                .filter_map(|(changed, kind)| if changed { Some(kind) } else { None })
+                .collect::<Vec<_>>()
+                .join(", ");
+                writeln!(
+                    f,
+                    "{} {}: {} -> {}",
+                    coords::cell_label(pos, self.grid.get_height()),
+                    kinds,
+                    describe_cell(cell_at(self.before, pos)),
+                    describe_cell(cell_at(self.after, pos))
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
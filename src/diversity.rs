@@ -0,0 +1,67 @@
+use crate::{
+    bit::Bit,
+    equations::{Assignment, Equations},
+    kernel,
+    rng::Rng,
+};
+
+/// Boards with at most this many solutions pick diverse solutions among all of them. Larger ones pick among random samples.
+pub const MAX_EXHAUSTIVE_SOLUTIONS: u128 = 1 << 12;
+/// How many random solutions are sampled for each requested diverse solution, when there are too many to consider them all.
+pub const SAMPLES_PER_SOLUTION: usize = 32;
+
+/// Counts the cells pressed by exactly one of `a` and `b`. Both must have the same length.
+pub fn distance(a: &[Bit], b: &[Bit]) -> usize {
+    kernel::weight(&kernel::add(a, b))
+}
+
+/// Greedily picks up to `count` of `candidates` that are as far apart from each other as possible, returning their indices.
+///
+/// Starts from the first candidate, then repeatedly adds the candidate whose distance to the closest already picked one is largest,
+/// breaking ties by the lowest index. Stops early once only duplicates of picked candidates are left.
+pub fn select_diverse(candidates: &[Vec<Bit>], count: usize) -> Vec<usize> {
+    if candidates.is_empty() || count == 0 {
+        return vec![];
+    }
+    let mut picked = vec![0];
+    let mut closest: Vec<usize> = candidates
+        .iter()
+        .map(|candidate| distance(candidate, &candidates[0]))
+        .collect();
+    while picked.len() < count {
+        let Some((best, &best_distance)) =
+            closest.iter().enumerate().rev().max_by_key(|&(_, &d)| d)
+        else {
+            break;
+        };
+        if best_distance == 0 {
+            break;
+        }
+        picked.push(best);
+        for (candidate, closest) in candidates.iter().zip(closest.iter_mut()) {
+            *closest = (*closest).min(distance(candidate, &candidates[best]));
+        }
+    }
+    picked
+}
+
+/// Picks up to `count` solutions of `eqns` that differ from each other in as many presses as possible.
+///
+/// Picks among every solution when there are at most `MAX_EXHAUSTIVE_SOLUTIONS`, and among `SAMPLES_PER_SOLUTION` random solutions
+/// per requested one otherwise. Fewer than `count` solutions are returned if the board does not have that many.
+pub fn diverse_solutions(eqns: &Equations, count: usize, rng: &mut Rng) -> Vec<Assignment> {
+    let candidates: Vec<Assignment> = match eqns.solution_count() {
+        Some(total) if total <= MAX_EXHAUSTIVE_SOLUTIONS => eqns.enumerate_all_results(),
+        _ => (0..count.saturating_mul(SAMPLES_PER_SOLUTION))
+            .map_while(|_| eqns.sample_solution(rng))
+            .collect(),
+    };
+    let bits: Vec<Vec<Bit>> = candidates
+        .iter()
+        .filter_map(|candidate| candidate.to_bits(eqns.var_count()))
+        .collect();
+    select_diverse(&bits, count)
+        .into_iter()
+        .map(|index| Assignment::from_bits(&bits[index]))
+        .collect()
+}
@@ -1,21 +1,29 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     fmt::Display,
 };
 
 use crate::{
     bit::Bit,
-    matrix::{get_leading_column, Matrix},
+    bitslice,
+    json::{self, Value},
+    kernel,
+    matrix::Matrix,
+    rng::Rng,
+    trace,
 };
 
 type Var = usize;
 
 /// Encodes a system of equations as a set of free variables, along with a set of equations encoding each non-free variable as a sum of
 /// free variables and a constant term.
+///
+/// A system whose reduced matrix contained a row `0 = 1` is inconsistent, and has no solutions regardless of its equations.
 #[derive(Clone, Debug)]
 pub struct Equations {
-    free_vars: HashSet<Var>,
+    free_vars: BTreeSet<Var>,
     eqns: HashMap<Var, (HashSet<Var>, Bit)>,
+    consistent: bool,
 }
 
 /// Encodes an assignment of values to variables, as a map from variable indices to concrete bits.
@@ -42,6 +50,16 @@ impl Display for Assignment {
 }
 
 impl Assignment {
+    /// Creates an assignment from a list of bits, where the `i`th bit is the value of the variable at index `i`.
+    pub fn from_bits(bits: &[Bit]) -> Self {
+        Assignment(bits.iter().copied().enumerate().collect())
+    }
+
+    /// Lists the values of the variables at indices `0..len` in order. Returns `None` if any of them is unassigned.
+    pub fn to_bits(&self, len: usize) -> Option<Vec<Bit>> {
+        (0..len).map(|i| self.0.get(&i).copied()).collect()
+    }
+
     /// Renders an assignment as a string of `1`s and `0`s, where the `i`th character corresponds to the variable at index `i`.
     pub fn as_bitstring(&self) -> Option<String> {
         let mut res = String::new();
@@ -61,15 +79,14 @@ impl Assignment {
 
 impl Display for Equations {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.consistent {
+            writeln!(f, "Inconsistent system, no solutions.")?;
+        }
         write!(f, "Free vars: ")?;
-        let sorted_free_vars = {
-            let mut sorted_free_vars = self.free_vars.iter().map(|&v| v).collect::<Vec<usize>>();
-            sorted_free_vars.sort();
-            sorted_free_vars
-        };
-        for free_var in sorted_free_vars.into_iter() {
+        for free_var in self.free_vars.iter() {
             write!(f, "x_{}, ", free_var)?;
         }
+        writeln!(f)?;
         let sorted_equations = {
             let mut sorted_equations = self
                 .eqns
@@ -83,7 +100,7 @@ impl Display for Equations {
         for (var, terms, constant_term) in sorted_equations.into_iter() {
             write!(f, "x_{} = ", var)?;
             let sorted_terms = {
-                let mut sorted_terms = terms.iter().map(|&b| b).collect::<Vec<usize>>();
+                let mut sorted_terms = terms.iter().copied().collect::<Vec<usize>>();
                 sorted_terms.sort();
                 sorted_terms
             };
@@ -102,12 +119,19 @@ impl Equations {
     /// Computes a system of equations from `matrix`` in reduced row-echelon form. Does not check if `matrix`` is in reduced row-echelon form.
     /// Will produce a system of equations of questionable quality otherwise.
     pub fn new(matrix: Matrix) -> Self {
-        let free_vars = {
-            let mut free_vars: HashSet<Var> = matrix.non_leading_columns().into_iter().collect();
-            free_vars.remove(&(matrix.cols - 1));
-            free_vars
-        };
+        // The first augmented column holds the constant terms. Without one, the system is homogeneous.
+        Equations::for_target(matrix, 0)
+    }
+
+    /// Computes the system of equations whose constant terms are in the augmented column at index `target` of `matrix`, in reduced
+    /// row-echelon form. A matrix augmented with the constant column of several targets is eliminated once, and every target then
+    /// reads its own system off it.
+    pub fn for_target(matrix: Matrix, target: usize) -> Self {
+        let _span = trace::span("extract");
+        let free_vars: BTreeSet<Var> = matrix.non_leading_columns().into_iter().collect();
+        let constant_col = matrix.variable_cols() + target;
         let mut eqns = HashMap::new();
+        let mut consistent = true;
         for row in 0..matrix.rows {
             if matrix.is_row_zero(row) {
                 break;
@@ -115,24 +139,364 @@ impl Equations {
             let params = free_vars
                 .iter()
                 .filter_map(|&col| {
-                    if matrix.data[row][col] == Bit::On {
+                    if matrix.get(row, col) == Bit::On {
                         Some(col)
                     } else {
                         None
                     }
                 })
                 .collect::<HashSet<Var>>();
-            let constant_term = matrix.data[row][matrix.cols - 1];
-            let leading_col = get_leading_column(&matrix.data[row]).unwrap();
-            if leading_col != matrix.cols - 1 {
-                eqns.insert(
-                    get_leading_column(&matrix.data[row]).unwrap(), // Safe to unwrap as we bailed before if row is zero
-                    (params, constant_term),
-                );
+            let constant_term = if constant_col < matrix.cols {
+                matrix.get(row, constant_col)
+            } else {
+                Bit::Off
+            };
+            let leading_col = matrix.leading_column(row).unwrap(); // Safe to unwrap as we bailed before if row is zero
+            if leading_col < matrix.variable_cols() {
+                eqns.insert(leading_col, (params, constant_term));
+            } else if constant_term == Bit::On {
+                // The row reads `0 = 1`.
+                consistent = false;
+            }
+        }
+
+        Equations {
+            free_vars,
+            eqns,
+            consistent,
+        }
+    }
+
+    /// Renders `self` as a JSON document: the number of variables, whether the system is consistent, the free variables, and one
+    /// object per dependent variable with the free variables it is the sum of and its constant term. Everything is in increasing
+    /// order of the variables.
+    pub fn to_json(&self) -> Value {
+        let indices =
+            |vars: &mut dyn Iterator<Item = Var>| Value::Array(vars.map(Value::from).collect());
+        let mut dependent: Vec<Var> = self.eqns.keys().copied().collect();
+        dependent.sort_unstable();
+        let equations = dependent
+            .into_iter()
+            .map(|var| {
+                let (terms, constant) = self
+                    .equation(var)
+                    .expect("Dependent variables have an equation");
+                Value::Object(vec![
+                    ("var".to_string(), Value::from(var)),
+                    ("terms".to_string(), indices(&mut terms.into_iter())),
+                    (
+                        "constant".to_string(),
+                        Value::from(usize::from(constant == Bit::On)),
+                    ),
+                ])
+            })
+            .collect();
+        Value::Object(vec![
+            ("variables".to_string(), Value::from(self.var_count())),
+            ("consistent".to_string(), Value::Bool(self.consistent)),
+            ("free".to_string(), indices(&mut self.free_vars())),
+            ("equations".to_string(), Value::Array(equations)),
+        ])
+    }
+
+    /// Parses a JSON document written by `to_json`. Fails unless every variable is either free or has a single equation, and every
+    /// equation only sums free variables.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let document = json::parse(text)?;
+        let index = |value: &Value| {
+            value
+                .as_f64()
+                .filter(|n| n.fract() == 0.0 && *n >= 0.0)
+                .map(|n| n as usize)
+                .ok_or_else(|| "Variables must be non-negative integers.".to_string())
+        };
+        let indices = |value: Option<&Value>, name: &str| {
+            value
+                .and_then(Value::as_array)
+                .ok_or_else(|| format!("Missing or invalid \"{}\".", name))?
+                .iter()
+                .map(index)
+                .collect::<Result<Vec<Var>, String>>()
+        };
+        let vars = document
+            .get("variables")
+            .ok_or("Missing \"variables\".")
+            .and_then(|value| index(value).map_err(|_| "Invalid \"variables\"."))?;
+        let consistent = match document.get("consistent") {
+            Some(Value::Bool(consistent)) => *consistent,
+            _ => return Err("Missing or invalid \"consistent\".".to_string()),
+        };
+        let free_vars: BTreeSet<Var> = indices(document.get("free"), "free")?.into_iter().collect();
+
+        let mut eqns = HashMap::new();
+        for equation in document
+            .get("equations")
+            .and_then(Value::as_array)
+            .ok_or("Missing or invalid \"equations\".")?
+        {
+            let var = index(equation.get("var").ok_or("Equation without \"var\".")?)?;
+            let terms = indices(equation.get("terms"), "terms")?;
+            let constant = match equation.get("constant").and_then(Value::as_f64) {
+                Some(0.0) => Bit::Off,
+                Some(1.0) => Bit::On,
+                _ => return Err(format!("Invalid constant in the equation of x_{}.", var)),
+            };
+            if let Some(term) = terms.iter().find(|term| !free_vars.contains(term)) {
+                return Err(format!(
+                    "The equation of x_{} sums x_{}, which is not free.",
+                    var, term
+                ));
+            }
+            if free_vars.contains(&var)
+                || eqns
+                    .insert(var, (terms.into_iter().collect(), constant))
+                    .is_some()
+            {
+                return Err(format!("x_{} is defined more than once.", var));
             }
         }
+        if let Some(var) = (0..vars).find(|var| !free_vars.contains(var) && !eqns.contains_key(var))
+        {
+            return Err(format!(
+                "x_{} is neither free nor defined by an equation.",
+                var
+            ));
+        }
+        if free_vars.len() + eqns.len() != vars {
+            return Err(
+                "Variables must be numbered from 0 to one less than \"variables\".".to_string(),
+            );
+        }
+        Ok(Equations {
+            free_vars,
+            eqns,
+            consistent,
+        })
+    }
+
+    /// Builds the equations of `matrix`, whose columns were reordered by `Matrix::eliminate_with`, in terms of the variables of the
+    /// original column order `order`.
+    pub fn with_column_order(matrix: Matrix, order: &[usize]) -> Self {
+        let equations = Equations::new(matrix);
+        Equations {
+            free_vars: equations.free_vars.iter().map(|&var| order[var]).collect(),
+            eqns: equations
+                .eqns
+                .into_iter()
+                .map(|(var, (params, constant))| {
+                    let params = params.into_iter().map(|param| order[param]).collect();
+                    (order[var], (params, constant))
+                })
+                .collect(),
+            consistent: equations.consistent,
+        }
+    }
+
+    /// Finds the equation determining the variable `var`: the free variables it is the sum of, in increasing order, and its
+    /// constant term. Returns `None` if `var` is free.
+    pub fn equation(&self, var: Var) -> Option<(Vec<Var>, Bit)> {
+        let (terms, constant) = self.eqns.get(&var)?;
+        let mut terms: Vec<Var> = terms.iter().copied().collect();
+        terms.sort_unstable();
+        Some((terms, *constant))
+    }
+
+    /// Checks whether the system has any solution at all.
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
 
-        Equations { free_vars, eqns }
+    /// Counts the free variables of `self`. The system has `2^n` solutions, where `n` is this count.
+    pub fn free_var_count(&self) -> usize {
+        self.free_vars.len()
+    }
+
+    /// Lists the free variables of `self`, in increasing order.
+    pub fn free_vars(&self) -> impl Iterator<Item = Var> + '_ {
+        self.free_vars.iter().copied()
+    }
+
+    /// Counts the variables of `self` that are determined by an equation.
+    pub fn dependent_count(&self) -> usize {
+        self.eqns.len()
+    }
+
+    /// Computes the particular solution of `self`, with every free variable `Off`. Every other solution is it plus an element of
+    /// the kernel. This is solution `0` in the order of `enumerate_all_results`. Returns `None` if `self` is inconsistent.
+    pub fn particular_solution(&self) -> Option<Assignment> {
+        self.consistent.then(|| {
+            let valuation = self.free_vars.iter().map(|&var| (var, Bit::Off)).collect();
+            self.backfeed(Assignment(valuation))
+        })
+    }
+
+    /// Computes the solution at position `index` of `enumerate_all_results`, without enumerating the ones before it.
+    /// Returns `None` if `self` is inconsistent, or if there are no more than `index` solutions.
+    pub fn nth_solution(&self, index: usize) -> Option<Assignment> {
+        self.solution_by_index(index as u128)
+    }
+
+    /// Maps `index` to the bit pattern of the free variables it stands for in the order of `enumerate_all_results`, and backfeeds it.
+    /// Returns `None` if `self` is inconsistent, or if there are no more than `index` solutions.
+    pub fn solution_by_index(&self, index: u128) -> Option<Assignment> {
+        if !self.consistent {
+            return None;
+        }
+        let count = self.free_vars.len();
+        if count < u128::BITS as usize && index >> count != 0 {
+            return None;
+        }
+        let valuation = self
+            .free_vars
+            .iter()
+            .enumerate()
+            .map(|(j, &var)| {
+                let shift = count - 1 - j;
+                let bit = if shift < u128::BITS as usize && (index >> shift) & 1 == 1 {
+                    Bit::On
+                } else {
+                    Bit::Off
+                };
+                (var, bit)
+            })
+            .collect();
+        Some(self.backfeed(Assignment(valuation)))
+    }
+
+    /// Finds the index of the solution `bits` in enumeration order, the inverse of `solution_by_index`. The `i`th bit is the
+    /// value of the variable at index `i`. Only the free variables are read. Returns `None` if there are too many free variables
+    /// for the index to fit in a `u128`.
+    pub fn index_of(&self, bits: &[Bit]) -> Option<u128> {
+        if self.free_vars.len() > u128::BITS as usize {
+            return None;
+        }
+        Some(self.free_vars.iter().fold(0, |index, &var| {
+            index << 1 | u128::from(bits[var] == Bit::On)
+        }))
+    }
+
+    /// Picks a uniformly random solution, by assigning a random bit to every free variable. Returns `None` if `self` is inconsistent.
+    pub fn sample_solution(&self, rng: &mut Rng) -> Option<Assignment> {
+        if !self.consistent {
+            return None;
+        }
+        let valuation = self
+            .free_vars
+            .iter()
+            .map(|&var| (var, rng.next_bit()))
+            .collect();
+        Some(self.backfeed(Assignment(valuation)))
+    }
+
+    /// Counts the solutions of `self`, which is `2^n` for `n` free variables, or zero if `self` is inconsistent.
+    /// Returns `None` if the count does not fit in a `u128`.
+    pub fn solution_count(&self) -> Option<u128> {
+        if !self.consistent {
+            Some(0)
+        } else {
+            1u128.checked_shl(self.free_vars.len() as u32)
+        }
+    }
+
+    /// Counts all the variables of `self`, free or not.
+    pub fn var_count(&self) -> usize {
+        self.free_vars.len() + self.eqns.len()
+    }
+
+    /// Computes a basis of the kernel of the system, that is, of the solutions to the system with every constant term set to `Off`.
+    /// Each of these encodes a set of presses that changes nothing on the board.
+    ///
+    /// There is one vector per free variable, in increasing order, with that variable `On` and every other free variable `Off`.
+    /// The `i`th bit of each vector is the value of the variable at index `i`.
+    pub fn kernel_basis(&self) -> Vec<Vec<Bit>> {
+        self.free_vars
+            .iter()
+            .map(|&free_var| {
+                let mut vector = vec![Bit::Off; self.var_count()];
+                vector[free_var] = Bit::On;
+                for (&var, (terms, _)) in self.eqns.iter() {
+                    if terms.contains(&free_var) {
+                        vector[var] = Bit::On;
+                    }
+                }
+                vector
+            })
+            .collect()
+    }
+
+    /// Finds the solution of `self` with the fewest variables `On`, breaking ties by `kernel::cmp_presses` like
+    /// `bitslice::minimum`. Returns `None` if `self` is inconsistent, or has more than 128 free variables.
+    ///
+    /// Rather than evaluating every solution, this searches the coset of the particular solution with `kernel::lightest_in_coset`,
+    /// over a reduced kernel basis, so it stays practical for many more free variables than enumeration.
+    pub fn minimum_weight_solution(&self) -> Option<Assignment> {
+        let _span = trace::span("search");
+        if self.free_vars.len() > u128::BITS as usize {
+            return None;
+        }
+        let particular = self.particular_solution()?.to_bits(self.var_count())?;
+        let mut basis = self.kernel_basis();
+        kernel::reduce_basis(&mut basis);
+        Some(Assignment::from_bits(&kernel::lightest_in_coset(
+            &particular,
+            &basis,
+        )))
+    }
+
+    /// Fixes the variable `var` to `value`, and returns the system of the solutions of `self` that agree with it. The result is
+    /// inconsistent if none do.
+    ///
+    /// A free variable simply stops being free. A dependent variable turns its equation into a constraint on the free variables,
+    /// which is solved for the largest of them, and that variable is then substituted into every other equation.
+    pub fn substitute(&self, var: Var, value: Bit) -> Equations {
+        let mut result = self.clone();
+        if result.free_vars.remove(&var) {
+            for (terms, constant) in result.eqns.values_mut() {
+                if terms.remove(&var) {
+                    *constant += value;
+                }
+            }
+        } else if let Some((mut terms, constant)) = result.eqns.remove(&var) {
+            match terms.iter().max().copied() {
+                // The equation reads `constant = value`, which either always or never holds.
+                None => result.consistent &= constant == value,
+                Some(pivot) => {
+                    // Solve `value = constant + pivot + rest` for `pivot`.
+                    terms.remove(&pivot);
+                    let solved_constant = constant + value;
+                    result.free_vars.remove(&pivot);
+                    for (other_terms, other_constant) in result.eqns.values_mut() {
+                        if other_terms.remove(&pivot) {
+                            *other_terms =
+                                other_terms.symmetric_difference(&terms).copied().collect();
+                            *other_constant += solved_constant;
+                        }
+                    }
+                    result.eqns.insert(pivot, (terms, solved_constant));
+                }
+            }
+        }
+        result.eqns.insert(var, (HashSet::new(), value));
+        result
+    }
+
+    /// Lists solutions leading from the solution `from` to the solution `to`, both included, where each differs from the previous
+    /// one by a single vector of `kernel_basis`. Returns the free variable of every vector added along the way, in order, alongside.
+    ///
+    /// The vector of a free variable is the only basis vector setting it, so the path flips the free variables the two solutions
+    /// disagree on, one at a time in increasing order.
+    pub fn path(&self, from: &[Bit], to: &[Bit]) -> Vec<(Option<Var>, Vec<Bit>)> {
+        let mut current = from.to_vec();
+        let mut path = vec![(None, current.clone())];
+        for (&free_var, vector) in self.free_vars.iter().zip(self.kernel_basis()) {
+            if current[free_var] != to[free_var] {
+                for (bit, step) in current.iter_mut().zip(&vector) {
+                    *bit += *step;
+                }
+                path.push((Some(free_var), current.clone()));
+            }
+        }
+        path
     }
 
     /// Uses a partial `assignment` of only free variables in `self`, and the equations of `self`, to compute a full assignment of all variables in `self`.
@@ -157,52 +521,134 @@ impl Equations {
         Assignment(results)
     }
 
-    /// Enumerates the full assignment of all possible partial assignments in the free variables in `self`.
+    /// Enumerates the full assignment of all possible partial assignments in the free variables in `self`. Returns no assignments
+    /// if `self` is inconsistent.
+    ///
+    /// Assignments are ordered lexicographically, comparing the values of free variables from the smallest index to the largest,
+    /// with `Off` before `On`. Equivalently, solution `i` sets the `j`th smallest free variable to the `j`th most significant of
+    /// the low bits of `i`, so it is always the one returned by `nth_solution(i)`. Solutions are evaluated 64 at a time by
+    /// `bitslice::blocks`.
+    ///
+    /// This holds all `2^free_var_count()` solutions in memory at once. Use `results` to go through them one at a time instead.
     pub fn enumerate_all_results(&self) -> Vec<Assignment> {
-        let assignments = enumerate_all_assignments(&self.free_vars);
-        if assignments.len() > 0 {
-            let assignments = assignments.into_iter().map(|a| self.backfeed(a)).collect();
-            assignments
-        } else {
-            let assignments = vec![self.backfeed(Assignment(HashMap::new()))];
-            assignments
-        }
+        self.results().collect()
     }
-}
 
-/// Produces all possible assignments of values for the variables in `vars`.
-pub fn enumerate_all_assignments(vars: &HashSet<Var>) -> Vec<Assignment> {
-    let mut assignments = Vec::new();
-    println!("Vars: {:?}", vars);
-    let sorted_vars = {
-        let mut sorted_vars = vars.iter().cloned().collect::<Vec<usize>>();
-        sorted_vars.sort();
-        sorted_vars
-    };
-    let remaining_vars = {
-        let mut remaining_vars = sorted_vars.clone();
-        if let Some(&arbitrary) = remaining_vars.first() {
-            assignments.push(Assignment(HashMap::from([(arbitrary, Bit::Off)])));
-            assignments.push(Assignment(HashMap::from([(arbitrary, Bit::On)])));
-            remaining_vars.remove(0);
-        } else {
-            return assignments;
-        }
-        let mut remaining_vars = remaining_vars.into_iter().collect::<Vec<usize>>();
-        remaining_vars.sort();
-        remaining_vars
-    };
-    for var in remaining_vars {
-        assignments = assignments
+    /// Produces the same solutions as `enumerate_all_results`, in the same order, but one at a time rather than all at once. Only
+    /// the block of 64 solutions being read is kept in memory, so this suits systems with far more solutions than
+    /// fit in memory, as long as `solution_count` can count them.
+    pub fn results(&self) -> impl Iterator<Item = Assignment> {
+        bitslice::blocks(self)
             .into_iter()
-            .flat_map(|Assignment(a)| {
-                let mut a1 = a.clone();
-                let mut a2 = a;
-                a1.insert(var, Bit::Off);
-                a2.insert(var, Bit::On);
-                [Assignment(a1), Assignment(a2)].into_iter()
+            .flatten()
+            .flat_map(|block| {
+                (0..block.lanes).map(move |lane| Assignment::from_bits(&block.lane(lane)))
             })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::parse_board_file, report::Report};
+
+    /// The equations of the board in `text`.
+    fn equations(text: &str) -> Equations {
+        Report::new(&parse_board_file(text).unwrap().to_matrix().0).equations
+    }
+
+    /// Every solution of `eqns`, sorted.
+    fn solutions(eqns: &Equations) -> Vec<Vec<Bit>> {
+        let mut solutions: Vec<Vec<Bit>> = (0..)
+            .map_while(|index| eqns.nth_solution(index))
+            .map(|solution| solution.to_bits(eqns.var_count()).unwrap())
             .collect();
+        solutions.sort_by_key(|solution| format!("{:?}", solution));
+        solutions
+    }
+
+    #[test]
+    fn substitute_keeps_the_agreeing_solutions() {
+        // Free and dependent variables of boards with 16, 4 and a single solution.
+        for text in [
+            "0000\n0000\n0000\n0000",
+            "00000\n01000\n00000\n00010\n00000",
+            "010\n111\n010",
+        ] {
+            let eqns = equations(text);
+            let all = solutions(&eqns);
+            assert!(!all.is_empty());
+            for var in 0..eqns.var_count() {
+                for value in [Bit::Off, Bit::On] {
+                    let constrained = eqns.substitute(var, value);
+                    let agreeing: Vec<Vec<Bit>> = all
+                        .iter()
+                        .filter(|solution| solution[var] == value)
+                        .cloned()
+                        .collect();
+                    assert_eq!(constrained.is_consistent(), !agreeing.is_empty());
+                    assert_eq!(solutions(&constrained), agreeing);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn substitute_chains() {
+        let eqns = equations("0000\n0000\n0000\n0000");
+        let corners = eqns.substitute(0, Bit::On).substitute(3, Bit::On);
+        let all = solutions(&eqns);
+        let expected: Vec<Vec<Bit>> = all
+            .into_iter()
+            .filter(|solution| solution[0] == Bit::On && solution[3] == Bit::On)
+            .collect();
+        assert_eq!(solutions(&corners), expected);
+        assert!(!corners.substitute(0, Bit::Off).is_consistent());
+    }
+
+    #[test]
+    fn json_round_trips() {
+        for text in ["0000\n0000\n0000\n0000", "010\n111\n010", "10"] {
+            let eqns = equations(text);
+            let json = eqns.to_json().to_string();
+            let parsed = Equations::from_json(&json).unwrap();
+            assert_eq!(parsed.to_json().to_string(), json);
+            assert_eq!(parsed.is_consistent(), eqns.is_consistent());
+            assert_eq!(solutions(&parsed), solutions(&eqns));
+        }
+    }
+
+    #[test]
+    fn json_needs_every_variable_once() {
+        let system = |free: &str, equations: &str| {
+            format!(
+                r#"{{"variables": 3, "consistent": true, "free": [{}], "equations": [{}]}}"#,
+                free, equations
+            )
+        };
+        let equation = |var: usize, terms: &str| {
+            format!(r#"{{"var": {}, "terms": [{}], "constant": 1}}"#, var, terms)
+        };
+        let valid = system("2", &format!("{}, {}", equation(0, "2"), equation(1, "")));
+        assert!(Equations::from_json(&valid).is_ok());
+        // x_1 sums x_0, which is not free.
+        assert!(Equations::from_json(&system(
+            "2",
+            &format!("{}, {}", equation(0, "2"), equation(1, "0"))
+        ))
+        .is_err());
+        // x_1 is missing.
+        assert!(Equations::from_json(&system("2", &equation(0, "2"))).is_err());
+        // x_2 is both free and defined.
+        assert!(Equations::from_json(&system(
+            "2",
+            &format!(
+                "{}, {}, {}",
+                equation(0, ""),
+                equation(1, ""),
+                equation(2, "")
+            )
+        ))
+        .is_err());
     }
-    assignments
 }
@@ -0,0 +1,42 @@
+use crate::{bit::Bit, report::Report};
+
+/// Why a variable has its value in a solution of a system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Explanation {
+    /// The variable is free, so the solution chose its value.
+    Free,
+    /// The variable is determined by an equation on the free variables.
+    Forced {
+        /// The free variables the equation sums, in increasing order, with their values in the solution.
+        terms: Vec<(usize, Bit)>,
+        /// The constant term of the equation.
+        constant: Bit,
+        /// The rows of the input that elimination added together into the equation. Each row of a board matrix is the equation
+        /// of one cell: the presses that toggle it must add up to the toggle it needs.
+        rows: Vec<usize>,
+    },
+}
+
+impl Explanation {
+    /// Explains the value of the variable `var` in the solution `bits` of the system in `report`, where the `i`th bit is the value
+    /// of the variable at index `i`. `report` must use the default pivot strategy.
+    pub fn new(report: &Report, bits: &[Bit], var: usize) -> Self {
+        let Some((free_vars, constant)) = report.equations.equation(var) else {
+            return Explanation::Free;
+        };
+        let rows = report
+            .pivot_columns
+            .iter()
+            .position(|&col| col == var)
+            .and_then(|row| report.input.row_provenance().into_iter().nth(row))
+            .unwrap_or_default();
+        Explanation::Forced {
+            terms: free_vars
+                .into_iter()
+                .map(|free| (free, bits[free]))
+                .collect(),
+            constant,
+            rows,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::HashMap, io::BufRead};
+
+use wayout_solver::{
+    board::BoardDescription,
+    coords,
+    equations::Assignment,
+    grid::Position,
+    i18n::{say, tr},
+    kernel,
+    report::Report,
+    solver,
+};
+
+/// Lets the user query the solved system of `board` one command at a time, until `quit` or the end of the input.
+///
+/// `eq`, `cell` and `kernel` read the equations of the board as it was solved. `press` plays on a copy of the board, and `min`
+/// solves that copy as it stands.
+pub fn run(
+    board: &BoardDescription,
+    report: &Report,
+    indexed_locations: &HashMap<usize, Position>,
+) {
+    say!("Exploring the system. Type \"help\" for the commands, or \"quit\" to leave.");
+    run_commands(
+        board,
+        report,
+        indexed_locations,
+        std::io::stdin().lock(),
+        false,
+    );
+}
+
+/// Runs the commands of `script` on the solved system of `board`, as if they were typed in `run`, printing each one before its
+/// output. Blank lines and lines starting with `#` are skipped.
+pub fn run_script(
+    board: &BoardDescription,
+    report: &Report,
+    indexed_locations: &HashMap<usize, Position>,
+    script: &str,
+) {
+    run_commands(board, report, indexed_locations, script.as_bytes(), true);
+}
+
+/// Runs the commands read from `input` until `quit` or its end. With `echo`, prints every command before running it.
+fn run_commands(
+    board: &BoardDescription,
+    report: &Report,
+    indexed_locations: &HashMap<usize, Position>,
+    input: impl BufRead,
+    echo: bool,
+) {
+    let eqns = &report.equations;
+    let mut basis = eqns.kernel_basis();
+    kernel::reduce_basis(&mut basis);
+    let var_of: HashMap<Position, usize> = indexed_locations
+        .iter()
+        .map(|(&var, &pos)| (pos, var))
+        .collect();
+    let describe_var = |var: usize| {
+        let pos = indexed_locations[&var];
+        tr!(
+            "x_{} is the cell at row {}, column {}.",
+            var,
+            coords::row_number(pos.row, board.height()),
+            coords::col_number(pos.col)
+        )
+    };
+    let equation = |var: usize| match eqns.equation(var) {
+        None => tr!("x_{} is free.", var),
+        Some((terms, constant)) => {
+            let mut text = format!("x_{} = {}", var, constant);
+            for term in terms {
+                text.push_str(&format!(" + x_{}", term));
+            }
+            text
+        }
+    };
+    let mut played = board.clone();
+    let mut presses = 0;
+
+    for line in input.lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if echo {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            println!("> {}", line.trim());
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [] => {}
+            ["quit"] => return,
+            ["help"] => {
+                say!("eq <n>: the equation of the variable x_n. Variables number the cells from 0, row by row.");
+                say!("cell <row> <col>: the variable and equation of a cell.");
+                say!("kernel <n>: quiet pattern number n, counting from 1.");
+                say!("min: a solution with the fewest presses from the board as it stands.");
+                say!("press <row> <col>: presses a cell of the board, and shows the result.");
+                say!("quit: leaves.");
+            }
+            ["eq", var] => match var.parse::<usize>() {
+                Ok(var) if var < eqns.var_count() => {
+                    println!("{}", describe_var(var));
+                    println!("{}", equation(var));
+                }
+                _ => say!("There is no variable x_{}.", var),
+            },
+            ["cell", row, col] => {
+                match coords::parse_position(row, col, board.height(), board.width())
+                    .and_then(|pos| var_of.get(&pos))
+                {
+                    Some(&var) => {
+                        println!("{}", describe_var(var));
+                        println!("{}", equation(var));
+                    }
+                    None => say!("There is no cell at row {}, column {}.", row, col),
+                }
+            }
+            ["kernel", number] => match number
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| basis.get(number.checked_sub(1)?))
+            {
+                Some(vector) => print!(
+                    "{}",
+                    board.assign_assignment(Assignment::from_bits(vector), indexed_locations)
+                ),
+                None => say!(
+                    "There is no quiet pattern #{}. The board has {}.",
+                    number,
+                    basis.len()
+                ),
+            },
+            ["min"] => match solver::minimum_solution(&played) {
+                Some(solution) => say!(
+                    "Solution with {} button presses:\n{}",
+                    solution.count_ones(),
+                    solution
+                ),
+                None => say!("Board has no solutions."),
+            },
+            ["press", row, col] => {
+                match coords::parse_position(row, col, played.height(), played.width())
+                    .filter(|&pos| matches!(played.get(pos), Some(Some(_))))
+                {
+                    Some(pos) => {
+                        played.press(pos);
+                        presses += 1;
+                        print!("{}", played);
+                        if played.is_solved() {
+                            say!("Solved in {} presses!", presses);
+                        }
+                    }
+                    None => say!("There is no cell at row {}, column {}.", row, col),
+                }
+            }
+            _ => say!("Unknown command. Type \"help\" for the commands."),
+        }
+    }
+}
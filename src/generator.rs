@@ -0,0 +1,91 @@
+use crate::{
+    bit::Bit,
+    board::{BoardDescription, Cell, RigidSemantics},
+    grid::Position,
+    rng::Rng,
+};
+
+/// Creates a fully lit board of plain cells, `width` by `height`. Both must be non-zero.
+pub fn solved_board(width: usize, height: usize) -> BoardDescription {
+    BoardDescription::from_rows(vec![vec![Some(Cell::new_basic(Bit::On)); width]; height])
+        .expect("Board dimensions must be non-zero")
+}
+
+/// Copies `board` with every cell starting at its target value, so that it is already solved.
+pub fn solved(board: &BoardDescription) -> BoardDescription {
+    let mut solved = board.clone();
+    for pos in Position::iter_row_major(board.width(), board.height()) {
+        if let Some(cell) = &mut solved[pos] {
+            cell.starting_value = cell.target_value;
+        }
+    }
+    solved
+}
+
+/// Scrambles `board` by pressing `presses` distinct random cells, which are returned in reading order.
+///
+/// Starting from a solved board, pressing the same cells again solves it, so the result is always solvable in at most `presses`
+/// presses. Asking for more presses than the board has cells presses every cell.
+pub fn scramble(
+    board: &BoardDescription,
+    presses: usize,
+    rng: &mut Rng,
+) -> (BoardDescription, Vec<Position>) {
+    let mut cells: Vec<Position> = Position::iter_row_major(board.width(), board.height())
+        .filter(|&pos| board[pos].is_some())
+        .collect();
+    // Partial Fisher-Yates shuffle, keeping the first `presses` cells.
+    let presses = presses.min(cells.len());
+    for i in 0..presses {
+        let j = i + rng.below((cells.len() - i) as u64) as usize;
+        cells.swap(i, j);
+    }
+    let mut chosen = cells[..presses].to_vec();
+    chosen.sort_by_key(|pos| (pos.row, pos.col));
+
+    let mut scrambled = board.clone();
+    for &pos in &chosen {
+        scrambled.press(pos);
+    }
+    (scrambled, chosen)
+}
+
+/// Generates a random solvable puzzle of plain cells, `width` by `height`, by scrambling a solved board with `presses` presses.
+pub fn generate(
+    width: usize,
+    height: usize,
+    presses: usize,
+    rng: &mut Rng,
+) -> (BoardDescription, Vec<Position>) {
+    scramble(&solved_board(width, height), presses, rng)
+}
+
+/// Generates a random board of any supported variant, `width` by `height`: random holes, starting values, targets and modifiers,
+/// and random rigid semantics. Such boards are often unsolvable, so they exercise every path of the solver. Both dimensions must
+/// be non-zero.
+pub fn random_variant(width: usize, height: usize, rng: &mut Rng) -> BoardDescription {
+    let mut rows = vec![vec![None; width]; height];
+    for row in &mut rows {
+        for slot in row.iter_mut() {
+            if rng.below(8) == 0 {
+                continue;
+            }
+            let mut cell = Cell::new_basic(rng.next_bit());
+            cell.target_value = rng.next_bit();
+            match rng.below(8) {
+                0 => cell.apply_modifier('H'),
+                1 => cell.apply_modifier('V'),
+                2 => cell.apply_modifier('O'),
+                _ => false,
+            };
+            *slot = Some(cell);
+        }
+    }
+    // A board needs at least one cell.
+    if rows.iter().flatten().all(Option::is_none) {
+        rows[0][0] = Some(Cell::new_basic(rng.next_bit()));
+    }
+    let mut board = BoardDescription::from_rows(rows).expect("Board dimensions must be non-zero");
+    board.set_rigid_semantics(RigidSemantics::ALL[rng.below(4) as usize]);
+    board
+}
@@ -178,18 +178,25 @@ impl<V> Grid<V> {
         if width == 0 || height == 0 {
             return None;
         }
-        if lines.len() == height {
-            if lines.iter().all(move |l| l.len() == width) {
-                return Some(Grid {
-                    data: lines.into_iter().flat_map(Vec::into_iter).collect(),
-                    width,
-                    height,
-                });
-            }
+        if lines.len() == height && lines.iter().all(move |l| l.len() == width) {
+            return Some(Grid {
+                data: lines.into_iter().flat_map(Vec::into_iter).collect(),
+                width,
+                height,
+            });
         }
         None
     }
 
+    /// Returns the element at `pos`, or `None` if `pos` is out of bounds of `self`.
+    pub fn get(&self, pos: Position) -> Option<&V> {
+        if pos.is_in_bounds_of(self) {
+            Some(&self[pos])
+        } else {
+            None
+        }
+    }
+
     /// Counts the total number of elements in `self` that match the predicate `p`.
     pub fn count(&self, p: impl Fn(&V) -> bool) -> usize {
         self.data.iter().filter(|v| p(*v)).count()
@@ -0,0 +1,158 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+    bitslice::{self, MAX_TALLIED_SOLUTIONS},
+    board::BoardDescription,
+    grid::Position,
+    report::Report,
+    rng::Rng,
+};
+
+/// Shades for press frequencies, from never pressed to always pressed.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// How often every cell of a board is pressed across its solutions.
+#[derive(Clone, Debug)]
+pub struct Heatmap {
+    /// The number of considered solutions pressing each cell, by row. `None` for holes.
+    counts: Vec<Vec<Option<usize>>>,
+    /// The number of considered solutions.
+    solutions: usize,
+    /// The number of considered solutions with each number of presses, by number of presses.
+    press_counts: Vec<usize>,
+    /// Whether every solution was considered, rather than a random sample.
+    exhaustive: bool,
+}
+
+impl Heatmap {
+    /// Counts the presses of every cell across all solutions of `board`, or across `samples` random solutions if it has more than
+    /// `MAX_TALLIED_SOLUTIONS`. Returns `None` if `board` has no solutions.
+    pub fn new(board: &BoardDescription, samples: usize, rng: &mut Rng) -> Option<Self> {
+        let (matrix, indexed_locations) = board.to_matrix();
+        let eqns = Report::new(&matrix).equations;
+        let mut counts: Vec<Vec<Option<usize>>> = (0..board.height())
+            .map(|row| {
+                (0..board.width())
+                    .map(|col| board[Position::new(row, col)].map(|_| 0))
+                    .collect()
+            })
+            .collect();
+
+        let exhaustive = eqns
+            .solution_count()
+            .is_some_and(|total| total <= MAX_TALLIED_SOLUTIONS);
+        if exhaustive {
+            let tally = bitslice::tally(&eqns)?;
+            for (var, &presses) in tally.presses.iter().enumerate() {
+                let pos = indexed_locations[&var];
+                counts[pos.row][pos.col] = Some(presses as usize);
+            }
+            return Some(Heatmap {
+                counts,
+                solutions: tally.solutions as usize,
+                press_counts: tally.press_counts.iter().map(|&n| n as usize).collect(),
+                exhaustive,
+            });
+        }
+
+        let assignments: Vec<_> = (0..samples)
+            .map_while(|_| eqns.sample_solution(rng))
+            .collect();
+        if assignments.is_empty() {
+            return None;
+        }
+        let mut press_counts = vec![0; eqns.var_count() + 1];
+        for assignment in &assignments {
+            let solution = board.assign_assignment(assignment.clone(), &indexed_locations);
+            let presses = solution.presses();
+            for pos in &presses {
+                if let Some(count) = &mut counts[pos.row][pos.col] {
+                    *count += 1;
+                }
+            }
+            press_counts[presses.len()] += 1;
+        }
+        Some(Heatmap {
+            counts,
+            solutions: assignments.len(),
+            press_counts,
+            exhaustive,
+        })
+    }
+
+    /// The fraction of considered solutions that press the cell at `pos`. `None` for holes.
+    pub fn frequency(&self, pos: Position) -> Option<f64> {
+        self.counts[pos.row][pos.col].map(|count| count as f64 / self.solutions as f64)
+    }
+
+    /// Cells pressed by every considered solution.
+    pub fn mandatory(&self) -> Vec<Position> {
+        self.positions_with(|count| count == self.solutions)
+    }
+
+    /// Cells pressed by some, but not all, considered solutions.
+    pub fn interchangeable(&self) -> Vec<Position> {
+        self.positions_with(|count| count != 0 && count != self.solutions)
+    }
+
+    fn positions_with(&self, p: impl Fn(usize) -> bool) -> Vec<Position> {
+        let width = self.counts.first().map_or(0, Vec::len);
+        Position::iter_row_major(width, self.counts.len())
+            .filter(|pos| self.counts[pos.row][pos.col].is_some_and(&p))
+            .collect()
+    }
+
+    /// The number of considered solutions.
+    pub fn solutions(&self) -> usize {
+        self.solutions
+    }
+
+    /// The number of considered solutions with each number of presses, skipping numbers no solution has.
+    pub fn press_counts(&self) -> Vec<(usize, usize)> {
+        (0..self.press_counts.len())
+            .map(|presses| (presses, self.press_counts[presses]))
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+
+    /// Whether every solution was considered, rather than a random sample.
+    pub fn is_exhaustive(&self) -> bool {
+        self.exhaustive
+    }
+}
+
+/// Renders a grid of shades, followed by a grid of percentages. Holes are left blank.
+impl Display for Heatmap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in &self.counts {
+            for count in row {
+                match count {
+                    Some(count) => {
+                        let shade = if *count == 0 {
+                            0
+                        } else if *count == self.solutions {
+                            SHADES.len() - 1
+                        } else {
+                            1 + count * (SHADES.len() - 2) / self.solutions
+                        };
+                        write!(f, "{}", SHADES[shade])?;
+                    }
+                    None => write!(f, " ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)?;
+        for row in &self.counts {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|count| match count {
+                    Some(count) => format!("{:>3}%", count * 100 / self.solutions),
+                    None => "    ".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" ").trim_end())?;
+        }
+        Ok(())
+    }
+}
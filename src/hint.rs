@@ -0,0 +1,69 @@
+use crate::{
+    board::{BoardAssignment, BoardDescription},
+    coords,
+    i18n::tr,
+    solver,
+};
+
+/// How much of the solution a hint gives away, from least to most.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HintLevel {
+    /// How many presses remain.
+    Count,
+    /// Which row contains a needed press.
+    Row,
+    /// The exact cell to press.
+    Cell,
+}
+
+impl HintLevel {
+    /// The next, more revealing level, if any.
+    pub fn next(self) -> Option<Self> {
+        match self {
+            HintLevel::Count => Some(HintLevel::Row),
+            HintLevel::Row => Some(HintLevel::Cell),
+            HintLevel::Cell => None,
+        }
+    }
+}
+
+/// Progressively reveals a solution with the fewest presses for a board.
+pub struct Hint {
+    solution: BoardAssignment,
+}
+
+impl Hint {
+    /// Computes the hints for `board`. Returns `None` if `board` has no solutions.
+    pub fn new(board: &BoardDescription) -> Option<Self> {
+        solver::minimum_solution(board).map(|solution| Hint { solution })
+    }
+
+    /// Reveals `solution`, which should have as few presses as possible.
+    pub fn from_solution(solution: BoardAssignment) -> Self {
+        Hint { solution }
+    }
+
+    /// The solution being revealed.
+    pub fn solution(&self) -> &BoardAssignment {
+        &self.solution
+    }
+
+    /// Renders the hint at `level`. The row and cell hints point at the first needed press in reading order, numbered by
+    /// `coords`.
+    pub fn reveal(&self, level: HintLevel) -> String {
+        let presses = self.solution.presses();
+        let Some(first) = presses.first() else {
+            return tr!("The board is already solved.");
+        };
+        let row = coords::row_number(first.row, self.solution.height());
+        match level {
+            HintLevel::Count => tr!("{} presses remain.", presses.len()),
+            HintLevel::Row => tr!("Row {} contains a needed press.", row),
+            HintLevel::Cell => tr!(
+                "Press row {}, column {}.",
+                row,
+                coords::col_number(first.col)
+            ),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::fmt::Write;
+
+use wayout_solver::{
+    bit::Bit,
+    board::{BoardAssignment, BoardDescription},
+    grid::Position,
+    level::Level,
+    report::Report,
+    solver,
+};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h1, h2 { font-weight: normal; }
+table.board { border-collapse: collapse; margin: 0.5em 0 1.5em 0; }
+table.board td { width: 2em; height: 2em; text-align: center; border: 1px solid #888; font-size: 0.8em; }
+td.on { background: #f5d442; }
+td.off { background: #3b3b4f; color: #eee; }
+td.hole { border: none; background: none; }
+td.press { background: #d9534f; color: #fff; font-weight: bold; }
+td.idle { background: #eee; }
+table.metrics td { padding: 0.2em 1em 0.2em 0; }
+pre { background: #f4f4f4; padding: 0.5em; display: inline-block; }
+";
+
+/// Additional style of booklets, which keep every puzzle on one page when printed and start the solutions on a new page.
+const BOOKLET_STYLE: &str = "
+div.puzzle { display: inline-block; vertical-align: top; margin: 0 2em 1em 0; break-inside: avoid; }
+h2.appendix { break-before: page; }
+@media print { body { margin: 0; } }
+";
+
+/// Renders a standalone HTML page describing the whole solving process of `board`: the input board, the matrices and equations
+/// in `report`, a table of metrics, and each of `solutions` as a grid of presses. `solution_count` is `None` when the count overflows.
+/// With `collapse_solutions`, the solutions and the fewest presses among them stay hidden until the reader expands them.
+pub fn render_report(
+    board: &BoardDescription,
+    report: &Report,
+    solution_count: Option<u128>,
+    solutions: &[BoardAssignment],
+    collapse_solutions: bool,
+) -> String {
+    let mut html = String::new();
+    // Writing to a `String` cannot fail, so the results of `write!` are ignored throughout.
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Lights-Out report</title>\n<style>{}</style>\n</head>\n<body>\n",
+        STYLE
+    );
+    html.push_str("<h1>Lights-Out report</h1>\n");
+
+    html.push_str("<h2>Board</h2>\n");
+    html.push_str(&render_board(board));
+
+    html.push_str("<h2>Metrics</h2>\n<table class=\"metrics\">\n");
+    let variables = report.input.variable_cols();
+    let free_variables = report.equations.free_var_count();
+    let min_presses = solutions
+        .iter()
+        .map(BoardAssignment::count_ones)
+        .min()
+        .filter(|_| !collapse_solutions);
+    for (name, value) in [
+        ("Width", board.width().to_string()),
+        ("Height", board.height().to_string()),
+        ("Cells", variables.to_string()),
+        ("Rank", report.rank.to_string()),
+        ("Free variables", free_variables.to_string()),
+        (
+            "Solutions",
+            match solution_count {
+                Some(count) => count.to_string(),
+                None => format!("2^{}", free_variables),
+            },
+        ),
+        (
+            "Fewest presses shown",
+            min_presses.map_or("-".to_string(), |m| m.to_string()),
+        ),
+    ] {
+        let _ = writeln!(html, "<tr><td>{}</td><td>{}</td></tr>", name, value);
+    }
+    html.push_str("</table>\n");
+
+    let _ = write!(
+        html,
+        "<h2>Board matrix</h2>\n<pre>{}</pre>\n<h2>Reduced matrix</h2>\n<pre>{}</pre>\n<h2>Equations</h2>\n<pre>{}</pre>\n",
+        escape(&report.input.to_string()),
+        escape(&report.reduced.to_string()),
+        escape(&report.equations.to_string())
+    );
+
+    html.push_str("<h2>Solutions</h2>\n");
+    if collapse_solutions {
+        html.push_str("<details>\n<summary>Show solutions</summary>\n");
+    }
+    if solutions.is_empty() {
+        html.push_str("<p>Board has no solutions.</p>\n");
+    }
+    for (count, solution) in solutions.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<h3>Solution #{}, {} button presses</h3>",
+            count + 1,
+            solution.count_ones()
+        );
+        html.push_str(&render_assignment(solution));
+    }
+    if collapse_solutions {
+        html.push_str("</details>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Renders a standalone, printable HTML booklet of `levels`, each given with its name: every puzzle with its par up front, and
+/// a solution with the fewest presses of each in an appendix.
+pub fn render_booklet(title: &str, levels: &[(String, Level)]) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}{}</style>\n</head>\n<body>\n",
+        escape(title),
+        STYLE,
+        BOOKLET_STYLE
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", escape(title));
+
+    for (number, (name, level)) in levels.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<div class=\"puzzle\">\n<h3>Puzzle {}: {}</h3>",
+            number + 1,
+            escape(name)
+        );
+        html.push_str(&render_board(&level.board));
+        let _ = writeln!(
+            html,
+            "<p>Par: {}</p>\n</div>",
+            level.par.map_or("?".to_string(), |par| par.to_string())
+        );
+    }
+
+    html.push_str("<h2 class=\"appendix\">Solutions</h2>\n");
+    for (number, (name, level)) in levels.iter().enumerate() {
+        let _ = writeln!(
+            html,
+            "<div class=\"puzzle\">\n<h3>Puzzle {}: {}</h3>",
+            number + 1,
+            escape(name)
+        );
+        match solver::minimum_solution(&level.board) {
+            Some(solution) => {
+                html.push_str(&render_assignment(&solution));
+                let _ = writeln!(html, "<p>{} button presses</p>", solution.count_ones());
+            }
+            None => html.push_str("<p>Board has no solutions.</p>\n"),
+        }
+        html.push_str("</div>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Renders `board` as a table, coloring lit and unlit cells, and labeling cells with their modifiers.
+pub fn render_board(board: &BoardDescription) -> String {
+    let mut html = String::from("<table class=\"board\">\n");
+    for row in 0..board.height() {
+        html.push_str("<tr>");
+        for col in 0..board.width() {
+            match &board[Position::new(row, col)] {
+                Some(cell) => {
+                    let class = match cell.starting_value {
+                        Bit::On => "on",
+                        Bit::Off => "off",
+                    };
+                    let _ = write!(html, "<td class=\"{}\">{}</td>", class, cell.modifiers());
+                }
+                None => html.push_str("<td class=\"hole\"></td>"),
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Renders `assignment` as a table, highlighting the cells to press.
+pub fn render_assignment(assignment: &BoardAssignment) -> String {
+    let mut html = String::from("<table class=\"board\">\n");
+    for row in 0..assignment.height() {
+        html.push_str("<tr>");
+        for col in 0..assignment.width() {
+            match assignment[Position::new(row, col)] {
+                Some(Bit::On) => html.push_str("<td class=\"press\">&#x2022;</td>"),
+                Some(Bit::Off) => html.push_str("<td class=\"idle\"></td>"),
+                None => html.push_str("<td class=\"hole\"></td>"),
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+/// Escapes the characters of `text` that have a special meaning in HTML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,534 @@
+use std::{fmt::Display, sync::OnceLock};
+
+/// The languages user-facing messages can be shown in.
+///
+/// Messages are written in English at their call site and translated through `tr!`, which looks them up in the catalog of the
+/// current language. Adding a language means adding a variant here, its code in `Language::from_code`, and a catalog in `catalog`.
+/// Messages missing from a catalog are shown in English.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+impl Language {
+    /// Finds the language for a code such as `es`, `es_MX` or `es_ES.UTF-8`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.split(['_', '-', '.']).next().unwrap_or("");
+        match code.to_ascii_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// Picks the language from `$WAYOUT_LANG`, then `$LANG`, defaulting to English.
+    fn from_env() -> Self {
+        ["WAYOUT_LANG", "LANG"]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find_map(|code| Language::from_code(&code))
+            .unwrap_or(Language::English)
+    }
+
+    /// The translations of English messages into this language.
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::Spanish => SPANISH,
+        }
+    }
+}
+
+/// Sets the language of every message shown from now on. Only the first call has any effect.
+pub fn set_language(language: Language) {
+    let _ = LANGUAGE.set(language);
+}
+
+/// The language messages are currently shown in.
+pub fn language() -> Language {
+    *LANGUAGE.get_or_init(Language::from_env)
+}
+
+/// Looks up the translation of the English message `msg` in the current language, falling back to `msg` itself.
+pub fn translate(msg: &'static str) -> &'static str {
+    language()
+        .catalog()
+        .iter()
+        .find(|(english, _)| *english == msg)
+        .map_or(msg, |(_, translated)| translated)
+}
+
+/// Fills the placeholders of `template` with `args`. `{}` takes the next argument in order, and `{n}` takes the argument at index `n`,
+/// which lets translations reorder them.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut res = String::new();
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        res.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let inside = &rest[start + 1..start + len];
+        let index = if inside.is_empty() {
+            next += 1;
+            Some(next - 1)
+        } else {
+            inside.parse::<usize>().ok()
+        };
+        match index.and_then(|i| args.get(i)) {
+            Some(arg) => res.push_str(&arg.to_string()),
+            None => res.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    res.push_str(rest);
+    res
+}
+
+/// Translates an English message literal into the current language, filling its placeholders with the remaining arguments.
+#[macro_export]
+macro_rules! tr {
+    ($msg:expr) => {
+        $crate::i18n::translate($msg).to_string()
+    };
+    ($msg:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::translate($msg), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+
+/// Prints a translated message on its own line. Takes the same arguments as `tr!`.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        println!("{}", $crate::i18n::tr!($($arg)*))
+    };
+}
+
+pub use say;
+pub use tr;
+
+static SPANISH: &[(&str, &str)] = &[
+    ("Welcome to the Lights-Out solver!", "¡Bienvenido al solucionador de Lights-Out!"),
+    ("Enter your board line by line, using 0 for an unlit cell, 1 for a lit cell, and space for a cell without a light. When finished, type 'done' on its own line.", "Introduce tu tablero línea por línea, usando 0 para una celda apagada, 1 para una celda encendida y un espacio para una celda sin luz. Al terminar, escribe 'listo' en su propia línea."),
+    ("done", "listo"),
+    ("yes", "sí"),
+    ("no", "no"),
+    ("all", "todas"),
+    ("min", "min"),
+    ("Error reading line: {}", "Error al leer la línea: {}"),
+    ("Error parsing board with text {}.\nError: {}", "Error al interpretar el tablero con el texto {}.\nError: {}"),
+    ("Does board contain modifiers? [yes/no]", "¿Tiene el tablero modificadores? [sí/no]"),
+    ("Error reading yes/no answer: {}", "Error al leer la respuesta sí/no: {}"),
+    ("Enter modifiers as a grid of spaces, \"H\"s, \"V\"s and \"O\"s.", "Introduce los modificadores como una cuadrícula de espacios, \"H\", \"V\" y \"O\"."),
+    ("Modifier applied to empty cell!", "¡Modificador aplicado a una celda vacía!"),
+    ("Could not read line: {}", "No se pudo leer la línea: {}"),
+    ("Could not read line.", "No se pudo leer la línea."),
+    ("Computed board matrix:\n{}", "Matriz del tablero:\n{}"),
+    ("Reduced board matrix:\n{}", "Matriz reducida del tablero:\n{}"),
+    ("Board has {} independent quiet patterns.", "El tablero tiene {} patrones silenciosos independientes."),
+    ("Quiet pattern #{}:\n{}", "Patrón silencioso #{}:\n{}"),
+    ("Board has no solutions.", "El tablero no tiene soluciones."),
+    ("Board has {} solutions.", "El tablero tiene {} soluciones."),
+    ("Board has 2^{} solutions.", "El tablero tiene 2^{} soluciones."),
+    ("Board has no solution #{}.", "El tablero no tiene la solución #{}."),
+    ("Solution #{}:\n{}", "Solución #{}:\n{}"),
+    ("Random solution #{}:\n{}", "Solución aleatoria #{}:\n{}"),
+    ("Show all solutions, only one with minimum presses, or only one with the lowest total cost? (all/min/cost)", "¿Mostrar todas las soluciones, solo una con el mínimo de pulsaciones, o solo una con el menor coste total? (todas/min/coste)"),
+    ("Please input either \"all\", \"min\" or \"cost\"", "Por favor, introduce \"todas\", \"min\" o \"coste\""),
+    ("cost", "coste"),
+    ("Error reading \"all\" or \"min\" value. Defaulting to \"min\".", "Error al leer \"todas\" o \"min\". Se usará \"min\"."),
+    ("Solution #{}, {} button presses:\n{}", "Solución #{}, {} pulsaciones:\n{}"),
+    ("Solution with {} button presses:\n{}", "Solución con {} pulsaciones:\n{}"),
+    ("Could not find a solution despite there being solutions. Please send board configuration to developer for debugging.", "No se encontró una solución a pesar de que existen. Por favor, envía la configuración del tablero al desarrollador."),
+    ("Report written to {}.", "Informe escrito en {}."),
+    ("Error writing report to {}: {}", "Error al escribir el informe en {}: {}"),
+    ("Simulated presses light up every cell.", "Las pulsaciones simuladas encienden todas las celdas."),
+    ("Warning: simulated presses do not light up every cell!", "¡Aviso: las pulsaciones simuladas no encienden todas las celdas!"),
+    ("Final board written to {}.", "Tablero final escrito en {}."),
+    ("Error writing final board to {}: {}", "Error al escribir el tablero final en {}: {}"),
+    ("Boards are identical.", "Los tableros son idénticos."),
+    ("Board has more than {} free variables, too many to decode.", "El tablero tiene más de {} variables libres, demasiadas para decodificar."),
+    ("Built decoder with {} free variables.", "Decodificador creado con {} variables libres."),
+    ("{}: {} button presses:\n{}", "{}: {} pulsaciones:\n{}"),
+    ("{}: board has no solutions.", "{}: el tablero no tiene soluciones."),
+    ("Board has more than {} free variables, too many for a template.", "El tablero tiene más de {} variables libres, demasiadas para una plantilla."),
+    ("Saved template \"{}\" with {} free variables.", "Plantilla \"{}\" guardada con {} variables libres."),
+    ("No saved templates.", "No hay plantillas guardadas."),
+    ("Board does not match the size and holes of the template.", "El tablero no coincide con el tamaño y los huecos de la plantilla."),
+    ("Error reading board file {}: {}", "Error al leer el archivo de tablero {}: {}"),
+    ("Error in board file {}: {}", "Error en el archivo de tablero {}: {}"),
+    ("Invalid line. Please only enter 0's 1's and spaces.", "Línea no válida. Introduce solo 0, 1 y espacios."),
+    ("Please input either \"yes\" or \"no\". [yes/no]", "Por favor, introduce \"sí\" o \"no\". [sí/no]"),
+    ("Unknown command \"{}\". Available commands: {}.", "Comando desconocido \"{}\". Comandos disponibles: {}."),
+    ("Show a more revealing hint? [yes/no]", "¿Mostrar una pista más reveladora? [sí/no]"),
+    ("{} presses remain.", "Quedan {} pulsaciones."),
+    ("Row {} contains a needed press.", "La fila {} contiene una pulsación necesaria."),
+    ("Press row {}, column {}.", "Pulsa la fila {}, columna {}."),
+    ("The board is already solved.", "El tablero ya está resuelto."),
+    ("Daily puzzle for {}:", "Puzzle diario del {}:"),
+    ("Share code: {}", "Código para compartir: {}"),
+    ("Par is unknown for this board.", "El par de este tablero es desconocido."),
+    ("Par is {}. You beat it, which should not be possible!", "El par es {}. Lo has superado, ¡lo que no debería ser posible!"),
+    ("Par is {}. You matched it!", "El par es {}. ¡Lo has igualado!"),
+    ("Par is {}. You were {} over.", "El par es {}. Te has pasado por {}."),
+    ("Invalid size \"{}\". Sizes are written as <width>x<height>.", "Tamaño no válido \"{}\". Los tamaños se escriben como <ancho>x<alto>."),
+    ("Invalid number of presses \"{}\".", "Número de pulsaciones no válido \"{}\"."),
+    ("Invalid seed \"{}\".", "Semilla no válida \"{}\"."),
+    ("quit", "salir"),
+    ("Press a cell by entering its row and column, or type \"quit\" to give up.", "Pulsa una celda introduciendo su fila y columna, o escribe \"salir\" para rendirte."),
+    ("Please enter a row and a column, such as \"2 3\".", "Introduce una fila y una columna, como \"2 3\"."),
+    ("There is no cell at row {}, column {}.", "No hay ninguna celda en la fila {}, columna {}."),
+    ("Diverse solution #{}, {} button presses:\n{}", "Solución diversa #{}, {} pulsaciones:\n{}"),
+    ("The number of samples must be a positive integer.", "El número de muestras debe ser un entero positivo."),
+    ("Press frequencies across all {} solutions:", "Frecuencia de pulsación en las {} soluciones:"),
+    ("Press frequencies across {} random solutions:", "Frecuencia de pulsación en {} soluciones aleatorias:"),
+    ("{} cells are pressed by every solution, and {} only by some.", "{} celdas se pulsan en todas las soluciones, y {} solo en algunas."),
+    ("Board has {} unknown cells.", "El tablero tiene {} celdas desconocidas."),
+    ("u{} is at row {}, column {}.", "u{} está en la fila {}, columna {}."),
+    ("The board is not solvable for any values of the unknown cells.", "El tablero no tiene solución para ningún valor de las celdas desconocidas."),
+    ("The board is solvable for every value of the unknown cells.", "El tablero tiene solución para cualquier valor de las celdas desconocidas."),
+    ("The board is solvable exactly when:", "El tablero tiene solución exactamente cuando:"),
+    ("{} of the 2^{} combinations of values are solvable.", "{} de las 2^{} combinaciones de valores tienen solución."),
+    ("Enter the value of the cell at row {}, column {}, or ? to leave it unknown. [0/1/?]", "Introduce el valor de la celda en la fila {}, columna {}, o ? para dejarla desconocida. [0/1/?]"),
+    ("Please input either \"0\", \"1\" or \"?\".", "Por favor, introduce \"0\", \"1\" o \"?\"."),
+    ("The interval must be a positive number of milliseconds.", "El intervalo debe ser un número positivo de milisegundos."),
+    ("Watching {} for changes. Press Ctrl-C to stop.", "Vigilando {} en busca de cambios. Pulsa Ctrl-C para parar."),
+    ("No solution.", "Sin solución."),
+    ("Already solved.", "Ya resuelto."),
+    ("{} presses: {}", "{} pulsaciones: {}"),
+    ("Board schema version {}, result schema version {}. Available schemas: board, result.", "Versión del esquema de tableros {}, versión del esquema de resultados {}. Esquemas disponibles: board, result."),
+    ("Unknown schema \"{}\". Available schemas: board, result.", "Esquema desconocido \"{}\". Esquemas disponibles: board, result."),
+    ("The plain format cannot express the target of this board.", "El formato de texto no puede expresar el objetivo de este tablero."),
+    ("Unknown matrix format \"{}\". Available formats: text, market.", "Formato de matriz desconocido \"{}\". Formatos disponibles: text, market."),
+    ("Error reading matrix file {}: {}", "Error al leer el archivo de matriz {}: {}"),
+    ("Error in matrix file {}: {}", "Error en el archivo de matriz {}: {}"),
+    ("Rank {}, with {} variables and {} free variables.", "Rango {}, con {} variables y {} variables libres."),
+    ("System has no solutions.", "El sistema no tiene soluciones."),
+    ("System has {} solutions.", "El sistema tiene {} soluciones."),
+    ("System has 2^{} solutions.", "El sistema tiene 2^{} soluciones."),
+    ("The limit must be a non-negative integer.", "El límite debe ser un entero no negativo."),
+    ("Kernel basis:", "Base del núcleo:"),
+    ("Showing the first {}:", "Mostrando las primeras {}:"),
+    ("Reduced matrix:", "Matriz reducida:"),
+    ("Board matrix is not invertible, as the board has free variables.", "La matriz del tablero no es invertible, ya que el tablero tiene variables libres."),
+    ("Pivot columns: {}", "Columnas pivote: {}"),
+    ("Redundant rows: {}", "Filas redundantes: {}"),
+    ("row {}, column {}", "fila {}, columna {}"),
+    ("Every press toggles an even number of these cells, but an odd number of them must be toggled: {}.", "Cada pulsación cambia un número par de estas celdas, pero un número impar de ellas debe cambiar: {}."),
+    ("Solutions by number of presses:", "Soluciones por número de pulsaciones:"),
+    ("{} presses: {}", "{} pulsaciones: {}"),
+    ("The report will not list the solutions.", "El informe no incluirá las soluciones."),
+    ("Solution with {} button presses:\n{}", "Solución con {} pulsaciones de botón:\n{}"),
+    ("This solution was improved greedily, and may not have the fewest presses.", "Esta solución se mejoró de forma voraz, y puede no tener el mínimo de pulsaciones."),
+    ("The solution number must be a positive integer.", "El número de solución debe ser un entero positivo."),
+    ("The cell at {} is free: solutions may press it or not, and this one presses it.", "La celda en {} es libre: las soluciones pueden pulsarla o no, y esta la pulsa."),
+    ("The cell at {} is free: solutions may press it or not, and this one does not.", "La celda en {} es libre: las soluciones pueden pulsarla o no, y esta no la pulsa."),
+    ("The cell at {} is determined by the free cells, whose values the solution chose:", "La celda en {} está determinada por las celdas libres, cuyos valores eligió la solución:"),
+    ("This equation is the sum of the equations of the cells at {}. Each says that the presses toggling that cell add up to the toggle it needs.", "Esta ecuación es la suma de las ecuaciones de las celdas en {}. Cada una dice que las pulsaciones que cambian esa celda suman el cambio que necesita."),
+    ("So the cell is pressed.", "Así que la celda se pulsa."),
+    ("So the cell is not pressed.", "Así que la celda no se pulsa."),
+    ("Every solution presses the cell at row {}, column {}.", "Todas las soluciones pulsan la celda en la fila {}, columna {}."),
+    ("Solution #{} becomes solution #{} in {} steps.", "La solución #{} se convierte en la solución #{} en {} pasos."),
+    ("Start:\n{}", "Inicio:\n{}"),
+    ("Step {}, toggling the quiet pattern of the free cell at row {}, column {}:\n{}", "Paso {}, alternando el patrón silencioso de la celda libre en la fila {}, columna {}:\n{}"),
+    ("Board matrix", "Matriz del tablero"),
+    ("Reduced board matrix", "Matriz reducida del tablero"),
+    ("{}, {} by {}, {} active entries ({}%):", "{}, {} por {}, {} entradas activas ({}%):"),
+    ("Rows count from {} at the bottom, and columns from {} at the left.", "Las filas se cuentan desde {} abajo, y las columnas desde {} a la izquierda."),
+    ("Rows count from {} at the top, and columns from {} at the left.", "Las filas se cuentan desde {} arriba, y las columnas desde {} a la izquierda."),
+    ("Unknown board format. Available formats: text, toml, json, code.", "Formato de tablero desconocido. Formatos disponibles: text, toml, json, code."),
+    ("Converting several boards needs --out-dir.", "Convertir varios tableros requiere --out-dir."),
+    ("Converted {} to {}.", "{} convertido a {}."),
+    ("Error writing {}: {}", "Error al escribir {}: {}"),
+    ("The board was scrambled with {} presses.", "El tablero se desordenó con {} pulsaciones."),
+    ("The board was scrambled by pressing {}.", "El tablero se desordenó pulsando {}."),
+    ("Presses: {}. Time: {}.", "Pulsaciones: {}. Tiempo: {}."),
+    ("Solved in {} presses, taking {}!", "¡Resuelto en {} pulsaciones, en {}!"),
+    ("Every level of this pack is completed.", "Todos los niveles de este paquete están completados."),
+    ("Level {}:", "Nivel {}:"),
+    ("New personal best!", "¡Nuevo récord personal!"),
+    ("{}: {} presses, {}.", "{}: {} pulsaciones, {}."),
+    ("{} levels completed.", "{} niveles completados."),
+    ("{}: {} presses (par {}), {}.", "{}: {} pulsaciones (par {}), {}."),
+    ("{}: not completed.", "{}: sin completar."),
+    ("{} of {} levels completed.", "{} de {} niveles completados."),
+    ("Error reading target file {}: {}", "Error al leer el archivo de objetivo {}: {}"),
+    ("Stage {}, {} button presses:\n{}", "Etapa {}, {} pulsaciones:\n{}"),
+    ("{} presses in total, in this order: {}.", "{} pulsaciones en total, en este orden: {}."),
+    (
+        "Unknown rigid semantics \"{}\". Available: shielded, frozen, unpressable, inert.",
+        "Semántica de celdas rígidas desconocida \"{}\". Disponibles: shielded, frozen, unpressable, inert.",
+    ),
+    (
+        "The cell at {} cannot be pressed, so no solution presses it.",
+        "La celda en {} no se puede pulsar, así que ninguna solución la pulsa.",
+    ),
+    (
+        "Board matrix is not square, as some cells cannot be pressed, so it has no inverse.",
+        "La matriz del tablero no es cuadrada, ya que algunas celdas no se pueden pulsar, así que no tiene inversa.",
+    ),
+    ("{}: brute force and linear algebra agree.", "{}: la fuerza bruta y el álgebra lineal coinciden."),
+    ("{}: {} discrepancies.", "{}: {} discrepancias."),
+    (
+        "These presses solve the board, but linear algebra missed them:",
+        "Estas pulsaciones resuelven el tablero, pero el álgebra lineal no las encontró:",
+    ),
+    (
+        "Linear algebra gave these presses, but they do not solve the board:",
+        "El álgebra lineal dio estas pulsaciones, pero no resuelven el tablero:",
+    ),
+    ("Reproducer:\n{}", "Para reproducirlo:\n{}"),
+    ("no presses", "ninguna pulsación"),
+    ("{} components:", "{} componentes:"),
+    (
+        "Component {} has no solutions, so neither does the board.",
+        "El componente {} no tiene soluciones, así que el tablero tampoco.",
+    ),
+    (
+        "Should every light end up off instead of on? [yes/no]",
+        "¿Deben acabar todas las luces apagadas en lugar de encendidas? [sí/no]",
+    ),
+    ("Error reading region file {}: {}", "Error al leer el archivo de región {}: {}"),
+    ("Error in region file {}: {}", "Error en el archivo de región {}: {}"),
+    (
+        "Every solution presses a cell outside of the region.",
+        "Todas las soluciones pulsan alguna celda fuera de la región.",
+    ),
+    ("{} solutions only press cells in the region.", "{} soluciones solo pulsan celdas de la región."),
+    ("x_{} is the cell at row {}, column {}.", "x_{} es la celda de la fila {}, columna {}."),
+    ("x_{} is free.", "x_{} es libre."),
+    (
+        "Exploring the system. Type \"help\" for the commands, or \"quit\" to leave.",
+        "Explorando el sistema. Escribe \"help\" para ver los comandos, o \"quit\" para salir.",
+    ),
+    (
+        "eq <n>: the equation of the variable x_n. Variables number the cells from 0, row by row.",
+        "eq <n>: la ecuación de la variable x_n. Las variables numeran las celdas desde 0, fila a fila.",
+    ),
+    ("cell <row> <col>: the variable and equation of a cell.", "cell <fila> <col>: la variable y la ecuación de una celda."),
+    ("kernel <n>: quiet pattern number n, counting from 1.", "kernel <n>: el patrón silencioso número n, contando desde 1."),
+    (
+        "min: a solution with the fewest presses from the board as it stands.",
+        "min: una solución con las menos pulsaciones desde el tablero tal como está.",
+    ),
+    (
+        "press <row> <col>: presses a cell of the board, and shows the result.",
+        "press <fila> <col>: pulsa una celda del tablero y muestra el resultado.",
+    ),
+    ("quit: leaves.", "quit: sale."),
+    ("There is no variable x_{}.", "No existe la variable x_{}."),
+    ("There is no quiet pattern #{}. The board has {}.", "No existe el patrón silencioso #{}. El tablero tiene {}."),
+    ("Solved in {} presses!", "¡Resuelto en {} pulsaciones!"),
+    ("Unknown command. Type \"help\" for the commands.", "Comando desconocido. Escribe \"help\" para ver los comandos."),
+    (
+        "Warning: in {}, {} pairs of cells only affect each other one way. If modifiers should be mutual, check the modifier grid.",
+        "Aviso: en {}, {} pares de celdas solo se afectan en un sentido. Si los modificadores deben ser mutuos, revisa la cuadrícula de modificadores.",
+    ),
+    ("Pressing {} toggles {}, but not the other way around.", "Pulsar {} cambia {}, pero no al revés."),
+    (
+        "The count and the maximum size must be positive integers.",
+        "La cantidad y el tamaño máximo deben ser enteros positivos.",
+    ),
+    ("Invalid seed.", "Semilla no válida."),
+    ("Board #{} failed: {} Saved to {}.", "El tablero #{} falló: {} Guardado en {}."),
+    ("Board #{} failed: {} Could not save it: {}", "El tablero #{} falló: {} No se pudo guardar: {}"),
+    ("{} boards checked, {} failed.", "{} tableros comprobados, {} fallaron."),
+    ("random #{}", "aleatorio #{}"),
+    (
+        "Packed boards take {}% of the bytes of their board files.",
+        "Los tableros empaquetados ocupan el {}% de los bytes de sus archivos de tablero.",
+    ),
+    (
+        "Board has too many solutions to list every one with the fewest presses.",
+        "El tablero tiene demasiadas soluciones para listar todas las que usan menos pulsaciones.",
+    ),
+    (
+        "Board has {} solutions with the fewest presses.",
+        "El tablero tiene {} soluciones con el mínimo de pulsaciones.",
+    ),
+    (
+        "Board has a single solution, so there is nothing to choose.",
+        "El tablero tiene una sola solución, así que no hay nada que elegir.",
+    ),
+    (
+        "Board has {} choice cells, marked with letters. Every other cell follows from them.",
+        "El tablero tiene {} celdas de elección, marcadas con letras. Todas las demás celdas dependen de ellas.",
+    ),
+    (
+        "Type \"help\" for the commands, or \"quit\" to leave.",
+        "Escribe \"help\" para ver los comandos, o \"quit\" para salir.",
+    ),
+    (
+        "flip <choice>: presses a choice cell if it is not pressed, and the other way around.",
+        "flip <elección>: pulsa una celda de elección si no está pulsada, y al revés.",
+    ),
+    (
+        "set <choice> <0|1>: picks whether to press a choice cell.",
+        "set <elección> <0|1>: elige si pulsar una celda de elección.",
+    ),
+    (
+        "reset: presses no choice cell.",
+        "reset: no pulsa ninguna celda de elección.",
+    ),
+    (
+        "map: shows where the choice cells are.",
+        "map: muestra dónde están las celdas de elección.",
+    ),
+    (
+        "Choice cells are named by their letter, or by their number counting from 1.",
+        "Las celdas de elección se nombran por su letra, o por su número contando desde 1.",
+    ),
+    (
+        "There is no choice cell {}.",
+        "No hay ninguna celda de elección {}.",
+    ),
+    (
+        "Values are 0 or 1.",
+        "Los valores son 0 o 1.",
+    ),
+    (
+        "Fragile cells, where a wrong reading changes whether the board is solvable, are marked with \"!\":",
+        "Las celdas frágiles, donde una lectura errónea cambia si el tablero tiene solución, están marcadas con \"!\":",
+    ),
+    ("{} of {} cells are fragile.", "{} de {} celdas son frágiles."),
+    ("Error reading equations file {}: {}", "Error al leer el archivo de ecuaciones {}: {}"),
+    ("Error in equations file {}: {}", "Error en el archivo de ecuaciones {}: {}"),
+    ("{} variables and {} free variables.", "{} variables y {} variables libres."),
+    ("Error reading rule file {}: {}", "Error al leer el archivo de reglas {}: {}"),
+    ("Error in rule file {}: {}", "Error en el archivo de reglas {}: {}"),
+    ("The board has {} cells, but the {} backend is limited to {}.", "El tablero tiene {} casillas, pero el motor {} se limita a {}."),
+    ("{}: the board has {} cells, but the {} backend is limited to {}.", "{}: el tablero tiene {} casillas, pero el motor {} se limita a {}."),
+    ("Backend", "Motor"),
+    ("Solvable", "Resoluble"),
+    ("Presses", "Pulsaciones"),
+    ("Time", "Tiempo"),
+    ("The backends disagree on whether the board has solutions.", "Los motores no coinciden en si el tablero tiene soluciones."),
+    ("The backends disagree on the fewest presses.", "Los motores no coinciden en el mínimo de pulsaciones."),
+    ("The backends agree on whether the board has solutions, but an approximate solution has more presses.", "Los motores coinciden en si el tablero tiene soluciones, pero una solución aproximada tiene más pulsaciones."),
+    ("The backends agree.", "Los motores coinciden."),
+    ("Enter the cost of pressing each cell line by line, as a digit per cell and a space for each cell without a light. When finished, type 'done' on its own line.", "Introduce el coste de pulsar cada casilla línea por línea, con un dígito por casilla y un espacio por cada casilla sin luz. Al terminar, escribe 'listo' en una línea aparte."),
+    ("Error reading cost file {}: {}", "Error al leer el archivo de costes {}: {}"),
+    ("Error in cost file {}: {}", "Error en el archivo de costes {}: {}"),
+    ("Solution with a total cost of {}, {} button presses:\n{}", "Solución con un coste total de {}, {} pulsaciones:\n{}"),
+    ("Cost of each press:\n{}", "Coste de cada pulsación:\n{}"),
+    ("This solution was improved greedily, and may not have the lowest total cost.", "Esta solución se mejoró de forma voraz, y puede no tener el menor coste total."),
+    ("Board is already solved, no presses are needed.", "El tablero ya está resuelto, no hace falta pulsar nada."),
+    ("The solutions file will not list the solutions.", "El archivo de soluciones no listará las soluciones."),
+    ("Solutions written to {}.", "Soluciones escritas en {}."),
+    ("Error writing solutions to {}: {}", "Error al escribir las soluciones en {}: {}"),
+    ("Saved target \"{}\".", "Objetivo \"{}\" guardado."),
+    ("No saved targets.", "No hay objetivos guardados."),
+    ("Error in target \"{}\": {}", "Error en el objetivo \"{}\": {}"),
+    ("Cropped the margins of holes off the board: {} rows at the top, {} at the bottom, {} columns at the left and {} at the right. Coordinates still count from the board as entered.", "Se recortaron los márgenes de huecos del tablero: {} filas arriba, {} abajo, {} columnas a la izquierda y {} a la derecha. Las coordenadas siguen contando desde el tablero tal como se introdujo."),
+    ("Fewest presses from the starting state to each notable state:", "Mínimo de pulsaciones desde el estado inicial hasta cada estado notable:"),
+    ("Error reading script file {}: {}", "Error al leer el archivo de guion {}: {}"),
+    (
+        "Ignoring unknown modifier '{}' at row {}, column {}.",
+        "Se ignora el modificador desconocido '{}' en la fila {}, columna {}.",
+    ),
+    (
+        "Unknown modifier '{}' at row {}, column {}. Did you mean '{}'? [yes/no]",
+        "Modificador desconocido '{}' en la fila {}, columna {}. ¿Quería decir '{}'? [yes/no]",
+    ),
+    ("Ignoring '{}'.", "Se ignora '{}'."),
+    ("The number of samples must be a positive integer.", "El número de muestras debe ser un entero positivo."),
+    ("Error writing kernel weights to {}: {}", "Error al escribir los pesos del núcleo en {}: {}"),
+    (
+        "Wrote {} sampled elements of the kernel of dimension {} to {}.",
+        "Se escribieron {} elementos muestreados del núcleo de dimensión {} en {}.",
+    ),
+    (
+        "Wrote all {} elements of the kernel of dimension {} to {}.",
+        "Se escribieron los {} elementos del núcleo de dimensión {} en {}.",
+    ),
+    ("Error reading modifiers file {}: {}", "Error al leer el archivo de modificadores {}: {}"),
+    ("Error in modifiers file {}: {}", "Error en el archivo de modificadores {}: {}"),
+    (
+        "Board has {} free variables, too many to list every solution.",
+        "El tablero tiene {} variables libres, demasiadas para listar todas las soluciones.",
+    ),
+    ("Error reading presses file {}: {}", "Error al leer el archivo de pulsaciones {}: {}"),
+    ("Error in presses file {}: {}", "Error en el archivo de pulsaciones {}: {}"),
+    ("The {} presses solve the board.", "Las {} pulsaciones resuelven el tablero."),
+    (
+        "The {} presses do not solve the board. They leave it as:\n{}",
+        "Las {} pulsaciones no resuelven el tablero. Lo dejan así:\n{}",
+    ),
+    ("Board #{}: {}", "Tablero #{}: {}"),
+    (
+        "Board #{} has par {}, but its fewest presses are {}.",
+        "El tablero #{} tiene par {}, pero sus pulsaciones mínimas son {}.",
+    ),
+    (
+        "Board #{} has par {}, but its fewest presses could not be found.",
+        "El tablero #{} tiene par {}, pero no se pudieron hallar sus pulsaciones mínimas.",
+    ),
+    (
+        "{} boards checked, {} could not be read, {} disagree with their par.",
+        "{} tableros comprobados, {} no se pudieron leer, {} no coinciden con su par.",
+    ),
+    ("Simulated presses turn off every cell.", "Las pulsaciones simuladas apagan todas las celdas."),
+    ("Warning: simulated presses do not turn off every cell!", "¡Aviso: las pulsaciones simuladas no apagan todas las celdas!"),
+    ("Simulated presses bring every cell to its target.", "Las pulsaciones simuladas llevan cada celda a su objetivo."),
+    ("Warning: simulated presses do not bring every cell to its target!", "¡Aviso: las pulsaciones simuladas no llevan cada celda a su objetivo!"),
+    ("Write the final board to a .toml file instead.", "Escriba el tablero final en un archivo .toml."),
+    ("Invalid solution number \"{}\".", "Número de solución no válido \"{}\"."),
+    ("Invalid memory budget \"{}\".", "Presupuesto de memoria no válido \"{}\"."),
+    (
+        "Unknown strategy \"{}\". Available strategies: auto, enumerate, coset, greedy, search.",
+        "Estrategia desconocida \"{}\". Estrategias disponibles: auto, enumerate, coset, greedy, search.",
+    ),
+    (
+        "Unknown backend \"{}\". Available backends: gaussian, brute-force.",
+        "Motor desconocido \"{}\". Motores disponibles: gaussian, brute-force.",
+    ),
+    (
+        "Unknown pivot strategy \"{}\". Available strategies: first, sparsest, heaviest.",
+        "Estrategia de pivote desconocida \"{}\". Estrategias disponibles: first, sparsest, heaviest.",
+    ),
+    (
+        "Unknown solution format \"{}\". Available formats: grid, presses.",
+        "Formato de solución desconocido \"{}\". Formatos disponibles: grid, presses.",
+    ),
+    (
+        "Unknown output format \"{}\". Available formats: text, json.",
+        "Formato de salida desconocido \"{}\". Formatos disponibles: text, json.",
+    ),
+    ("Unknown option \"{}\".", "Opción desconocida \"{}\"."),
+    (
+        "Options \"--all\" and \"--any\" cannot be used together.",
+        "Las opciones \"--all\" y \"--any\" no se pueden usar juntas.",
+    ),
+    (
+        "Options \"--input\" and \"--board\" cannot be used together.",
+        "Las opciones \"--input\" y \"--board\" no se pueden usar juntas.",
+    ),
+    (
+        "Option \"--output json\" needs a board from \"--input\" or \"--board\".",
+        "La opción \"--output json\" necesita un tablero de \"--input\" o \"--board\".",
+    ),
+    (
+        "Unknown spoiler style \"{}\". Available styles: rot13, block.",
+        "Estilo de spoiler desconocido \"{}\". Estilos disponibles: rot13, block.",
+    ),
+    ("Option \"{}\" requires a value.", "La opción \"{}\" necesita un valor."),
+    ("Invalid value \"{}\" for option \"{}\".", "Valor no válido \"{}\" para la opción \"{}\"."),
+    (
+        "Invalid index \"{}\". Rows and columns count from 0 or 1.",
+        "Índice no válido \"{}\". Las filas y columnas se cuentan desde 0 o 1.",
+    ),
+    (
+        "Invalid origin \"{}\". Rows count from the top or the bottom.",
+        "Origen no válido \"{}\". Las filas se cuentan desde arriba o desde abajo.",
+    ),
+    ("Board", "Tablero"),
+    ("Text", "Texto"),
+    ("Packed", "Empaquetado"),
+    ("Share code", "Código para compartir"),
+    ("Solution text", "Solución en texto"),
+    ("Solution packed", "Solución empaquetada"),
+];
@@ -0,0 +1,469 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::HashMap,
+};
+
+use crate::{
+    bit::Bit,
+    matrix::{pack_row, packed_bit, unpack_row, WORD_BITS},
+    rng::Rng,
+};
+
+/// The largest kernel dimension `spectrum` lists every element for. Larger kernels are sampled.
+pub const MAX_SPECTRUM_NULLITY: usize = 16;
+
+/// Elements of a kernel, either all of them or a uniform random sample.
+#[derive(Clone, Debug)]
+pub struct Spectrum {
+    /// The dimension of the kernel.
+    pub nullity: usize,
+    /// The listed elements. When every element is listed, the first one is zero and each next one differs from the previous one
+    /// by a single basis vector.
+    pub elements: Vec<Vec<Bit>>,
+    /// Whether `elements` is a random sample rather than the whole kernel.
+    pub sampled: bool,
+}
+
+/// Counts the `On` bits of `vector`. For a press pattern, this is the number of button presses.
+pub fn weight(vector: &[Bit]) -> usize {
+    vector.iter().filter(|&&b| b == Bit::On).count()
+}
+
+/// Orders press patterns by their number of presses, then by the lowest index at which they differ, putting first the pattern
+/// that presses there. Board variables number the cells in reading order, so among minimums this picks the smallest set of
+/// presses in reading order, however the solutions were found. Both must have the same length.
+pub fn cmp_presses(a: &[Bit], b: &[Bit]) -> Ordering {
+    weight(a)
+        .cmp(&weight(b))
+        .then_with(|| match a.iter().zip(b).find(|(x, y)| x != y) {
+            Some((&Bit::On, _)) => Ordering::Less,
+            Some(_) => Ordering::Greater,
+            None => Ordering::Equal,
+        })
+}
+
+/// Sums the costs of the `On` bits of `vector`, where the bit at index `i` costs `costs[i]`. For a press pattern, this is the total
+/// cost of its presses. Both must have the same length.
+pub fn cost(vector: &[Bit], costs: &[u32]) -> u32 {
+    vector
+        .iter()
+        .zip(costs)
+        .filter(|(&b, _)| b == Bit::On)
+        .map(|(_, &cost)| cost)
+        .sum()
+}
+
+/// Orders press patterns by their total cost, then like `cmp_presses`. All three must have the same length.
+pub fn cmp_costs(a: &[Bit], b: &[Bit], costs: &[u32]) -> Ordering {
+    cost(a, costs)
+        .cmp(&cost(b, costs))
+        .then_with(|| cmp_presses(a, b))
+}
+
+/// Adds `a` and `b` bit by bit. Both must have the same length.
+pub fn add(a: &[Bit], b: &[Bit]) -> Vec<Bit> {
+    a.iter().zip(b).map(|(&x, &y)| x + y).collect()
+}
+
+/// Greedily lowers the weight of the vectors of `basis`, while keeping them a basis of the same space.
+///
+/// Repeatedly replaces a vector with its sum with another vector of the basis, whenever that sum is lighter, until no such pair is
+/// left. Every replacement strictly lowers the total weight, so this always terminates. The result is then sorted by weight.
+/// This does not guarantee the lightest possible basis, but usually produces far more readable quiet patterns than the raw kernel basis.
+pub fn reduce_basis(basis: &mut [Vec<Bit>]) {
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..basis.len() {
+            for j in 0..basis.len() {
+                if i == j {
+                    continue;
+                }
+                let sum = add(&basis[i], &basis[j]);
+                if weight(&sum) < weight(&basis[i]) {
+                    basis[i] = sum;
+                    improved = true;
+                }
+            }
+        }
+    }
+    basis.sort_by_key(|v| weight(v));
+}
+
+/// Lists the elements of the kernel spanned by `basis`, vectors of length `len`. Kernels of dimension up to
+/// `MAX_SPECTRUM_NULLITY` are listed whole, in Gray code order. Larger ones are sampled `samples` times with `rng`, drawing each
+/// element uniformly and independently, so the same element may appear more than once.
+pub fn spectrum(basis: &[Vec<Bit>], len: usize, samples: usize, rng: &mut Rng) -> Spectrum {
+    let nullity = basis.len();
+    if nullity <= MAX_SPECTRUM_NULLITY {
+        let mut current = vec![Bit::Off; len];
+        let mut elements = vec![current.clone()];
+        for step in 1..1usize << nullity {
+            current = add(&current, &basis[step.trailing_zeros() as usize]);
+            elements.push(current.clone());
+        }
+        return Spectrum {
+            nullity,
+            elements,
+            sampled: false,
+        };
+    }
+    let elements = (0..samples)
+        .map(|_| {
+            basis
+                .iter()
+                .filter(|_| rng.next_bit() == Bit::On)
+                .fold(vec![Bit::Off; len], |sum, vector| add(&sum, vector))
+        })
+        .collect();
+    Spectrum {
+        nullity,
+        elements,
+        sampled: true,
+    }
+}
+
+/// Finds the lightest vector of the coset `base + span(basis)`, breaking ties by `cmp_presses`. Every vector of `basis` must be as
+/// long as `base`, and `basis` must hold at most 128 vectors.
+///
+/// Splits `basis` into components that share no bits first, since each of them can be searched on its own: separate regions of a
+/// board then cost the sum of their searches, rather than their product.
+pub fn lightest_in_coset(base: &[Bit], basis: &[Vec<Bit>]) -> Vec<Bit> {
+    let mut lightest = base.to_vec();
+    for component in components(basis) {
+        let bits: Vec<usize> = (0..base.len())
+            .filter(|&bit| {
+                component
+                    .iter()
+                    .any(|&vector| basis[vector][bit] == Bit::On)
+            })
+            .collect();
+        let project =
+            |vector: &[Bit]| -> Vec<Bit> { bits.iter().map(|&bit| vector[bit]).collect() };
+        let projected: Vec<Vec<Bit>> = component
+            .iter()
+            .map(|&vector| project(&basis[vector]))
+            .collect();
+        for (&bit, value) in bits
+            .iter()
+            .zip(search_component(&project(base), &projected))
+        {
+            lightest[bit] = value;
+        }
+    }
+    lightest
+}
+
+/// Splits the indices of the vectors of `basis` into components, where two vectors sharing a bit belong to the same component.
+fn components(basis: &[Vec<Bit>]) -> Vec<Vec<usize>> {
+    let len = basis.first().map_or(0, Vec::len);
+    let mut label: Vec<usize> = (0..basis.len()).collect();
+    for bit in 0..len {
+        let mut touching = (0..basis.len()).filter(|&vector| basis[vector][bit] == Bit::On);
+        let Some(first) = touching.next() else {
+            continue;
+        };
+        for vector in touching {
+            let (keep, merged) = (label[first], label[vector]);
+            for label in label.iter_mut().filter(|label| **label == merged) {
+                *label = keep;
+            }
+        }
+    }
+    let mut components: Vec<Vec<usize>> = vec![];
+    for vector in 0..basis.len() {
+        match components
+            .iter_mut()
+            .find(|component| label[component[0]] == label[vector])
+        {
+            Some(component) => component.push(vector),
+            None => components.push(vec![vector]),
+        }
+    }
+    components
+}
+
+/// Finds the lightest vector of the coset `base + span(basis)` like `lightest_in_coset`, for a basis that does not split.
+///
+/// Greedily improves `base` first, then branches on the coefficient of one basis vector at a time. Each branch is bounded from
+/// below by grouping the bits that the undecided coefficients still change by which of them they depend on: the bits of a group
+/// all flip together, so at least the smaller of their `On` and `Off` counts ends up `On`. Branches whose bound exceeds the
+/// lightest vector found so far are cut. The search stays exact, but takes time exponential in `basis.len()` in the worst case.
+fn search_component(base: &[Bit], basis: &[Vec<Bit>]) -> Vec<Bit> {
+    let mut best = base.to_vec();
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for vector in basis {
+            let candidate = add(&best, vector);
+            if cmp_presses(&candidate, &best) == Ordering::Less {
+                best = candidate;
+                improved = true;
+            }
+        }
+    }
+
+    let order = branching_order(basis);
+    let masks: Vec<u128> = (0..base.len())
+        .map(|bit| {
+            order
+                .iter()
+                .enumerate()
+                .filter(|(_, &vector)| basis[vector][bit] == Bit::On)
+                .fold(0, |mask, (depth, _)| mask | 1 << depth)
+        })
+        .collect();
+    let levels = (0..=order.len())
+        .map(|depth| Level::new(&masks, depth))
+        .collect();
+    let mut search = Search {
+        basis: order
+            .iter()
+            .map(|&vector| pack_row(&basis[vector]))
+            .collect(),
+        levels,
+        best_weight: weight(&best),
+        best: pack_row(&best),
+    };
+    search.visit(0, &mut pack_row(base));
+    unpack_row(&search.best, base.len())
+}
+
+/// Orders the vectors of `basis` for `lightest_in_coset`, picking next the vector that leaves the most bits depending on no
+/// undecided coefficient, so that branches are bounded tightly as early as possible.
+fn branching_order(basis: &[Vec<Bit>]) -> Vec<usize> {
+    let len = basis.first().map_or(0, Vec::len);
+    let mut pending: Vec<usize> = (0..len)
+        .map(|bit| basis.iter().filter(|vector| vector[bit] == Bit::On).count())
+        .collect();
+    let mut left: Vec<usize> = (0..basis.len()).collect();
+    let mut order = vec![];
+    while !left.is_empty() {
+        let (index, _) = left
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, &vector)| {
+                let completed = (0..len)
+                    .filter(|&bit| basis[vector][bit] == Bit::On && pending[bit] == 1)
+                    .count();
+                (completed, Reverse(vector))
+            })
+            .unwrap(); // `left` is not empty.
+        let vector = left.remove(index);
+        for (bit, pending) in pending.iter_mut().enumerate() {
+            if basis[vector][bit] == Bit::On {
+                *pending -= 1;
+            }
+        }
+        order.push(vector);
+    }
+    order
+}
+
+/// The bits of a coset at one depth of `lightest_in_coset`, when the coefficients of the first `depth` vectors are decided.
+struct Level {
+    /// The bits depending on no undecided coefficient, packed by `pack_row`.
+    decided: Vec<u64>,
+    /// The undecided bits, grouped by the undecided coefficients they depend on. Groups of a single bit bound nothing, and are
+    /// left out.
+    groups: Vec<Vec<usize>>,
+}
+
+impl Level {
+    fn new(masks: &[u128], depth: usize) -> Self {
+        let undecided = u128::MAX.checked_shl(depth as u32).unwrap_or(0);
+        let mut groups: HashMap<u128, Vec<usize>> = HashMap::new();
+        for (bit, &mask) in masks.iter().enumerate() {
+            groups.entry(mask & undecided).or_default().push(bit);
+        }
+        let mut decided = vec![0; masks.len().div_ceil(WORD_BITS)];
+        // The padding bits of the last word never change, so they count as decided.
+        let padding = masks.len()..decided.len() * WORD_BITS;
+        for bit in groups
+            .remove(&0)
+            .unwrap_or_default()
+            .into_iter()
+            .chain(padding)
+        {
+            decided[bit / WORD_BITS] |= 1 << (bit % WORD_BITS);
+        }
+        Level {
+            decided,
+            groups: groups
+                .into_values()
+                .filter(|group| group.len() > 1)
+                .collect(),
+        }
+    }
+}
+
+/// The state of `lightest_in_coset` while it branches. Vectors are packed by `pack_row`.
+struct Search {
+    /// The basis vectors, in the order their coefficients are decided.
+    basis: Vec<Vec<u64>>,
+    /// The bits at each depth.
+    levels: Vec<Level>,
+    /// The lightest vector found so far.
+    best: Vec<u64>,
+    best_weight: usize,
+}
+
+impl Search {
+    /// Searches the vectors that `current` leads to by deciding the coefficients from `depth` on.
+    fn visit(&mut self, depth: usize, current: &mut Vec<u64>) {
+        let level = &self.levels[depth];
+        let fixed: usize = current
+            .iter()
+            .zip(&level.decided)
+            .map(|(word, decided)| (word & decided).count_ones() as usize)
+            .sum();
+        let bound = fixed
+            + level
+                .groups
+                .iter()
+                .map(|group| {
+                    let on = group
+                        .iter()
+                        .filter(|&&bit| packed_bit(current, bit))
+                        .count();
+                    on.min(group.len() - on)
+                })
+                .sum::<usize>();
+        if bound > self.best_weight
+            || bound == self.best_weight && !self.may_precede(level, current)
+        {
+            return;
+        }
+        if depth == self.basis.len() {
+            // Every bit is decided, so `current` weighs exactly `bound`.
+            self.best.clone_from(current);
+            self.best_weight = bound;
+            return;
+        }
+        self.visit(depth + 1, current);
+        for (word, step) in current.iter_mut().zip(&self.basis[depth]) {
+            *word ^= step;
+        }
+        self.visit(depth + 1, current);
+        for (word, step) in current.iter_mut().zip(&self.basis[depth]) {
+            *word ^= step;
+        }
+    }
+
+    /// Whether a vector that `current` leads to at `level` may come before the best one by `cmp_presses`, if they weigh the same:
+    /// whether the lowest bit at which they may differ is undecided, or set in `current`.
+    fn may_precede(&self, level: &Level, current: &[u64]) -> bool {
+        let words = current.iter().zip(&self.best).zip(&level.decided);
+        for ((&word, &best), &decided) in words {
+            let open = !decided | (word ^ best);
+            if open != 0 {
+                let lowest = open & open.wrapping_neg();
+                return lowest & !decided != 0 || word & lowest != 0;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bitslice, board::parse_board_file, equations::Equations, generator, report::Report,
+        rng::Rng,
+    };
+
+    /// A board of cells `1` and holes ` `, one line of characters per row.
+    type Lines = Vec<Vec<char>>;
+
+    fn equations(lines: &[Vec<char>]) -> Equations {
+        let text: Vec<String> = lines.iter().map(|line| line.iter().collect()).collect();
+        let board = parse_board_file(&text.join("\n")).unwrap();
+        Report::new(&board.to_matrix().0).equations
+    }
+
+    /// Scrambles the solved board `lines` by pressing half of its cells at random, so that it stays solvable.
+    fn scrambled(lines: &[Vec<char>], rng: &mut Rng) -> Lines {
+        let text: Vec<String> = lines.iter().map(|line| line.iter().collect()).collect();
+        let board = parse_board_file(&text.join("\n")).unwrap();
+        let (board, _) = generator::scramble(&board, board.cell_count() / 2, rng);
+        board
+            .to_board_text()
+            .lines()
+            .map(|line| line.chars().collect())
+            .collect()
+    }
+
+    /// Checks that the search finds the same solution as enumeration, which breaks ties the same way.
+    fn assert_search_matches_enumeration(eqns: &Equations) {
+        let found = eqns
+            .minimum_weight_solution()
+            .map(|solution| solution.to_bits(eqns.var_count()).unwrap());
+        assert!(found.is_some());
+        assert_eq!(found, bitslice::minimum(eqns));
+    }
+
+    #[test]
+    fn search_matches_enumeration_on_a_large_board() {
+        // A plain 19x19 board has 16 free variables, and every hole here takes one away.
+        let mut rng = Rng::new(2258);
+        for holes in 0..=6 {
+            let mut lines = vec![vec!['1'; 19]; 19];
+            for _ in 0..holes {
+                lines[rng.below(19) as usize][rng.below(19) as usize] = ' ';
+            }
+            let eqns = equations(&scrambled(&lines, &mut rng));
+            assert!((10..=16).contains(&eqns.free_var_count()));
+            assert_search_matches_enumeration(&eqns);
+        }
+    }
+
+    #[test]
+    fn search_matches_enumeration_on_a_chain_of_boards() {
+        // Plain 4x4 boards side by side, joined by a single cell along their top rows, with 10 to 19 free variables in all.
+        let mut rng = Rng::new(2258);
+        for tiles in 3..=6 {
+            let width = tiles * 5 - 1;
+            let mut lines = vec![vec!['1'; width]; 4];
+            for line in &mut lines[1..] {
+                for column in (4..width).step_by(5) {
+                    line[column] = ' ';
+                }
+            }
+            let eqns = equations(&scrambled(&lines, &mut rng));
+            assert!((10..=20).contains(&eqns.free_var_count()));
+            assert_search_matches_enumeration(&eqns);
+        }
+    }
+
+    #[test]
+    fn separate_regions_cost_their_sum() {
+        // A 19x19 board next to two 9x9 boards, with 16 + 8 + 8 free variables: too many to enumerate at once, but each region
+        // can be enumerated on its own.
+        let mut rng = Rng::new(2258);
+        let mut lines = vec![vec!['1'; 29]; 19];
+        for (row, line) in lines.iter_mut().enumerate() {
+            line[19] = ' ';
+            if row == 9 {
+                line[20..].fill(' ');
+            }
+        }
+        let lines = scrambled(&lines, &mut rng);
+        let region = |rows: std::ops::Range<usize>, columns: std::ops::Range<usize>| -> Lines {
+            lines[rows]
+                .iter()
+                .map(|line| line[columns.clone()].to_vec())
+                .collect()
+        };
+        let eqns = equations(&lines);
+        assert_eq!(eqns.free_var_count(), 32);
+        let lightest = |lines: Lines| weight(&bitslice::minimum(&equations(&lines)).unwrap());
+        let found = eqns.minimum_weight_solution().unwrap();
+        assert_eq!(
+            weight(&found.to_bits(eqns.var_count()).unwrap()),
+            lightest(region(0..19, 0..19))
+                + lightest(region(0..9, 20..29))
+                + lightest(region(10..19, 20..29))
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    board::{self, BoardDescription},
+    i18n::tr,
+    solver,
+};
+
+/// A puzzle to play: a board along with its metadata.
+///
+/// Levels are stored as board files preceded by metadata lines of the form `# key: value`. Unknown keys are ignored, so plain
+/// board files are levels without metadata.
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub board: BoardDescription,
+    /// The true minimum number of presses that solve the board. `None` if it has no solutions, or it could not be computed exactly.
+    pub par: Option<usize>,
+}
+
+impl Level {
+    /// Creates a level out of `board`, computing its par.
+    pub fn new(board: BoardDescription) -> Self {
+        let par = solver::par(&board);
+        Level { board, par }
+    }
+
+    /// Creates a level out of `board` with a known `par`, only computing it if it is `None`.
+    pub fn with_par(board: BoardDescription, par: Option<usize>) -> Self {
+        match par {
+            Some(par) => Level {
+                board,
+                par: Some(par),
+            },
+            None => Level::new(board),
+        }
+    }
+
+    /// Parses a level file. A par missing from its metadata is computed from the board.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let board = board::parse_board_file(text)?;
+        let mut par = None;
+        for line in text.lines().take_while(|line| line.starts_with('#')) {
+            let Some((key, value)) = line[1..].split_once(':') else {
+                continue;
+            };
+            if key.trim() == "par" {
+                let value = value.trim();
+                par = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid par \"{}\".", value))?,
+                );
+            }
+        }
+        Ok(Level::with_par(board, par))
+    }
+
+    /// Renders `self` as a level file, readable by `Level::parse` and, skipping the metadata, by `parse_board_file`.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        if let Some(par) = self.par {
+            text.push_str(&format!("# par: {}\n", par));
+        }
+        text.push_str(&self.board.to_board_text());
+        text
+    }
+
+    /// Compares a play-through that solved the board in `presses` presses against par.
+    pub fn score(&self, presses: usize) -> String {
+        match self.par {
+            None => tr!("Par is unknown for this board."),
+            Some(par) if presses < par => {
+                tr!("Par is {}. You beat it, which should not be possible!", par)
+            }
+            Some(par) if presses == par => tr!("Par is {}. You matched it!", par),
+            Some(par) => tr!("Par is {}. You were {} over.", par, presses - par),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Solves Lights-Out style puzzles, as found in the game Wayout, by linear algebra over GF(2).
+//!
+//! The items in `prelude` and the modules below are the stable API of the crate, and only change along with its major version.
+//! The modules that only serve the `wayout-solver` command line tool belong to the binary, and are not part of the library.
+
+pub mod analysis;
+pub mod bit;
+pub mod bitslice;
+pub mod board;
+pub mod convert;
+pub mod coords;
+pub mod crosscheck;
+pub mod decoder;
+pub mod diff;
+pub mod diversity;
+pub mod equations;
+pub mod explain;
+pub mod generator;
+pub mod grid;
+pub mod heatmap;
+pub mod hint;
+pub mod i18n;
+pub mod json;
+pub mod kernel;
+pub mod level;
+pub mod matrix;
+pub mod matrix_io;
+pub mod memory;
+pub mod packed;
+pub mod report;
+pub mod rng;
+pub mod rules;
+pub mod schema;
+pub mod sharecode;
+pub mod solutionset;
+pub mod solver;
+pub mod table;
+#[cfg(feature = "toml")]
+pub mod toml_board;
+pub mod trace;
+pub mod unknown;
+
+/// The types and functions most users of the crate need: parsing a board, solving it, and reading the solutions.
+pub mod prelude {
+    pub use crate::{
+        bit::Bit,
+        board::{parse_board_file, BoardAssignment, BoardDescription, Cell},
+        equations::{Assignment, Equations},
+        grid::Position,
+        level::Level,
+        matrix::Matrix,
+        report::Report,
+        solver::{minimum_solution, minimum_solution_with, solve, SolveResult, Strategy},
+    };
+}
+
+pub use prelude::*;
@@ -1,125 +1,517 @@
-use std::io::Error;
+//! The `wayout-solver` command line tool. Without a command, it asks for a board and how to solve it at a chain of prompts. A
+//! command as the first argument skips the prompts, so the tool can be scripted. These cover the usual scripted uses:
+//!
+//! - `solve [--all | --min] [--modifiers-file <path>] <board>` prints a solution with the fewest presses, or every solution.
+//! - `analyze [--components] [--sensitivity] [--matrix] [--targets] [--plain] <board>` describes the solutions of a board.
+//! - `generate [--size <W>x<H>] [--presses <N>] [--seed <N>]` prints a random solvable board.
+//! - `verify <board> <presses>` tells whether a grid of presses solves a board.
+//! - `play <level|pack>` plays a level, or the levels of a pack that are not solved yet.
+//!
+//! Arguments are parsed by hand, with the helpers in `cli` and in the `run_*` function of each command, rather than with a parser
+//! crate. Each command prints its own usage line when its arguments do not fit.
 
-use board::BoardAssignment;
-use equations::Equations;
-use grid::Position;
+use std::{
+    collections::HashMap,
+    io::{Error, IsTerminal},
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
 
-pub mod bit;
-pub mod board;
-pub mod equations;
-pub mod grid;
-pub mod matrix;
+use wayout_solver::{
+    analysis::{self, Analysis},
+    bit::Bit,
+    bitslice,
+    board::{self, closest_modifier, BoardAssignment, BoardDescription, RigidSemantics, MODIFIERS},
+    convert::{self, BoardFormat},
+    coords,
+    crosscheck::{self, Discrepancy},
+    decoder::{self, CosetDecoder},
+    diff::BoardDiff,
+    diversity,
+    equations::{Assignment, Equations},
+    explain::Explanation,
+    generator,
+    grid::Position,
+    heatmap::Heatmap,
+    hint::{Hint, HintLevel},
+    i18n::{self, say, tr, Language},
+    json::Value,
+    kernel,
+    level::Level,
+    matrix::Matrix,
+    matrix_io::{self, MatrixFormat},
+    memory, packed,
+    report::Report,
+    rng::Rng,
+    rules::PressRules,
+    schema, sharecode,
+    solver::{self, Backend, SolveOptions, Strategy},
+    table::{Table, TableStyle},
+    trace,
+    unknown::PartialBoard,
+};
 
-const MODIFIERS: &[char] = &['H', 'V', 'O'];
+use crate::{
+    cli::{Options, OutputFormat, SolutionFormat},
+    daily::Date,
+    progress::Progress,
+    spoiler::Spoiler,
+};
 
-fn main() {
-    println!("Welcome to the Lights-Out solver!");
-    println!();
-    println!("Enter your board line by line, using 0 for an unlit cell, 1 for a lit cell, and space for a cell without a light. When finished, type 'done' on its own line.");
+mod accessible;
+mod choose;
+mod cli;
+mod config;
+mod daily;
+mod demo;
+mod explore;
+mod html;
+mod oneshot;
+mod progress;
+mod sparsity;
+mod spoiler;
+mod stress;
+mod targets;
+mod template;
+mod tutorial;
+mod watch;
 
-    let board_text = match read_board_text() {
-        Ok(board_text) => board_text,
+/// The rigid semantics chosen with `--rigid`, overriding those of every board read.
+static RIGID: OnceLock<RigidSemantics> = OnceLock::new();
+/// Whether `--target-off` asked for every light of every board read to end up off.
+static TARGET_OFF: OnceLock<bool> = OnceLock::new();
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    // Spans are written once the command is done, however it ends.
+    let trace_path = match cli::take_option(&mut args, "--trace") {
+        Ok(path) => path,
         Err(e) => {
-            println!("Error reading line: {}", e);
+            println!("{}", e);
             return;
         }
     };
+    if trace_path.is_some() {
+        trace::enable();
+    }
+    run(args);
+    if let Some(path) = trace_path {
+        if let Err(e) = trace::write(&path) {
+            println!("{}", e);
+        }
+    }
+}
 
-    let board = {
-        let mut board = match board::parse_board(&board_text) {
-            Ok(board) => board.1,
-            Err(e) => {
-                println!(
-                    "Error parsing board with text {}.\nError: {}",
-                    board_text, e
+/// Runs the command in `args`, or the interactive solver if there is none.
+fn run(mut args: Vec<String>) {
+    match cli::take_option(&mut args, "--lang") {
+        Ok(Some(code)) => match Language::from_code(&code) {
+            Some(language) => i18n::set_language(language),
+            None => println!("Unknown language \"{}\". Using the default language.", code),
+        },
+        Ok(None) => {}
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    }
+    match cli::take_coordinates(&mut args) {
+        Ok(coordinates) => coords::set_coordinates(coordinates),
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    }
+    match cli::take_option(&mut args, "--rigid") {
+        Ok(Some(name)) => match RigidSemantics::from_name(&name) {
+            Some(rigid) => {
+                let _ = RIGID.set(rigid);
+            }
+            None => {
+                say!(
+                    "Unknown rigid semantics \"{}\". Available: shielded, frozen, unpressable, inert.",
+                    name
                 );
                 return;
             }
-        };
+        },
+        Ok(None) => {}
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    }
+    let _ = TARGET_OFF.set(cli::take_flag(&mut args, "--target-off"));
+    if let Some(command) = args.first().filter(|arg| !arg.starts_with("--")) {
+        match command.as_str() {
+            "diff" => run_diff(&args[1..]),
+            "decode" => run_decode(&args[1..]),
+            "template" => run_template(&args[1..]),
+            "target" => run_target(&args[1..]),
+            "analyze" => run_analyze(&args[1..]),
+            "solve" => run_solve(&args[1..]),
+            "verify" => run_verify(&args[1..]),
+            "tutorial" => tutorial::run(),
+            "demo" => demo::run(),
+            "hint" => run_hint(&args[1..]),
+            "daily" => run_daily(&args[1..]),
+            "generate" => run_generate(&args[1..]),
+            "scramble" => run_scramble(&args[1..]),
+            "play" => run_play(&args[1..]),
+            "progress" => run_progress(&args[1..]),
+            "booklet" => run_booklet(&args[1..]),
+            "heatmap" => run_heatmap(&args[1..]),
+            "unknowns" => run_unknowns(&args[1..]),
+            "watch" => run_watch(&args[1..]),
+            "oneshot" => run_oneshot(&args[1..]),
+            "schema" => run_schema(&args[1..]),
+            "to-toml" => run_to_toml(&args[1..]),
+            "to-text" => run_to_text(&args[1..]),
+            "export-matrix" => run_export_matrix(&args[1..]),
+            "solve-matrix" => run_solve_matrix(&args[1..]),
+            "export-equations" => run_export_equations(&args[1..]),
+            "solve-equations" => run_solve_equations(&args[1..]),
+            "why" => run_why(&args[1..]),
+            "avoid" => run_avoid(&args[1..]),
+            "within" => run_within(&args[1..]),
+            "chain" => run_chain(&args[1..]),
+            "morph" => run_morph(&args[1..]),
+            "sparsity" => run_sparsity(&args[1..]),
+            "convert" => run_convert(&args[1..]),
+            "crosscheck" => run_crosscheck(&args[1..]),
+            "stress" => run_stress(&args[1..]),
+            "compression" => run_compression(&args[1..]),
+            "choose" => run_choose(&args[1..]),
+            "rules" => run_rules(&args[1..]),
+            "script" => run_script(&args[1..]),
+            "compare-backends" => run_compare_backends(&args[1..]),
+            "spectrum" => run_spectrum(&args[1..]),
+            "batch" => run_batch(&args[1..]),
+            _ => say!(
+                "Unknown command \"{}\". Available commands: {}.",
+                command,
+                "solve, verify, diff, decode, template, target, analyze, tutorial, hint, daily, generate, scramble, play, progress, booklet, heatmap, unknowns, watch, oneshot, schema, to-toml, to-text, export-matrix, solve-matrix, export-equations, solve-equations, why, avoid, within, chain, morph, sparsity, convert, crosscheck, stress, compression, choose, rules, compare-backends, script, spectrum, demo, batch"
+            ),
+        }
+        return;
+    }
 
-        println!("Does board contain modifiers? [yes/no]");
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
 
-        let has_modifiers = match read_yes_no() {
-            Ok(has_modifiers) => has_modifiers,
+    let template = match &options.template {
+        Some(name) => match template::load_template(name) {
+            Ok(decoder) => Some(decoder),
             Err(e) => {
-                println!("Error reading yes/no answer: {}", e);
+                println!("{}", e);
                 return;
             }
-        };
+        },
+        None => None,
+    };
+    let target = match &options.target_name {
+        Some(name) => match targets::load_target(name) {
+            Ok(target) => Some(target),
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        },
+        None => None,
+    };
 
-        if has_modifiers {
-            println!("Enter modifiers as a grid of spaces, \"H\"s, \"V\"s and \"O\"s.");
-            for (row, line) in (0..board.height()).zip(std::io::stdin().lines()) {
-                match line {
-                    Ok(line) => {
-                        for (col, ch) in (0..board.width()).zip(line.chars()) {
-                            if ch == ' ' {
-                                continue;
-                            } else if MODIFIERS.contains(&ch) {
-                                match &mut board[Position { row, col }] {
-                                    Some(cell) => {
-                                        if ch == 'H' {
-                                            cell.affects_up = false;
-                                            cell.affects_down = false;
-                                        } else if ch == 'V' {
-                                            cell.affects_left = false;
-                                            cell.affects_right = false;
-                                        } else if ch == 'O' {
-                                            cell.is_rigid = true;
-                                        }
-                                    }
-                                    None => {
-                                        println!("Modifier applied to empty cell!");
-                                        return;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        println!("Could not read line: {}", e);
-                        return;
-                    }
+    let board_text = match board_input(&options) {
+        Some(Ok(board_text)) => board_text,
+        Some(Err(e)) => {
+            println!("{}", e);
+            return;
+        }
+        None => {
+            say!("Welcome to the Lights-Out solver!");
+            println!();
+            say!("Enter your board line by line, using 0 for an unlit cell, 1 for a lit cell, and space for a cell without a light. When finished, type 'done' on its own line.");
+            match read_board_text() {
+                Ok(board_text) => board_text,
+                Err(e) => {
+                    say!("Error reading line: {}", e);
+                    return;
                 }
             }
         }
+    };
 
-        board
+    if let Some(decoder) = &template {
+        solve_with_template(decoder, &board_text, &options);
+        return;
+    }
+
+    // TOML board files carry their own target, such as the one of a final board written with `--final-board`.
+    let toml_input = options
+        .input
+        .as_ref()
+        .filter(|path| path.ends_with(".toml"));
+    let board = if let Some(path) = toml_input {
+        match read_board_file(path) {
+            Ok(board) => Some(board),
+            Err(e) => {
+                println!("{}", e);
+                None
+            }
+        }
+    } else if options.input.is_some() || options.board.is_some() {
+        parse_board_input(
+            &board_text,
+            target.as_deref(),
+            options.target_name.as_deref(),
+        )
+    } else {
+        prompt_board(
+            &board_text,
+            target.as_deref(),
+            options.target_name.as_deref(),
+        )
+    };
+    let Some(board) = board else {
+        return;
     };
 
-    let (mut matrix, indexed_locations) = board.to_matrix();
-    println!("Computed board matrix:\n{}", matrix);
-    matrix.eliminate();
-    println!("Reduced board matrix:\n{}", matrix);
+    if board.is_solved() && options.output == OutputFormat::Text {
+        say!("Board is already solved, no presses are needed.");
+    }
 
-    let eqns = Equations::new(matrix);
-    let results = eqns.enumerate_all_results();
-    if results.len() != 0 {
-        println!("Board has {} solutions.", results.len());
+    let mut costs = match &options.costs {
+        Some(path) => match read_costs_file(path, &board) {
+            Ok(costs) => Some(costs),
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        },
+        None => None,
+    };
+    // Cropping keeps the cells in reading order, so costs entered for the board as it was entered still line up with it.
+    let entered = board.clone();
+    let board = if options.crop {
+        crop_board(board)
     } else {
-        println!("Board has no solutions.");
+        board
+    };
+
+    let budget = match memory::budget(options.memory_budget) {
+        Ok(budget) => budget,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    if let Err(e) = memory::check(
+        "The board matrix",
+        memory::board_matrix_bytes(&board),
+        budget,
+    ) {
+        println!("{}", e);
+        return;
+    }
+    let (matrix, indexed_locations) = board.to_matrix();
+    let report = Report::with_strategy(&matrix, options.pivot);
+    if options.output == OutputFormat::Json {
+        print_result_json(
+            &board,
+            &report,
+            &indexed_locations,
+            costs.as_deref(),
+            &options,
+        );
+        return;
+    }
+    if options.accessible {
+        print!("{}", accessible::describe_board(&board));
+    } else {
+        print_matrices(&board, &report, &indexed_locations, options.label_columns);
+    }
+
+    let eqns = &report.equations;
+    if options.kernel {
+        let mut basis = eqns.kernel_basis();
+        kernel::reduce_basis(&mut basis);
+        say!("Board has {} independent quiet patterns.", basis.len());
+        for (count, vector) in basis.iter().enumerate() {
+            let pattern =
+                board.assign_assignment(Assignment::from_bits(vector), &indexed_locations);
+            say!(
+                "Quiet pattern #{}:\n{}",
+                count + 1,
+                render_solution(&pattern, &options)
+            );
+        }
+    }
+
+    let solution_count = eqns.solution_count();
+    match solution_count {
+        Some(0) => {
+            say!("Board has no solutions.");
+            if let Some(rows) = report.contradiction() {
+                // Rows past the cells pin unpressable cells, and name no cell of their own.
+                let cells: Vec<String> = rows
+                    .iter()
+                    .filter_map(|row| indexed_locations.get(row))
+                    .map(|pos| {
+                        tr!(
+                            "row {}, column {}",
+                            coords::row_number(pos.row, board.height()),
+                            coords::col_number(pos.col)
+                        )
+                    })
+                    .collect();
+                say!(
+                    "Every press toggles an even number of these cells, but an odd number of them must be toggled: {}.",
+                    cells.join("; ")
+                );
+            }
+            if let Some(path) = &options.report {
+                write_report(
+                    path,
+                    &board,
+                    &report,
+                    Some(0),
+                    &[],
+                    options.spoiler.is_some(),
+                );
+            }
+            explore_if_asked(&options, &board, &report, &indexed_locations);
+            return;
+        }
+        Some(count) => say!("Board has {} solutions.", count),
+        None => say!("Board has 2^{} solutions.", eqns.free_var_count()),
+    }
+
+    if let Some(number) = options.solution {
+        let Some(assignment) = eqns.solution_by_index(number - 1) else {
+            say!("Board has no solution #{}.", number);
+            return;
+        };
+        let assigned_board = board.assign_assignment(assignment, &indexed_locations);
+        say!(
+            "Solution #{}:\n{}",
+            number,
+            render_solution(&assigned_board, &options)
+        );
+        if let Some(path) = &options.final_board {
+            write_final_board(path, &board, &assigned_board);
+        }
+        if let Some(path) = &options.solutions_csv {
+            write_solutions_csv(
+                path,
+                eqns,
+                std::slice::from_ref(&assigned_board),
+                &indexed_locations,
+            );
+        }
+        if let Some(path) = &options.report {
+            write_report(
+                path,
+                &board,
+                &report,
+                solution_count,
+                &[assigned_board],
+                options.spoiler.is_some(),
+            );
+        }
+        explore_if_asked(&options, &board, &report, &indexed_locations);
+        return;
+    }
+
+    if options.random.is_some() || options.diverse.is_some() {
+        let mut rng = match options.seed {
+            Some(seed) => Rng::new(seed),
+            None => Rng::from_time(),
+        };
+        let assignments = match options.random {
+            Some(count) => (0..count)
+                .map_while(|_| eqns.sample_solution(&mut rng))
+                .collect(),
+            None => diversity::diverse_solutions(eqns, options.diverse.unwrap_or(0), &mut rng),
+        };
+        let mut shown_solutions = vec![];
+        for (number, assignment) in (1..).zip(assignments) {
+            let assigned_board = board.assign_assignment(assignment, &indexed_locations);
+            let rendered = render_solution(&assigned_board, &options);
+            if options.random.is_some() {
+                say!("Random solution #{}:\n{}", number, rendered);
+            } else {
+                say!(
+                    "Diverse solution #{}, {} button presses:\n{}",
+                    number,
+                    assigned_board.count_ones(),
+                    rendered
+                );
+            }
+            shown_solutions.push(assigned_board);
+        }
+        if let (Some(path), Some(solution)) = (&options.final_board, shown_solutions.first()) {
+            write_final_board(path, &board, solution);
+        }
+        if let Some(path) = &options.report {
+            write_report(
+                path,
+                &board,
+                &report,
+                solution_count,
+                &shown_solutions,
+                options.spoiler.is_some(),
+            );
+        }
+        if let Some(path) = &options.solutions_csv {
+            write_solutions_csv(path, eqns, &shown_solutions, &indexed_locations);
+        }
+        explore_if_asked(&options, &board, &report, &indexed_locations);
         return;
     }
-    println!("Show all solutions, or only one with minimum presses? (all/min)");
 
-    let show_all: bool = {
+    // With `--any`, the particular solution is shown without asking, and without searching the others. `--all` answers "all".
+    let show_all: bool = if options.any {
+        false
+    } else if options.all {
+        true
+    } else {
+        say!("Show all solutions, only one with minimum presses, or only one with the lowest total cost? (all/min/cost)");
         let mut show_all = None;
+        let mut wants_costs = false;
         for line in std::io::stdin().lines() {
             match line {
                 Ok(line) => {
-                    if line == "all" {
+                    if line == "all" || line == tr!("all") {
                         show_all = Some(true);
                         break;
-                    } else if line == "min" {
+                    } else if line == "min" || line == tr!("min") {
+                        show_all = Some(false);
+                        break;
+                    } else if line == "cost" || line == tr!("cost") {
+                        wants_costs = true;
                         show_all = Some(false);
                         break;
                     } else {
-                        println!("Please input either \"all\" or \"min\"");
+                        say!("Please input either \"all\", \"min\" or \"cost\"");
                         continue;
                     }
                 }
                 Err(e) => {
-                    println!("Error reading line: {}", e);
+                    say!("Error reading line: {}", e);
+                    return;
+                }
+            }
+        }
+        // The costs are read once the loop above lets go of `stdin`.
+        if wants_costs && costs.is_none() {
+            match read_costs(&entered) {
+                Ok(entered) => costs = Some(entered),
+                Err(e) => {
+                    say!("Error reading line: {}", e);
                     return;
                 }
             }
@@ -127,115 +519,3176 @@ fn main() {
         match show_all {
             Some(show_all) => show_all,
             None => {
-                println!("Error reading \"all\" or \"min\" value. Defaulting to \"min\".");
+                say!("Error reading \"all\" or \"min\" value. Defaulting to \"min\".");
                 false
             }
         }
     };
 
+    // Solutions are streamed, so only the ones kept for the report count against the memory budget.
+    let mut shown_solutions = vec![];
+    let mut keep_solutions = true;
+    if show_all && (options.report.is_some() || options.solutions_csv.is_some()) {
+        if let Err(e) = memory::check(
+            "Keeping every solution for the report",
+            solution_count.and_then(|count| memory::solutions_bytes(&board, count)),
+            budget,
+        ) {
+            println!("{}", e);
+            if options.report.is_some() {
+                say!("The report will not list the solutions.");
+            }
+            if options.solutions_csv.is_some() {
+                say!("The solutions file will not list the solutions.");
+            }
+            keep_solutions = false;
+        }
+    }
     if show_all {
-        for (count, assignment) in results.into_iter().enumerate() {
-            println!(
+        let _span = trace::span("enumerate");
+        for (count, assignment) in eqns.results().enumerate() {
+            let assigned_board = board.assign_assignment(assignment, &indexed_locations);
+            say!(
                 "Solution #{}:\n{}",
                 count + 1,
-                board.assign_assignment(assignment, &indexed_locations)
+                render_solution(&assigned_board, &options)
             );
+            if keep_solutions {
+                shown_solutions.push(assigned_board);
+            }
         }
-    } else {
-        let mut min_moves_board_count: Option<(usize, BoardAssignment, usize)> = None;
-        for (count, assignment) in results.into_iter().enumerate() {
-            let assigned_board = board.assign_assignment(assignment, &indexed_locations);
-            match &min_moves_board_count {
-                Some((min_moves, _, _)) => {
-                    if assigned_board.count_ones() < *min_moves {
-                        min_moves_board_count =
-                            Some((assigned_board.count_ones(), assigned_board, count));
-                    }
-                }
-                None => {
-                    min_moves_board_count =
-                        Some((assigned_board.count_ones(), assigned_board, count));
+    } else if options.any {
+        match eqns.particular_solution() {
+            Some(assignment) => {
+                let assigned_board = board.assign_assignment(assignment, &indexed_locations);
+                say!(
+                    "Solution #1, {} button presses:\n{}",
+                    assigned_board.count_ones(),
+                    render_solution(&assigned_board, &options)
+                );
+                shown_solutions.push(assigned_board);
+            }
+            None => say!("Could not find a solution despite there being solutions. Please send board configuration to developer for debugging."),
+        }
+    } else if let Some(costs) = &costs {
+        let result = solver::cheapest_solution(&board, costs);
+        match result.solution {
+            Some(assigned_board) => {
+                let bits = solution_bits(&assigned_board, &indexed_locations);
+                say!(
+                    "Solution with a total cost of {}, {} button presses:\n{}",
+                    kernel::cost(&bits, costs),
+                    assigned_board.count_ones(),
+                    render_solution(&assigned_board, &options)
+                );
+                say!(
+                    "Cost of each press:\n{}",
+                    assigned_board.to_cost_overlay(costs)
+                );
+                if !result.exact {
+                    say!("This solution was improved greedily, and may not have the lowest total cost.");
                 }
+                shown_solutions.push(assigned_board);
             }
+            None => say!("Could not find a solution despite there being solutions. Please send board configuration to developer for debugging."),
+        }
+    } else if options.all_minimums && eqns.free_var_count() > solver::MAX_ENUMERATED_NULLITY {
+        say!("Board has too many solutions to list every one with the fewest presses.");
+    } else if options.all_minimums {
+        let minimums = bitslice::minimums(eqns).unwrap_or_default();
+        say!(
+            "Board has {} solutions with the fewest presses.",
+            minimums.len()
+        );
+        for bits in minimums {
+            let assigned_board =
+                board.assign_assignment(Assignment::from_bits(&bits), &indexed_locations);
+            say_minimum_solution(eqns, &assigned_board, &indexed_locations, &options);
+            shown_solutions.push(assigned_board);
         }
-        match min_moves_board_count {
-            Some((moves, board, count)) => {
-                println!("Solution #{}, {} button presses:\n{}", count, moves, board);
+    } else {
+        // The backend eliminates with the same pivots as above, so that a greedy solution starts from the first solution of "all".
+        let opts = SolveOptions {
+            strategy: options.strategy,
+            pivot: options.pivot,
+        };
+        let solver = options.backend.solver();
+        let result = match solver.max_cells() {
+            Some(max) if board.cell_count() > max => {
+                say!(
+                    "The board has {} cells, but the {} backend is limited to {}.",
+                    board.cell_count(),
+                    options.backend.name(),
+                    max
+                );
+                return;
+            }
+            _ => solver.solve(&board, &opts),
+        };
+        match result.solution {
+            Some(assigned_board) => {
+                say_minimum_solution(eqns, &assigned_board, &indexed_locations, &options);
+                if !result.exact {
+                    say!(
+                        "This solution was improved greedily, and may not have the fewest presses."
+                    );
+                }
+                shown_solutions.push(assigned_board);
             }
             None => {
-                println!("Could not find a solution despite there being solutions. Please send board configuration to developer for debugging.");
-                return;
+                say!("Could not find a solution despite there being solutions. Please send board configuration to developer for debugging.");
             }
         }
     }
+
+    if let (Some(path), Some(solution)) = (&options.final_board, shown_solutions.first()) {
+        write_final_board(path, &board, solution);
+    }
+    if let Some(path) = &options.report {
+        write_report(
+            path,
+            &board,
+            &report,
+            solution_count,
+            &shown_solutions,
+            options.spoiler.is_some(),
+        );
+    }
+    if let Some(path) = &options.solutions_csv {
+        write_solutions_csv(path, eqns, &shown_solutions, &indexed_locations);
+    }
+    explore_if_asked(&options, &board, &report, &indexed_locations);
 }
 
-fn read_board_text() -> Result<String, Error> {
-    let mut board_text = String::new();
+/// Prints the result of solving `board` as a JSON document following `schema::RESULT_SCHEMA`, and nothing else, for `--output json`.
+fn print_result_json(
+    board: &BoardDescription,
+    report: &Report,
+    indexed_locations: &HashMap<usize, Position>,
+    costs: Option<&[u32]>,
+    options: &Options,
+) {
+    let eqns = &report.equations;
+    match json_solutions(board, eqns, indexed_locations, costs, options) {
+        Ok(solutions) => println!(
+            "{}",
+            convert::result_to_json(
+                board,
+                report.rank,
+                eqns.free_var_count(),
+                eqns.solution_count(),
+                &solutions
+            )
+        ),
+        Err(e) => println!("{}", e),
+    }
+}
 
-    for line in std::io::stdin().lines() {
-        let line = line?;
-        if line == "done" {
-            break;
+/// Picks the solutions of `board` the text output would show, without asking: every solution with `--all`, and otherwise the ones
+/// chosen by `--solution`, `--random`, `--diverse`, `--any`, `--costs` or `--all-minimums`, or else one with the fewest presses.
+fn json_solutions(
+    board: &BoardDescription,
+    eqns: &Equations,
+    indexed_locations: &HashMap<usize, Position>,
+    costs: Option<&[u32]>,
+    options: &Options,
+) -> Result<Vec<BoardAssignment>, String> {
+    let assign = |assignment: Assignment| board.assign_assignment(assignment, indexed_locations);
+    if !eqns.is_consistent() {
+        return Ok(vec![]);
+    }
+    if let Some(number) = options.solution {
+        return match eqns.solution_by_index(number - 1) {
+            Some(assignment) => Ok(vec![assign(assignment)]),
+            None => Err(tr!("Board has no solution #{}.", number)),
+        };
+    }
+    if options.random.is_some() || options.diverse.is_some() {
+        let mut rng = match options.seed {
+            Some(seed) => Rng::new(seed),
+            None => Rng::from_time(),
+        };
+        let assignments = match options.random {
+            Some(count) => (0..count)
+                .map_while(|_| eqns.sample_solution(&mut rng))
+                .collect(),
+            None => diversity::diverse_solutions(eqns, options.diverse.unwrap_or(0), &mut rng),
+        };
+        return Ok(assignments.into_iter().map(assign).collect());
+    }
+    if options.all {
+        if eqns.free_var_count() > solver::MAX_ENUMERATED_NULLITY {
+            return Err(tr!(
+                "Board has {} free variables, too many to list every solution.",
+                eqns.free_var_count()
+            ));
         }
-        if is_valid_line(&line) {
-            board_text.push_str(&line);
-            board_text.push('\n');
-        } else {
-            println!("Invalid line. Please only enter 0's 1's and spaces.");
-            continue;
+        return Ok(eqns.results().map(assign).collect());
+    }
+    if options.any {
+        return Ok(eqns.particular_solution().map(assign).into_iter().collect());
+    }
+    if let Some(costs) = costs {
+        return Ok(solver::cheapest_solution(board, costs)
+            .solution
+            .into_iter()
+            .collect());
+    }
+    if options.all_minimums {
+        if eqns.free_var_count() > solver::MAX_ENUMERATED_NULLITY {
+            return Err(tr!(
+                "Board has too many solutions to list every one with the fewest presses."
+            ));
         }
+        return Ok(bitslice::minimums(eqns)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|bits| assign(Assignment::from_bits(&bits)))
+            .collect());
+    }
+    let solver = options.backend.solver();
+    if let Some(max) = solver.max_cells().filter(|&max| board.cell_count() > max) {
+        return Err(tr!(
+            "The board has {} cells, but the {} backend is limited to {}.",
+            board.cell_count(),
+            options.backend.name(),
+            max
+        ));
+    }
+    let opts = SolveOptions {
+        strategy: options.strategy,
+        pivot: options.pivot,
+    };
+    Ok(solver.solve(board, &opts).solution.into_iter().collect())
+}
+
+/// Writes `solutions` to a CSV file at `path`, or a TSV file if `path` ends in `.tsv`. Every row holds the number of a solution in
+/// enumeration order, left empty when it does not fit in a `u128`, its number of presses, and its presses as a bit string in the
+/// order of the variables.
+fn write_solutions_csv(
+    path: &str,
+    eqns: &Equations,
+    solutions: &[BoardAssignment],
+    indexed_locations: &HashMap<usize, Position>,
+) {
+    let separator = if path.ends_with(".tsv") { "\t" } else { "," };
+    let mut text = ["solution", "presses", "bits"].join(separator);
+    text.push('\n');
+    for solution in solutions {
+        let bits = solution_bits(solution, indexed_locations);
+        let number = eqns
+            .index_of(&bits)
+            .map_or(String::new(), |index| (index + 1).to_string());
+        let row = [
+            number,
+            solution.count_ones().to_string(),
+            Assignment::from_bits(&bits)
+                .as_bitstring()
+                .unwrap_or_default(),
+        ];
+        text.push_str(&row.join(separator));
+        text.push('\n');
     }
+    match std::fs::write(path, text) {
+        Ok(()) => say!("Solutions written to {}.", path),
+        Err(e) => say!("Error writing solutions to {}: {}", path, e),
+    }
+}
 
-    board_text.pop();
+/// Prints `solution` with its number of presses, and its number in enumeration order when that fits in a `u128`.
+fn say_minimum_solution(
+    eqns: &Equations,
+    solution: &BoardAssignment,
+    indexed_locations: &HashMap<usize, Position>,
+    options: &Options,
+) {
+    match eqns.index_of(&solution_bits(solution, indexed_locations)) {
+        Some(count) => say!(
+            "Solution #{}, {} button presses:\n{}",
+            count + 1,
+            solution.count_ones(),
+            render_solution(solution, options)
+        ),
+        None => say!(
+            "Solution with {} button presses:\n{}",
+            solution.count_ones(),
+            render_solution(solution, options)
+        ),
+    }
+}
 
-    Ok(board_text)
+/// Prints the matrix of `board` and its reduced form from `report`. With `label_columns`, every column is headed by the cell
+/// it stands for.
+fn print_matrices(
+    board: &BoardDescription,
+    report: &Report,
+    indexed_locations: &HashMap<usize, Position>,
+    label_columns: bool,
+) {
+    if label_columns {
+        let label = |col| coords::cell_label(indexed_locations[&col], board.height());
+        say!("Computed board matrix:\n{}", report.input.labeled(label));
+        say!("Reduced board matrix:\n{}", report.reduced.labeled(label));
+    } else {
+        say!("Computed board matrix:\n{}", report.input);
+        say!("Reduced board matrix:\n{}", report.reduced);
+    }
 }
 
-/// Reads a single line from `stdin` and returns it as a `String`.
-fn read_single_line() -> Result<String, Error> {
-    match std::io::stdin().lines().next() {
-        Some(line) => {
-            return line;
+/// Starts exploring the system of `board` if `--explore` was given.
+fn explore_if_asked(
+    options: &Options,
+    board: &BoardDescription,
+    report: &Report,
+    indexed_locations: &HashMap<usize, Position>,
+) {
+    if options.explore {
+        println!();
+        explore::run(board, report, indexed_locations);
+    }
+}
+
+/// Renders an HTML report of solving `board`, and writes it to the file at `path`. With `collapse_solutions`, the solutions are
+/// hidden until the reader expands them.
+fn write_report(
+    path: &str,
+    board: &BoardDescription,
+    report: &Report,
+    solution_count: Option<u128>,
+    solutions: &[BoardAssignment],
+    collapse_solutions: bool,
+) {
+    let html = html::render_report(board, report, solution_count, solutions, collapse_solutions);
+    match std::fs::write(path, html) {
+        Ok(()) => say!("Report written to {}.", path),
+        Err(e) => say!("Error writing report to {}: {}", path, e),
+    }
+}
+
+/// Renders `assignment` in the chosen solution format, as a spoken list of presses if the accessible output mode is enabled, or
+/// as that list encoded if solutions are hidden with `rot13`.
+fn render_solution(assignment: &BoardAssignment, options: &Options) -> String {
+    if options.spoiler == Some(Spoiler::Rot13) {
+        return spoiler::rot13(&accessible::describe_assignment(assignment));
+    }
+    if options.accessible {
+        return accessible::describe_assignment(assignment);
+    }
+    match options.format {
+        SolutionFormat::Grid => assignment.to_string(),
+        SolutionFormat::Presses => assignment.to_press_grid(),
+    }
+}
+
+/// Simulates pressing the buttons of `solution` on `board`, and writes the resulting board to the file at `path`, in the format
+/// `read_board_file` reads it back in. The plain format cannot express targets other than every cell lit, so those need a `.toml`
+/// path.
+fn write_final_board(path: &str, board: &BoardDescription, solution: &BoardAssignment) {
+    let final_board = board.apply_assignment(solution);
+    let solved = final_board.is_solved();
+    if final_board.has_default_target() {
+        match solved {
+            true => say!("Simulated presses light up every cell."),
+            false => say!("Warning: simulated presses do not light up every cell!"),
         }
-        None => {
-            println!("Could not read line.");
-            panic!();
+    } else if final_board.has_uniform_target(Bit::Off) {
+        match solved {
+            true => say!("Simulated presses turn off every cell."),
+            false => say!("Warning: simulated presses do not turn off every cell!"),
+        }
+    } else {
+        match solved {
+            true => say!("Simulated presses bring every cell to its target."),
+            false => say!("Warning: simulated presses do not bring every cell to its target!"),
+        }
+    }
+    let level = Level::with_par(final_board, None);
+    let format = if path.ends_with(".toml") {
+        BoardFormat::Toml
+    } else {
+        BoardFormat::Text
+    };
+    let text = match convert::render(&level, format) {
+        Ok(text) => text,
+        Err(_) => {
+            say!("The plain format cannot express the target of this board.");
+            say!("Write the final board to a .toml file instead.");
+            return;
         }
+    };
+    match std::fs::write(path, text) {
+        Ok(()) => say!("Final board written to {}.", path),
+        Err(e) => say!("Error writing final board to {}: {}", path, e),
     }
 }
 
-/// Reads a single `yes/no` response, and returns it as a `bool`.
-fn read_yes_no() -> Result<bool, Error> {
-    loop {
-        let line = read_single_line()?;
-        if line == "yes" {
-            return Ok(true);
-        } else if line == "no" {
-            return Ok(false);
-        } else {
-            println!("Please input either \"yes\" or \"no\". [yes/no]");
+/// Runs the `diff` command, comparing the two board files named in `args` cell by cell. Changed cells are highlighted only on a
+/// terminal, and never with `--accessible`.
+fn run_diff(args: &[String]) {
+    let mut args = args.to_vec();
+    let accessible = cli::take_flag(&mut args, "--accessible");
+    let [before_path, after_path] = args.as_slice() else {
+        say!("Usage: wayout-solver diff [--accessible] <before> <after>");
+        return;
+    };
+
+    let before = match read_board_file(before_path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let after = match read_board_file(after_path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
         }
+    };
+
+    // Escape codes would end up in files and confuse screen readers.
+    let diff = BoardDiff::new(&before, &after)
+        .with_highlight(std::io::stdout().is_terminal() && !accessible);
+    if diff.is_empty() {
+        say!("Boards are identical.");
+    } else {
+        print!("{}", diff);
+        println!("{}", coords::describe());
     }
 }
 
-/// Checks if `src` is a valid description of a board row.
-pub fn is_valid_line(src: &str) -> bool {
-    for c in src.chars() {
-        if c == '0' || c == '1' || c == ' ' {
-            continue;
-        } else {
-            return false;
+/// Runs the `decode` command. Builds a `CosetDecoder` for the shape of the first board file in `args`, then uses it
+/// to find a solution with the fewest presses for the start state of every board file in `args`, including the first.
+fn run_decode(args: &[String]) {
+    let Some(shape_path) = args.first() else {
+        say!("Usage: wayout-solver decode <board> [<board>...]");
+        return;
+    };
+    let shape = match read_board_file(shape_path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let Some(decoder) = CosetDecoder::new(&shape) else {
+        say!(
+            "Board has more than {} free variables, too many to decode.",
+            decoder::MAX_DECODER_NULLITY
+        );
+        return;
+    };
+    say!("Built decoder with {} free variables.", decoder.nullity());
+
+    for path in args {
+        let board = match read_board_file(path) {
+            Ok(board) => board,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        match decoder.decode(&board) {
+            Ok(Some(solution)) => say!(
+                "{}: {} button presses:\n{}",
+                path,
+                solution.count_ones(),
+                solution
+            ),
+            Ok(None) => say!("{}: board has no solutions.", path),
+            Err(e) => say!("{}: {}", path, e),
+        }
+    }
+}
+
+/// Runs the `analyze` command. Prints the metrics of a single board file, or a summary of several, as a table.
+/// The `--plain` flag draws the tables without box-drawing characters, and `--components` adds a report for every component of
+/// a single board.
+fn run_analyze(args: &[String]) {
+    let mut args = args.to_vec();
+    let style = if cli::take_flag(&mut args, "--plain") {
+        TableStyle::Plain
+    } else {
+        TableStyle::Pretty
+    };
+    let by_component = cli::take_flag(&mut args, "--components");
+    let by_cell = cli::take_flag(&mut args, "--sensitivity");
+    let with_matrix = cli::take_flag(&mut args, "--matrix");
+    let by_target = cli::take_flag(&mut args, "--targets");
+    if args.is_empty() || ((by_component || by_cell || with_matrix || by_target) && args.len() > 1)
+    {
+        say!("Usage: wayout-solver analyze [--plain] <board> [<board>...]");
+        say!("Usage: wayout-solver analyze [--components] [--sensitivity] [--matrix] [--targets] [--plain] <board>");
+        return;
+    }
+
+    let mut analyses = vec![];
+    let mut boards = vec![];
+    for path in &args {
+        match read_board_file(path) {
+            Ok(board) => {
+                analyses.push((path.clone(), Analysis::new(&board)));
+                boards.push(board);
+            }
+            Err(e) => println!("{}", e),
         }
     }
-    return true;
+    match analyses.as_slice() {
+        [] => {}
+        [(_, analysis)] => print!("{}", analysis.to_table(style)),
+        _ => print!("{}", analysis::summary_table(&analyses, style)),
+    }
+    if let (true, [board]) = (by_component, boards.as_slice()) {
+        analyze_components(board, style);
+    }
+    if let (true, [board]) = (by_cell, boards.as_slice()) {
+        analyze_sensitivity(board, style);
+    }
+    if let (true, [board]) = (by_target, boards.as_slice()) {
+        println!();
+        say!("Fewest presses from the starting state to each notable state:");
+        print!(
+            "{}",
+            analysis::target_table(&analysis::target_distances(board), style)
+        );
+    }
+    if let (true, [board]) = (with_matrix, boards.as_slice()) {
+        let (matrix, indexed_locations) = board.to_matrix();
+        println!();
+        print_matrices(board, &Report::new(&matrix), &indexed_locations, true);
+    }
+    for ((path, _), board) in analyses.iter().zip(&boards) {
+        warn_asymmetric(path, board);
+    }
+}
+
+/// Warns about the pairs of cells of `board`, read from `path`, where only one of them toggles the other.
+fn warn_asymmetric(path: &str, board: &BoardDescription) {
+    let pairs = analysis::asymmetric_pairs(board);
+    if pairs.is_empty() {
+        return;
+    }
+    println!();
+    say!(
+        "Warning: in {}, {} pairs of cells only affect each other one way. If modifiers should be mutual, check the modifier grid.",
+        path,
+        pairs.len()
+    );
+    let cell = |pos: Position| {
+        tr!(
+            "row {}, column {}",
+            coords::row_number(pos.row, board.height()),
+            coords::col_number(pos.col)
+        )
+    };
+    for (pressed, affected) in pairs {
+        say!(
+            "Pressing {} toggles {}, but not the other way around.",
+            cell(pressed),
+            cell(affected)
+        );
+    }
+}
+
+/// Prints the components of `board`, as a map of their labels and a table, and names the ones that make it unsolvable.
+fn analyze_components(board: &BoardDescription, style: TableStyle) {
+    let components = analysis::components(board);
+    println!();
+    say!("{} components:", components.len());
+    print!("{}", analysis::component_map(board, &components));
+    println!();
+    print!("{}", analysis::component_table(&components, style));
+    for (index, component) in components.iter().enumerate() {
+        if !component.solvable {
+            say!(
+                "Component {} has no solutions, so neither does the board.",
+                analysis::component_label(index)
+            );
+        }
+    }
+}
+
+/// Prints how flipping each cell of `board` changes it, as a map of the fragile cells and a table, and counts the fragile cells.
+fn analyze_sensitivity(board: &BoardDescription, style: TableStyle) {
+    let cells = analysis::sensitivity(board);
+    println!();
+    say!("Fragile cells, where a wrong reading changes whether the board is solvable, are marked with \"!\":");
+    print!("{}", analysis::sensitivity_map(board, &cells));
+    println!();
+    print!("{}", analysis::sensitivity_table(board, &cells, style));
+    say!(
+        "{} of {} cells are fragile.",
+        cells.iter().filter(|cell| cell.fragile).count(),
+        cells.len()
+    );
+}
+
+/// Runs the `hint` command. Gives escalating hints towards a solution with the fewest presses for a board file: first how many
+/// presses remain, then which row contains a needed press, and finally the exact cell. `--level <1-3>` starts at a given level.
+fn run_hint(args: &[String]) {
+    let mut args = args.to_vec();
+    let mut level = match cli::take_option(&mut args, "--level") {
+        Ok(None) => HintLevel::Count,
+        Ok(Some(level)) => match level.as_str() {
+            "1" => HintLevel::Count,
+            "2" => HintLevel::Row,
+            "3" => HintLevel::Cell,
+            _ => {
+                say!("Hint level must be 1, 2 or 3.");
+                return;
+            }
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [path] = args.as_slice() else {
+        say!("Usage: wayout-solver hint [--level <1-3>] <board>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let Some(hint) = Hint::new(&board) else {
+        say!("Board has no solutions.");
+        return;
+    };
+
+    loop {
+        println!("{}", hint.reveal(level));
+        let Some(next) = level.next() else {
+            break;
+        };
+        if hint.solution().count_ones() == 0 {
+            break;
+        }
+        say!("Show a more revealing hint? [yes/no]");
+        match read_yes_no() {
+            Ok(true) => level = next,
+            Ok(false) => break,
+            Err(e) => {
+                say!("Error reading yes/no answer: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Runs the `daily` command. Prints the puzzle of the day and its share code, so that everyone can play the same board.
+/// `--date YYYY-MM-DD` shows the puzzle of another day instead.
+fn run_daily(args: &[String]) {
+    let mut args = args.to_vec();
+    let date = match cli::take_option(&mut args, "--date") {
+        Ok(None) => Date::today(),
+        Ok(Some(text)) => match Date::parse(&text) {
+            Ok(date) => date,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    if !args.is_empty() {
+        say!("Usage: wayout-solver daily [--date <YYYY-MM-DD>]");
+        return;
+    }
+
+    let level = Level::new(daily::daily_puzzle(date));
+    say!("Daily puzzle for {}:", date);
+    print!("{}", level.to_text());
+    say!("Share code: {}", sharecode::encode(&level.board));
+}
+
+/// Runs the `generate` command. Prints a random solvable level, with its par, in the level file format. `--size <W>x<H>` sets the
+/// size of the board, `--presses <N>` how many random presses scramble it, and `--seed <N>` makes the level reproducible.
+fn run_generate(args: &[String]) {
+    let mut args = args.to_vec();
+    let (width, height, presses, seed) = match parse_generate_options(&mut args) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    if !args.is_empty() {
+        say!("Usage: wayout-solver generate [--size <W>x<H>] [--presses <N>] [--seed <N>]");
+        return;
+    }
+
+    let mut rng = seed.map_or_else(Rng::from_time, Rng::new);
+    let (board, _) = generator::generate(width, height, presses, &mut rng);
+    print!("{}", Level::new(board).to_text());
+}
+
+/// Runs the `scramble` command. Makes a practice puzzle by pressing `--presses` distinct random cells of a solved board, either
+/// of the shape of a board file or a plain board of `--size`. Prints the puzzle as a level file, in the TOML format if its target
+/// needs it, recording the presses in an `intended` comment as `row,column` pairs counting from 1 at the top left. With `--play`,
+/// lets the user solve it right away instead.
+fn run_scramble(args: &[String]) {
+    let mut args = args.to_vec();
+    let play = cli::take_flag(&mut args, "--play");
+    let (width, height, presses, seed) = match parse_generate_options(&mut args) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let shape = match args.as_slice() {
+        [] => generator::solved_board(width, height),
+        [path] => match read_board_file(path) {
+            Ok(board) => board,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        },
+        _ => {
+            say!("Usage: wayout-solver scramble [--size <W>x<H>] [--presses <N>] [--seed <N>] [--play] [<board>]");
+            return;
+        }
+    };
+
+    let mut rng = seed.map_or_else(Rng::from_time, Rng::new);
+    let (board, intended) = generator::scramble(&generator::solved(&shape), presses, &mut rng);
+    let level = Level::new(board);
+    if !play {
+        let intended: Vec<String> = intended
+            .iter()
+            .map(|pos| format!("{},{}", pos.row + 1, pos.col + 1))
+            .collect();
+        // Both formats skip comment lines, so the presses can be recorded in either.
+        let format = if level.board.has_default_target() {
+            BoardFormat::Text
+        } else {
+            BoardFormat::Toml
+        };
+        match convert::render(&level, format) {
+            Ok(text) => print!("# intended: {}\n{}", intended.join(" "), text),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+    if play_level(&level).is_some() {
+        say!("The board was scrambled with {} presses.", intended.len());
+    } else {
+        let intended: Vec<String> = intended
+            .iter()
+            .map(|pos| {
+                tr!(
+                    "row {}, column {}",
+                    coords::row_number(pos.row, level.board.height()),
+                    coords::col_number(pos.col)
+                )
+            })
+            .collect();
+        say!(
+            "The board was scrambled by pressing {}.",
+            intended.join("; ")
+        );
+    }
+}
+
+/// Takes the options of the `generate` command out of `args`: the width and height of the board, the number of presses, and the seed.
+fn parse_generate_options(
+    args: &mut Vec<String>,
+) -> Result<(usize, usize, usize, Option<u64>), String> {
+    let (width, height) = match cli::take_option(args, "--size")? {
+        None => (5, 5),
+        Some(size) => size
+            .split_once('x')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+            .filter(|&(w, h)| w > 0 && h > 0)
+            .ok_or_else(|| {
+                tr!(
+                    "Invalid size \"{}\". Sizes are written as <width>x<height>.",
+                    size
+                )
+            })?,
+    };
+    let presses = match cli::take_option(args, "--presses")? {
+        None => 8,
+        Some(presses) => presses
+            .parse()
+            .map_err(|_| tr!("Invalid number of presses \"{}\".", presses))?,
+    };
+    let seed = match cli::take_option(args, "--seed")? {
+        None => None,
+        Some(seed) => Some(
+            seed.parse()
+                .map_err(|_| tr!("Invalid seed \"{}\".", seed))?,
+        ),
+    };
+    Ok((width, height, presses, seed))
+}
+
+/// Runs the `play` command. Lets the user solve a level file by pressing cells one at a time, then compares their number of
+/// presses against the level's par. Given a directory, plays through the level pack in it, skipping completed levels until
+/// the user gives up. Completed levels are recorded in the saved progress.
+fn run_play(args: &[String]) {
+    let [path] = args else {
+        say!("Usage: wayout-solver play <level|pack>");
+        return;
+    };
+    // Levels can still be played without a place to save progress.
+    let mut progress = Progress::load().unwrap_or_else(|e| {
+        println!("{}", e);
+        Progress::default()
+    });
+    let path = std::path::Path::new(path);
+    let levels = if path.is_dir() {
+        match progress::pack_levels(path) {
+            Ok(levels) => levels
+                .into_iter()
+                .filter(|level| progress.get(level).is_none())
+                .collect(),
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        }
+    } else {
+        vec![path.to_path_buf()]
+    };
+    if levels.is_empty() {
+        say!("Every level of this pack is completed.");
+        return;
+    }
+
+    for level_path in levels {
+        let name = level_path.display().to_string();
+        let level = match read_level_file(&name) {
+            Ok(level) => level,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        if path.is_dir() {
+            say!("Level {}:", name);
+        }
+        let Some((presses, time)) = play_level(&level) else {
+            return;
+        };
+        if progress.complete(&level_path, presses, time) {
+            say!("New personal best!");
+        }
+        if let Err(e) = progress.save() {
+            println!("{}", e);
+        }
+    }
+}
+
+/// Runs the `progress` command. Lists the best results of every completed level, or given a directory, of every level of the
+/// level pack in it, along with how many of them are completed.
+fn run_progress(args: &[String]) {
+    let progress = match Progress::load() {
+        Ok(progress) => progress,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    match args {
+        [] => {
+            for (level, record) in progress.records() {
+                let text = tr!(
+                    "{}: {} presses, {}.",
+                    level,
+                    record.presses,
+                    format_elapsed(record.time)
+                );
+                print_with_thumbnail(level, &text);
+            }
+            say!("{} levels completed.", progress.records().count());
+        }
+        [pack] => {
+            let levels = match progress::pack_levels(std::path::Path::new(pack)) {
+                Ok(levels) => levels,
+                Err(e) => {
+                    println!("{}", e);
+                    return;
+                }
+            };
+            let mut completed = 0;
+            for level in &levels {
+                let name = level.display().to_string();
+                match progress.get(level) {
+                    Some(record) => {
+                        completed += 1;
+                        let par = read_level_file(&name)
+                            .ok()
+                            .and_then(|level| level.par)
+                            .map_or_else(|| "?".to_string(), |par| par.to_string());
+                        let text = tr!(
+                            "{}: {} presses (par {}), {}.",
+                            name,
+                            record.presses,
+                            par,
+                            format_elapsed(record.time)
+                        );
+                        print_with_thumbnail(&name, &text);
+                    }
+                    None => print_with_thumbnail(&name, &tr!("{}: not completed.", name)),
+                }
+            }
+            say!("{} of {} levels completed.", completed, levels.len());
+        }
+        _ => say!("Usage: wayout-solver progress [<pack>]"),
+    }
+}
+
+/// Prints `text` followed by an indented thumbnail of the level at `path`, if the level can still be read.
+fn print_with_thumbnail(path: &str, text: &str) {
+    println!("{}", text);
+    if let Ok(level) = read_level_file(path) {
+        for line in level.board.thumbnail() {
+            println!("    {}", line.trim_end());
+        }
+    }
+}
+
+/// Runs the `booklet` command. Prints a printable HTML booklet of the level pack in a directory, with the puzzles and their
+/// pars up front and a solution with the fewest presses of each in an appendix.
+fn run_booklet(args: &[String]) {
+    let mut args = args.to_vec();
+    let title = match cli::take_option(&mut args, "--title") {
+        Ok(title) => title.unwrap_or_else(|| "Lights-Out puzzles".to_string()),
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [pack] = args.as_slice() else {
+        say!("Usage: wayout-solver booklet [--title <title>] <pack>");
+        return;
+    };
+    let paths = match progress::pack_levels(std::path::Path::new(pack)) {
+        Ok(paths) => paths,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let mut levels = vec![];
+    for path in paths {
+        let name = path.display().to_string();
+        match read_level_file(&name) {
+            Ok(level) => levels.push((
+                path.file_stem()
+                    .map_or(name, |stem| stem.to_string_lossy().into_owned()),
+                level,
+            )),
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        }
+    }
+    print!("{}", html::render_booklet(&title, &levels));
+}
+
+/// Lets the user solve `level` by pressing cells one at a time, keeping count of their presses and the time taken, then compares
+/// their number of presses against its par. Returns the presses and time it took them, or `None` if they gave up.
+fn play_level(level: &Level) -> Option<(usize, Duration)> {
+    let mut board = level.board.clone();
+    let mut presses = 0;
+    let start = Instant::now();
+    say!("Press a cell by entering its row and column, or type \"quit\" to give up.");
+    println!("{}", coords::describe());
+    print!("{}", board.to_board_text());
+    while !board.is_solved() {
+        let Some(Ok(line)) = std::io::stdin().lines().next() else {
+            return None;
+        };
+        if line.trim() == "quit" || line.trim() == tr!("quit") {
+            return None;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let &[row, col] = words.as_slice() else {
+            say!("Please enter a row and a column, such as \"2 3\".");
+            continue;
+        };
+        let Some(pos) = coords::parse_position(row, col, board.height(), board.width())
+            .filter(|&pos| matches!(board.get(pos), Some(Some(_))))
+        else {
+            say!("There is no cell at row {}, column {}.", row, col);
+            continue;
+        };
+        board.press(pos);
+        presses += 1;
+        print!("{}", board.to_board_text());
+        say!(
+            "Presses: {}. Time: {}.",
+            presses,
+            format_elapsed(start.elapsed())
+        );
+    }
+    let elapsed = start.elapsed();
+    say!(
+        "Solved in {} presses, taking {}!",
+        presses,
+        format_elapsed(elapsed)
+    );
+    println!("{}", level.score(presses));
+    Some((presses, elapsed))
+}
+
+/// Renders a duration as minutes and seconds, such as `2:05`, with hours in front when there are any.
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+    }
+}
+
+/// Lists the values of the variables of `solution`, where the `i`th bit is the value of the variable at index `i` of
+/// `indexed_locations`.
+fn solution_bits(
+    solution: &BoardAssignment,
+    indexed_locations: &HashMap<usize, Position>,
+) -> Vec<Bit> {
+    (0..indexed_locations.len())
+        .map(|var| solution[indexed_locations[&var]].unwrap_or(Bit::Off))
+        .collect()
+}
+
+/// Runs the `why` command. Explains why the cell at a row and column of a board file is pressed or not in a solution with the
+/// fewest presses, or in solution `--solution <N>` in enumeration order: the equation on the free cells that
+/// forces it, the values chosen for them, and the cells whose equations were added together into it.
+fn run_why(args: &[String]) {
+    let mut args = args.to_vec();
+    let number = match cli::take_option(&mut args, "--solution") {
+        Ok(None) => None,
+        Ok(Some(number)) => match number.parse::<u128>() {
+            Ok(number) if number > 0 => Some(number),
+            _ => {
+                say!("The solution number must be a positive integer.");
+                return;
+            }
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [path, row, col] = args.as_slice() else {
+        say!("Usage: wayout-solver why [--solution <N>] <board> <row> <col>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let (matrix, indexed_locations) = board.to_matrix();
+    let Some(var) =
+        coords::parse_position(row, col, board.height(), board.width()).and_then(|target| {
+            (0..indexed_locations.len()).find(|var| indexed_locations[var] == target)
+        })
+    else {
+        say!("There is no cell at row {}, column {}.", row, col);
+        return;
+    };
+    let report = Report::new(&matrix);
+    let eqns = &report.equations;
+    let bits = match number {
+        Some(number) => match eqns.solution_by_index(number - 1) {
+            Some(assignment) => assignment.to_bits(eqns.var_count()),
+            None => {
+                say!("Board has no solution #{}.", number);
+                return;
+            }
+        },
+        None => match Strategy::Auto.resolve(eqns.free_var_count()) {
+            Strategy::Enumerate => bitslice::minimum(eqns),
+            strategy => solver::minimum_solution_with(&board, strategy)
+                .map(|solution| solution_bits(&solution, &indexed_locations)),
+        },
+    };
+    let Some(bits) = bits else {
+        say!("Board has no solutions.");
+        return;
+    };
+    let solution = board.assign_assignment(Assignment::from_bits(&bits), &indexed_locations);
+    match number {
+        Some(number) => say!("Solution #{}:\n{}", number, solution),
+        None => say!(
+            "Solution with {} button presses:\n{}",
+            solution.count_ones(),
+            solution
+        ),
+    }
+
+    let cell = |var: usize| {
+        let pos = indexed_locations[&var];
+        tr!(
+            "row {}, column {}",
+            coords::row_number(pos.row, board.height()),
+            coords::col_number(pos.col)
+        )
+    };
+    let pressed = bits[var] == Bit::On;
+    match Explanation::new(&report, &bits, var) {
+        _ if !board.is_pressable(indexed_locations[&var]) => say!(
+            "The cell at {} cannot be pressed, so no solution presses it.",
+            cell(var)
+        ),
+        Explanation::Free if pressed => say!(
+            "The cell at {} is free: solutions may press it or not, and this one presses it.",
+            cell(var)
+        ),
+        Explanation::Free => say!(
+            "The cell at {} is free: solutions may press it or not, and this one does not.",
+            cell(var)
+        ),
+        Explanation::Forced {
+            terms,
+            constant,
+            rows,
+        } => {
+            let name = |var: usize| {
+                let pos = indexed_locations[&var];
+                format!(
+                    "[{},{}]",
+                    coords::row_number(pos.row, board.height()),
+                    coords::col_number(pos.col)
+                )
+            };
+            let mut names = vec![];
+            let mut values = vec![];
+            if constant == Bit::On || terms.is_empty() {
+                names.push(constant.to_string());
+                values.push(constant.to_string());
+            }
+            for &(free, value) in &terms {
+                names.push(name(free));
+                values.push(value.to_string());
+            }
+            say!(
+                "The cell at {} is determined by the free cells, whose values the solution chose:",
+                cell(var)
+            );
+            println!("  {} = {}", name(var), names.join(" + "));
+            println!("  {} = {} = {}", name(var), values.join(" + "), bits[var]);
+            let cells: Vec<String> = rows
+                .iter()
+                .filter(|row| indexed_locations.contains_key(row))
+                .map(|&row| cell(row))
+                .collect();
+            say!(
+                "This equation is the sum of the equations of the cells at {}. Each says that the presses toggling that cell add up to the toggle it needs.",
+                cells.join("; ")
+            );
+            if pressed {
+                say!("So the cell is pressed.");
+            } else {
+                say!("So the cell is not pressed.");
+            }
+        }
+    }
+}
+
+/// Runs the `avoid` command. Finds a solution of a board file that does not press the cell at a row and column, or reports that
+/// every solution must press it.
+fn run_avoid(args: &[String]) {
+    let [path, row, col] = args else {
+        say!("Usage: wayout-solver avoid <board> <row> <col>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let Some(pos) = coords::parse_position(row, col, board.height(), board.width())
+        .filter(|&pos| matches!(board.get(pos), Some(Some(_))))
+    else {
+        say!("There is no cell at row {}, column {}.", row, col);
+        return;
+    };
+
+    match solver::solution_avoiding(&board, pos) {
+        None => say!("Board has no solutions."),
+        Some(None) => say!(
+            "Every solution presses the cell at row {}, column {}.",
+            row,
+            col
+        ),
+        Some(Some(solution)) => say!(
+            "Solution with {} button presses:\n{}",
+            solution.count_ones(),
+            solution
+        ),
+    }
+}
+
+/// Runs the `solve` command. Solves a board file without any prompts, printing a solution with as few presses as possible, or
+/// every solution with `--all`. A modifier grid given with `--modifiers-file` is applied on top of the modifiers of the board file.
+fn run_solve(args: &[String]) {
+    let mut args = args.to_vec();
+    let all = cli::take_flag(&mut args, "--all");
+    let min = cli::take_flag(&mut args, "--min");
+    let modifiers_path = match cli::take_option(&mut args, "--modifiers-file") {
+        Ok(modifiers_path) => modifiers_path,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let ([path], false) = (args.as_slice(), all && min) else {
+        say!("Usage: wayout-solver solve [--all | --min] [--modifiers-file <path>] <board>");
+        return;
+    };
+    let mut board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    if let Some(modifiers_path) = modifiers_path {
+        let applied = match std::fs::read_to_string(&modifiers_path) {
+            Ok(text) => board.apply_modifier_text(&text),
+            Err(e) => {
+                say!("Error reading modifiers file {}: {}", modifiers_path, e);
+                return;
+            }
+        };
+        if let Err(e) = applied {
+            say!("Error in modifiers file {}: {}", modifiers_path, e);
+            return;
+        }
+    }
+
+    if !all {
+        match solver::solve(&board).solution {
+            Some(solution) => say!(
+                "Solution with {} button presses:\n{}",
+                solution.count_ones(),
+                solution
+            ),
+            None => say!("Board has no solutions."),
+        }
+        return;
+    }
+    let (matrix, indexed_locations) = board.to_matrix();
+    let eqns = Report::new(&matrix).equations;
+    if !eqns.is_consistent() {
+        say!("Board has no solutions.");
+        return;
+    }
+    if eqns.solution_count().is_none() {
+        say!(
+            "Board has {} free variables, too many to list every solution.",
+            eqns.free_var_count()
+        );
+        return;
+    }
+    for (index, assignment) in eqns.results().enumerate() {
+        say!(
+            "Solution #{}:\n{}",
+            index + 1,
+            board.assign_assignment(assignment, &indexed_locations)
+        );
+    }
+}
+
+/// Runs the `verify` command. Presses the cells marked with a `1` in a presses file on a board file, and tells whether that solves
+/// it. The presses file is a grid like the solutions the solver prints. Lines of anything but `0`, `1` and spaces, like the header
+/// lines of the output of `solve`, are skipped, so that output can be checked as it is.
+fn run_verify(args: &[String]) {
+    let [path, presses_path] = args else {
+        say!("Usage: wayout-solver verify <board> <presses>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let presses = match std::fs::read_to_string(presses_path) {
+        Ok(text) => board.parse_region(
+            &text
+                .trim_end()
+                .lines()
+                .filter(|line| line.chars().all(|c| matches!(c, '0' | '1' | ' ')))
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => {
+            say!("Error reading presses file {}: {}", presses_path, e);
+            return;
+        }
+    };
+    let presses = match presses {
+        Ok(presses) => presses,
+        Err(e) => {
+            say!("Error in presses file {}: {}", presses_path, e);
+            return;
+        }
+    };
+
+    let mut played = board.clone();
+    for &pos in &presses {
+        played.press(pos);
+    }
+    if played.is_solved() {
+        say!("The {} presses solve the board.", presses.len());
+    } else {
+        say!(
+            "The {} presses do not solve the board. They leave it as:\n{}",
+            presses.len(),
+            played
+        );
+    }
+}
+
+/// Runs the `within` command. Finds a solution of a board file that only presses cells in the region of a region file, or
+/// reports that none exists. Region files hold rows of `1`s for the cells in the region, and `0`s or spaces for the rest.
+fn run_within(args: &[String]) {
+    let [path, region_path] = args else {
+        say!("Usage: wayout-solver within <board> <region>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let region = match std::fs::read_to_string(region_path) {
+        Ok(text) => board.parse_region(&text.lines().map(str::to_string).collect::<Vec<_>>()),
+        Err(e) => {
+            say!("Error reading region file {}: {}", region_path, e);
+            return;
+        }
+    };
+    let region = match region {
+        Ok(region) => region,
+        Err(e) => {
+            say!("Error in region file {}: {}", region_path, e);
+            return;
+        }
+    };
+
+    match solver::solution_within(&board, &region) {
+        None => say!("Board has no solutions."),
+        Some((None, _)) => say!("Every solution presses a cell outside of the region."),
+        Some((Some(solution), count)) => {
+            if let Some(count) = count {
+                say!("{} solutions only press cells in the region.", count);
+            }
+            say!(
+                "Solution with {} button presses:\n{}",
+                solution.count_ones(),
+                solution
+            );
+        }
+    }
+}
+
+/// Runs the `choose` command. Marks the free variables of a board file as choice cells, and lets the user pick whether to press
+/// each of them while showing the solution that follows.
+fn run_choose(args: &[String]) {
+    let [path] = args else {
+        say!("Usage: wayout-solver choose <board>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let (matrix, indexed_locations) = board.to_matrix();
+    choose::run(&board, &Report::new(&matrix), &indexed_locations);
+}
+
+/// Runs the `compare-backends` command. Solves every board file with two backends, checks that they agree on whether the board
+/// has solutions and on the fewest presses it takes, and shows how long each one took.
+fn run_compare_backends(args: &[String]) {
+    let [first, second, paths @ ..] = args else {
+        say!("Usage: wayout-solver compare-backends <backend> <backend> <board>...");
+        return;
+    };
+    if paths.is_empty() {
+        say!("Usage: wayout-solver compare-backends <backend> <backend> <board>...");
+        return;
+    }
+    let mut backends = vec![];
+    for name in [first, second] {
+        match Backend::from_name(name) {
+            Some(backend) => backends.push(backend),
+            None => {
+                say!(
+                    "Unknown backend \"{}\". Available backends: gaussian, brute-force.",
+                    name
+                );
+                return;
+            }
+        }
+    }
+
+    for path in paths {
+        let board = match read_board_file(path) {
+            Ok(board) => board,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        let mut table = Table::new(&[tr!("Backend"), tr!("Solvable"), tr!("Presses"), tr!("Time")]);
+        let mut answers = vec![];
+        for &backend in &backends {
+            let solver = backend.solver();
+            if let Some(max) = solver.max_cells().filter(|&max| board.cell_count() > max) {
+                say!(
+                    "{}: the board has {} cells, but the {} backend is limited to {}.",
+                    path,
+                    board.cell_count(),
+                    backend.name(),
+                    max
+                );
+                break;
+            }
+            let start = Instant::now();
+            let result = solver.solve(&board, &SolveOptions::default());
+            let elapsed = start.elapsed();
+            let presses = result.solution.as_ref().map(BoardAssignment::count_ones);
+            table.add_row(&[
+                backend.name().to_string(),
+                if presses.is_some() {
+                    tr!("yes")
+                } else {
+                    tr!("no")
+                },
+                match presses {
+                    Some(count) if result.exact => count.to_string(),
+                    Some(count) => format!("~{}", count),
+                    None => "-".to_string(),
+                },
+                format!("{:.2?}", elapsed),
+            ]);
+            answers.push((presses, result.exact));
+        }
+        let [(first, first_exact), (second, second_exact)] = answers[..] else {
+            continue;
+        };
+        println!("{}", path);
+        print!("{}", table);
+        if first.is_some() != second.is_some() {
+            say!("The backends disagree on whether the board has solutions.");
+        } else if first != second && first_exact && second_exact {
+            say!("The backends disagree on the fewest presses.");
+        } else if first != second {
+            say!("The backends agree on whether the board has solutions, but an approximate solution has more presses.");
+        } else {
+            say!("The backends agree.");
+        }
+    }
+}
+
+/// Runs the `script` command. Solves a board file, then runs the `--explore` commands of a script file on its system, so the same
+/// queries can be repeated on many boards. Reads the script from standard input if its path is `-`.
+fn run_script(args: &[String]) {
+    let [path, script_path] = args else {
+        say!("Usage: wayout-solver script <board> <script|->");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let script = if script_path == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(script_path)
+    };
+    let script = match script {
+        Ok(script) => script,
+        Err(e) => {
+            say!("Error reading script file {}: {}", script_path, e);
+            return;
+        }
+    };
+    let (matrix, indexed_locations) = board.to_matrix();
+    explore::run_script(&board, &Report::new(&matrix), &indexed_locations, &script);
+}
+
+/// Runs the `spectrum` command. Writes the elements of the kernel of a board file with their weights, to a JSON file if the output
+/// path ends in `.json`, and otherwise to a CSV file, or a TSV file if it ends in `.tsv`. Kernels of dimension above
+/// `MAX_SPECTRUM_NULLITY` are sampled, `--samples` times (65536 by default) from a generator seeded with `--seed`.
+fn run_spectrum(args: &[String]) {
+    let mut args = args.to_vec();
+    let options = cli::take_number(&mut args, "--samples")
+        .and_then(|samples| Ok((samples, cli::take_number(&mut args, "--seed")?)));
+    let (samples, seed) = match options {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [path, out_path] = args.as_slice() else {
+        say!("Usage: wayout-solver spectrum [--samples <N>] [--seed <N>] <board> <output.csv|output.tsv|output.json>");
+        return;
+    };
+    let samples = match samples {
+        None => 1 << 16,
+        Some(0) => {
+            say!("The number of samples must be a positive integer.");
+            return;
+        }
+        Some(samples) => samples,
+    };
+    let mut rng = seed.map_or_else(Rng::from_time, Rng::new);
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let (matrix, _) = board.to_matrix();
+    let eqns = Report::new(&matrix).equations;
+    let spectrum = kernel::spectrum(&eqns.kernel_basis(), board.cell_count(), samples, &mut rng);
+    let bitstring = |element: &[Bit]| -> String {
+        element
+            .iter()
+            .map(|&b| if b == Bit::On { '1' } else { '0' })
+            .collect()
+    };
+    let text = if out_path.ends_with(".json") {
+        let elements = spectrum
+            .elements
+            .iter()
+            .map(|element| {
+                Value::Object(vec![
+                    (
+                        "weight".to_string(),
+                        Value::Number(kernel::weight(element) as f64),
+                    ),
+                    ("bits".to_string(), Value::String(bitstring(element))),
+                ])
+            })
+            .collect();
+        let document = Value::Object(vec![
+            (
+                "cells".to_string(),
+                Value::Number(board.cell_count() as f64),
+            ),
+            (
+                "nullity".to_string(),
+                Value::Number(spectrum.nullity as f64),
+            ),
+            ("sampled".to_string(), Value::Bool(spectrum.sampled)),
+            ("elements".to_string(), Value::Array(elements)),
+        ]);
+        format!("{}\n", document)
+    } else {
+        let separator = if out_path.ends_with(".tsv") {
+            "\t"
+        } else {
+            ","
+        };
+        let mut text = ["element", "weight", "bits"].join(separator);
+        text.push('\n');
+        for (index, element) in spectrum.elements.iter().enumerate() {
+            let row = [
+                (index + 1).to_string(),
+                kernel::weight(element).to_string(),
+                bitstring(element),
+            ];
+            text.push_str(&row.join(separator));
+            text.push('\n');
+        }
+        text
+    };
+    if let Err(e) = std::fs::write(out_path, text) {
+        say!("Error writing kernel weights to {}: {}", out_path, e);
+        return;
+    }
+    if spectrum.sampled {
+        say!(
+            "Wrote {} sampled elements of the kernel of dimension {} to {}.",
+            spectrum.elements.len(),
+            spectrum.nullity,
+            out_path
+        );
+    } else {
+        say!(
+            "Wrote all {} elements of the kernel of dimension {} to {}.",
+            spectrum.elements.len(),
+            spectrum.nullity,
+            out_path
+        );
+    }
+}
+
+/// Runs the `rules` command. Solves a board file under the press rules of a rule file, with as few presses as possible.
+fn run_rules(args: &[String]) {
+    let [rules_path, path] = args else {
+        say!("Usage: wayout-solver rules <rule file> <board>");
+        return;
+    };
+    let rules = match std::fs::read_to_string(rules_path) {
+        Ok(text) => PressRules::parse(&text),
+        Err(e) => {
+            say!("Error reading rule file {}: {}", rules_path, e);
+            return;
+        }
+    };
+    let rules = match rules {
+        Ok(rules) => rules,
+        Err(e) => {
+            say!("Error in rule file {}: {}", rules_path, e);
+            return;
+        }
+    };
+    let board = match std::fs::read_to_string(path) {
+        Ok(text) => rules.parse_board(&text),
+        Err(e) => {
+            say!("Error reading board file {}: {}", path, e);
+            return;
+        }
+    };
+    let board = match board {
+        Ok(board) => board,
+        Err(e) => {
+            say!("Error in board file {}: {}", path, e);
+            return;
+        }
+    };
+
+    let result = rules.minimum_solution(&board);
+    match result.solution {
+        None => say!("Board has no solutions."),
+        Some(solution) => {
+            say!(
+                "Solution with {} button presses:\n{}",
+                solution.count_ones(),
+                solution
+            );
+            if !result.exact {
+                say!("This solution was improved greedily, and may not have the fewest presses.");
+            }
+        }
+    }
+}
+
+/// Runs the `chain` command. Solves a staged puzzle: starting from a board file, reaches the pattern in each of the target files in
+/// turn, every one from the pattern before it. Target files hold rows of `0`s and `1`s, with spaces over the holes of the board.
+/// Prints the solution of every stage, followed by all presses in the order to make them.
+fn run_chain(args: &[String]) {
+    let [path, targets @ ..] = args else {
+        say!("Usage: wayout-solver chain <board> <target>...");
+        return;
+    };
+    if targets.is_empty() {
+        say!("Usage: wayout-solver chain <board> <target>...");
+        return;
+    }
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let mut stages = vec![];
+    for target in targets {
+        match std::fs::read_to_string(target) {
+            Ok(text) => stages.push(text.lines().map(str::to_string).collect()),
+            Err(e) => {
+                say!("Error reading target file {}: {}", target, e);
+                return;
+            }
+        }
+    }
+
+    let solutions = match solver::solve_chain(&board, &stages) {
+        Ok(solutions) => solutions,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let mut presses = vec![];
+    for (stage, solution) in solutions.iter().enumerate() {
+        say!(
+            "Stage {}, {} button presses:\n{}",
+            stage + 1,
+            solution.count_ones(),
+            solution
+        );
+        presses.extend(solution.presses().into_iter().map(|pos| {
+            tr!(
+                "row {}, column {}",
+                coords::row_number(pos.row, board.height()),
+                coords::col_number(pos.col)
+            )
+        }));
+    }
+    say!(
+        "{} presses in total, in this order: {}.",
+        presses.len(),
+        presses.join("; ")
+    );
+}
+
+/// Runs the `morph` command. Walks from one solution of a board file to another, both numbered from 1 in enumeration order, adding
+/// a single quiet pattern at every step, and shows every solution along the way.
+fn run_morph(args: &[String]) {
+    let [path, from, to] = args else {
+        say!("Usage: wayout-solver morph <board> <from> <to>");
+        return;
+    };
+    let (Some(from), Some(to)) = (
+        from.parse::<u128>().ok().filter(|&n| n > 0),
+        to.parse::<u128>().ok().filter(|&n| n > 0),
+    ) else {
+        say!("The solution number must be a positive integer.");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let (matrix, indexed_locations) = board.to_matrix();
+    let eqns = Report::new(&matrix).equations;
+    let mut ends = vec![];
+    for number in [from, to] {
+        match eqns
+            .solution_by_index(number - 1)
+            .and_then(|assignment| assignment.to_bits(eqns.var_count()))
+        {
+            Some(bits) => ends.push(bits),
+            None => {
+                say!("Board has no solution #{}.", number);
+                return;
+            }
+        }
+    }
+
+    let steps = eqns.path(&ends[0], &ends[1]);
+    say!(
+        "Solution #{} becomes solution #{} in {} steps.",
+        from,
+        to,
+        steps.len() - 1
+    );
+    for (step, (free_var, bits)) in steps.into_iter().enumerate() {
+        let solution = board.assign_assignment(Assignment::from_bits(&bits), &indexed_locations);
+        match free_var {
+            None => say!("Start:\n{}", solution),
+            Some(var) => {
+                let pos = indexed_locations[&var];
+                say!(
+                    "Step {}, toggling the quiet pattern of the free cell at row {}, column {}:\n{}",
+                    step,
+                    coords::row_number(pos.row, board.height()),
+                    coords::col_number(pos.col),
+                    solution
+                );
+            }
+        }
+    }
+}
+
+/// Runs the `sparsity` command. Plots where the board matrix of a board file has active entries, before and after elimination,
+/// in braille characters covering 4 rows and 2 columns each.
+fn run_sparsity(args: &[String]) {
+    let [path] = args else {
+        say!("Usage: wayout-solver sparsity <board>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let (matrix, _) = board.to_matrix();
+    let report = Report::new(&matrix);
+    for (title, matrix) in [
+        (tr!("Board matrix"), &report.input),
+        (tr!("Reduced board matrix"), &report.reduced),
+    ] {
+        let (count, density) = sparsity::density(matrix);
+        say!(
+            "{}, {} by {}, {} active entries ({}%):",
+            title,
+            matrix.rows,
+            matrix.variable_cols(),
+            count,
+            format!("{:.1}", density * 100.0)
+        );
+        println!("{}", sparsity::braille(matrix));
+    }
+}
+
+/// Runs the `batch` command. Solves every board of a file holding several, as read by `convert::parse_many`, and prints a table
+/// summarizing each one. Boards whose par disagrees with the fewest presses found are listed after it, so a level pack can be
+/// checked in one go.
+fn run_batch(args: &[String]) {
+    let mut args = args.to_vec();
+    let style = if cli::take_flag(&mut args, "--plain") {
+        TableStyle::Plain
+    } else {
+        TableStyle::Pretty
+    };
+    let [path] = args.as_slice() else {
+        say!("Usage: wayout-solver batch [--plain] <file>");
+        return;
+    };
+    let levels = match std::fs::read_to_string(path) {
+        Ok(text) => convert::parse_many(&text),
+        Err(e) => {
+            say!("Error reading board file {}: {}", path, e);
+            return;
+        }
+    };
+    let levels = match levels {
+        Ok(levels) => levels,
+        Err(e) => {
+            say!("Error in board file {}: {}", path, e);
+            return;
+        }
+    };
+
+    let mut analyses = vec![];
+    let mut mismatches = vec![];
+    let mut failures = 0;
+    for (number, level) in (1..).zip(levels) {
+        let level = match level {
+            Ok(level) => apply_level_overrides(level),
+            Err(e) => {
+                say!("Board #{}: {}", number, e);
+                failures += 1;
+                continue;
+            }
+        };
+        let analysis = Analysis::new(&level.board);
+        match (level.par, analysis.min_presses) {
+            (Some(par), Some(min_presses)) if par != min_presses => mismatches.push(tr!(
+                "Board #{} has par {}, but its fewest presses are {}.",
+                number,
+                par,
+                min_presses
+            )),
+            (Some(par), None) => mismatches.push(tr!(
+                "Board #{} has par {}, but its fewest presses could not be found.",
+                number,
+                par
+            )),
+            _ => {}
+        }
+        analyses.push((format!("#{}", number), analysis));
+    }
+    if !analyses.is_empty() {
+        print!("{}", analysis::summary_table(&analyses, style));
+    }
+    for mismatch in &mismatches {
+        println!("{}", mismatch);
+    }
+    say!(
+        "{} boards checked, {} could not be read, {} disagree with their par.",
+        analyses.len(),
+        failures,
+        mismatches.len()
+    );
+}
+
+/// Runs the `crosscheck` command. Compares the solutions linear algebra finds for each small board file with those found by brute
+/// force, and prints every disagreement with the board and presses that reproduce it.
+fn run_crosscheck(args: &[String]) {
+    if args.is_empty() {
+        say!("Usage: wayout-solver crosscheck <board> [<board>...]");
+        return;
+    }
+    for path in args {
+        let board = match read_board_file(path) {
+            Ok(board) => board,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        let discrepancies = match crosscheck::crosscheck(&board) {
+            Ok(discrepancies) => discrepancies,
+            Err(e) => {
+                println!("{}: {}", path, e);
+                continue;
+            }
+        };
+        if discrepancies.is_empty() {
+            say!("{}: brute force and linear algebra agree.", path);
+            continue;
+        }
+        say!("{}: {} discrepancies.", path, discrepancies.len());
+        for discrepancy in &discrepancies {
+            let (presses, message) = match discrepancy {
+                Discrepancy::Missed(presses) => (
+                    presses,
+                    tr!("These presses solve the board, but linear algebra missed them:"),
+                ),
+                Discrepancy::Spurious(presses) => (
+                    presses,
+                    tr!("Linear algebra gave these presses, but they do not solve the board:"),
+                ),
+            };
+            let presses: Vec<String> = presses
+                .iter()
+                .map(|pos| {
+                    tr!(
+                        "row {}, column {}",
+                        coords::row_number(pos.row, board.height()),
+                        coords::col_number(pos.col)
+                    )
+                })
+                .collect();
+            if presses.is_empty() {
+                println!("{} {}", message, tr!("no presses"));
+            } else {
+                println!("{} {}", message, presses.join("; "));
+            }
+        }
+        // Board files cannot express targets, so boards with other targets are reproduced as JSON.
+        let level = Level::with_par(board, None);
+        let reproducer = convert::render(&level, BoardFormat::Text)
+            .or_else(|_| convert::render(&level, BoardFormat::Json));
+        if let Ok(reproducer) = reproducer {
+            say!("Reproducer:\n{}", reproducer);
+        }
+    }
+}
+
+/// Runs the `stress` command. Checks the solver with `stress::check` on `--count` random boards of every variant, up to
+/// `--max-size` cells wide and high, seeded with `--seed`. Every failing board is saved as JSON to `--out-dir`, which defaults to
+/// `stress-failures`.
+fn run_stress(args: &[String]) {
+    let mut args = args.to_vec();
+    let options = cli::take_number(&mut args, "--count").and_then(|count| {
+        Ok((
+            count,
+            cli::take_number(&mut args, "--max-size")?,
+            cli::take_number(&mut args, "--seed")?,
+            cli::take_option(&mut args, "--out-dir")?,
+        ))
+    });
+    let (count, max_size, seed, out_dir) = match options {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    if !args.is_empty() {
+        say!("Usage: wayout-solver stress [--count <N>] [--max-size <N>] [--seed <N>] [--out-dir <dir>]");
+        return;
+    }
+    let (count, max_size) = (count.unwrap_or(1000), max_size.unwrap_or(5));
+    if count == 0 || max_size == 0 {
+        say!("The count and the maximum size must be positive integers.");
+        return;
+    }
+    let mut rng = seed.map_or_else(Rng::from_time, Rng::new);
+    let out_dir = out_dir.unwrap_or_else(|| "stress-failures".to_string());
+
+    let mut failures = 0;
+    for number in 1..=count {
+        let width = 1 + rng.below(max_size as u64) as usize;
+        let height = 1 + rng.below(max_size as u64) as usize;
+        let board = generator::random_variant(width, height, &mut rng);
+        let Err(problem) = stress::check(&board) else {
+            continue;
+        };
+        failures += 1;
+        let path = std::path::Path::new(&out_dir).join(format!("failure-{}.json", number));
+        let json = convert::render(&Level::with_par(board, None), BoardFormat::Json);
+        let saved = std::fs::create_dir_all(&out_dir)
+            .map_err(|e| e.to_string())
+            .and(json)
+            .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+        match saved {
+            Ok(()) => say!(
+                "Board #{} failed: {} Saved to {}.",
+                number,
+                problem,
+                path.display()
+            ),
+            Err(e) => say!(
+                "Board #{} failed: {} Could not save it: {}",
+                number,
+                problem,
+                e
+            ),
+        }
+    }
+    say!("{} boards checked, {} failed.", count, failures);
+}
+
+/// Runs the `compression` command. Compares the size of board files and their solutions as text with their packed binary form
+/// and share code, for the given board files, or for `--count <N>` random boards of up to `--max-size <N>` cells per side,
+/// seeded with `--seed <N>`.
+fn run_compression(args: &[String]) {
+    let mut args = args.to_vec();
+    let style = if cli::take_flag(&mut args, "--plain") {
+        TableStyle::Plain
+    } else {
+        TableStyle::Pretty
+    };
+    let options = cli::take_option(&mut args, "--count").and_then(|count| {
+        Ok((
+            count,
+            cli::take_option(&mut args, "--max-size")?,
+            cli::take_option(&mut args, "--seed")?,
+        ))
+    });
+    let (count, max_size, seed) = match options {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let parse_positive = |value: Option<String>, default: usize| match value {
+        None => Some(default),
+        Some(value) => value.parse::<usize>().ok().filter(|&n| n > 0),
+    };
+    let (Some(count), Some(max_size)) = (parse_positive(count, 100), parse_positive(max_size, 10))
+    else {
+        say!("The count and the maximum size must be positive integers.");
+        return;
+    };
+
+    let mut boards = vec![];
+    if args.is_empty() {
+        let mut rng = match seed.map(|seed| seed.parse::<u64>()) {
+            None => Rng::from_time(),
+            Some(Ok(seed)) => Rng::new(seed),
+            Some(Err(_)) => {
+                say!("Invalid seed.");
+                return;
+            }
+        };
+        for number in 1..=count {
+            let width = 1 + rng.below(max_size as u64) as usize;
+            let height = 1 + rng.below(max_size as u64) as usize;
+            boards.push((
+                tr!("random #{}", number),
+                generator::random_variant(width, height, &mut rng),
+            ));
+        }
+    } else {
+        for path in &args {
+            match read_board_file(path) {
+                Ok(board) => boards.push((path.clone(), board)),
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+
+    let mut table = Table::new(&[
+        tr!("Board"),
+        tr!("Text"),
+        tr!("Packed"),
+        tr!("Share code"),
+        tr!("Solution text"),
+        tr!("Solution packed"),
+    ])
+    .with_style(style);
+    let (mut text_total, mut packed_total) = (0, 0);
+    for (name, board) in &boards {
+        let text = board.to_board_text().len();
+        let packed = packed::pack_board(board).len();
+        text_total += text;
+        packed_total += packed;
+        let (solution_text, solution_packed) = match solver::minimum_solution(board) {
+            Some(solution) => (
+                solution.to_string().len().to_string(),
+                packed::pack_assignment(&solution).len().to_string(),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        table.add_row(&[
+            name.clone(),
+            text.to_string(),
+            packed.to_string(),
+            sharecode::encode(board).len().to_string(),
+            solution_text,
+            solution_packed,
+        ]);
+    }
+    print!("{}", table);
+    if let Some(percent) = (packed_total * 100).checked_div(text_total) {
+        say!(
+            "Packed boards take {}% of the bytes of their board files.",
+            percent
+        );
+    }
+}
+
+/// Runs the `heatmap` command. Shows how often each cell of a board file is pressed across all of its solutions, or across
+/// `--samples <N>` random ones when there are too many, seeded with `--seed <N>`.
+fn run_heatmap(args: &[String]) {
+    let mut args = args.to_vec();
+    let (samples, seed) = match cli::take_number(&mut args, "--samples")
+        .and_then(|samples| Ok((samples, cli::take_number(&mut args, "--seed")?)))
+    {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let samples = match samples {
+        None => 10000,
+        Some(0) => {
+            say!("The number of samples must be a positive integer.");
+            return;
+        }
+        Some(samples) => samples,
+    };
+    let mut rng = seed.map_or_else(Rng::from_time, Rng::new);
+    let [path] = args.as_slice() else {
+        say!("Usage: wayout-solver heatmap [--samples <N>] [--seed <N>] <board>");
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let Some(heatmap) = Heatmap::new(&board, samples, &mut rng) else {
+        say!("Board has no solutions.");
+        return;
+    };
+    if heatmap.is_exhaustive() {
+        say!(
+            "Press frequencies across all {} solutions:",
+            heatmap.solutions()
+        );
+    } else {
+        say!(
+            "Press frequencies across {} random solutions:",
+            heatmap.solutions()
+        );
+    }
+    println!("{}", heatmap);
+    say!(
+        "{} cells are pressed by every solution, and {} only by some.",
+        heatmap.mandatory().len(),
+        heatmap.interchangeable().len()
+    );
+    say!("Solutions by number of presses:");
+    for (presses, count) in heatmap.press_counts() {
+        say!("{} presses: {}", presses, count);
+    }
+}
+
+/// Runs the `unknowns` command. Reads a board file where `?` marks cells with unknown starting state, and reports for which
+/// values of those cells the board is solvable. With `--correct`, then asks for the value of every unknown cell, solving the board
+/// once none are left.
+fn run_unknowns(args: &[String]) {
+    let mut args = args.to_vec();
+    let correct = cli::take_flag(&mut args, "--correct");
+    let [path] = args.as_slice() else {
+        say!("Usage: wayout-solver unknowns [--correct] <board>");
+        return;
+    };
+    let mut partial = match std::fs::read_to_string(path)
+        .map_err(|e| tr!("Error reading board file {}: {}", path, e))
+        .and_then(|text| {
+            PartialBoard::parse(&text).map_err(|e| tr!("Error in board file {}: {}", path, e))
+        }) {
+        Ok(partial) => partial,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    print_solvability(&partial);
+    if !correct || partial.unknowns.is_empty() {
+        return;
+    }
+
+    let mut k = 0;
+    while let Some(&pos) = partial.unknowns.get(k) {
+        say!(
+            "Enter the value of the cell at row {}, column {}, or ? to leave it unknown. [0/1/?]",
+            coords::row_number(pos.row, partial.board.height()),
+            coords::col_number(pos.col)
+        );
+        match read_single_line().as_deref().map(str::trim) {
+            Ok("0") => partial.fix(k, Bit::Off),
+            Ok("1") => partial.fix(k, Bit::On),
+            Ok("?") => k += 1,
+            Ok(_) => say!("Please input either \"0\", \"1\" or \"?\"."),
+            Err(e) => {
+                say!("Error reading line: {}", e);
+                return;
+            }
+        }
+    }
+    if !partial.unknowns.is_empty() {
+        print_solvability(&partial);
+        return;
+    }
+    match solver::minimum_solution(&partial.board) {
+        Some(solution) => say!(
+            "Solution with {} button presses:\n{}",
+            solution.count_ones(),
+            solution
+        ),
+        None => say!("Board has no solutions."),
+    }
+}
+
+/// Prints the unknown cells of `partial`, and for which of their values it is solvable. Lists every solvable combination if there
+/// are at most `MAX_LISTED_UNKNOWNS` unknowns.
+fn print_solvability(partial: &PartialBoard) {
+    const MAX_LISTED_UNKNOWNS: usize = 6;
+    say!("Board has {} unknown cells.", partial.unknowns.len());
+    for (k, pos) in partial.unknowns.iter().enumerate() {
+        say!(
+            "u{} is at row {}, column {}.",
+            k + 1,
+            coords::row_number(pos.row, partial.board.height()),
+            coords::col_number(pos.col)
+        );
+    }
+    let solvability = partial.solvability();
+    match solvability.conditions() {
+        None => say!("The board is not solvable for any values of the unknown cells."),
+        Some([]) => say!("The board is solvable for every value of the unknown cells."),
+        Some(conditions) => {
+            say!("The board is solvable exactly when:");
+            for condition in conditions {
+                println!("  {}", condition);
+            }
+        }
+    }
+    let count = partial.unknowns.len();
+    if let Some(solvable) = solvability.solvable_count() {
+        say!(
+            "{} of the 2^{} combinations of values are solvable.",
+            solvable,
+            count
+        );
+    }
+    if count <= MAX_LISTED_UNKNOWNS {
+        for values in solvability.solvable_values() {
+            let terms: Vec<String> = (1..)
+                .zip(values)
+                .map(|(u, value)| format!("u{} = {}", u, value))
+                .collect();
+            println!("  {}", terms.join(", "));
+        }
+    }
+}
+
+/// Runs the `watch` command. Checks a board file for changes every `--interval <MS>` milliseconds, and shows the next press
+/// towards a solution with the fewest presses whenever it changes, until interrupted.
+fn run_watch(args: &[String]) {
+    let mut args = args.to_vec();
+    let interval = match cli::take_option(&mut args, "--interval") {
+        Ok(None) => 500,
+        Ok(Some(interval)) => match interval.parse::<u64>() {
+            Ok(interval) if interval > 0 => interval,
+            _ => {
+                say!("The interval must be a positive number of milliseconds.");
+                return;
+            }
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [path] = args.as_slice() else {
+        say!("Usage: wayout-solver watch [--interval <MS>] <board>");
+        return;
+    };
+
+    say!("Watching {} for changes. Press Ctrl-C to stop.", path);
+    let mut resolver = watch::Resolver::new();
+    let mut file = watch::FileWatch::new(std::path::Path::new(path));
+    loop {
+        if file.changed() {
+            match read_board_file(path) {
+                Ok(board) => match resolver.solve(&board) {
+                    Some(solution) if solution.count_ones() == 0 => {
+                        say!("The board is already solved.")
+                    }
+                    Some(solution) => {
+                        let hint = Hint::from_solution(solution);
+                        println!(
+                            "{} {}",
+                            hint.reveal(HintLevel::Count),
+                            hint.reveal(HintLevel::Cell)
+                        );
+                    }
+                    None => say!("Board has no solutions."),
+                },
+                Err(e) => println!("{}", e),
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(interval));
+    }
+}
+
+/// Runs the `oneshot` command. Solves a board given as a single argument, either a share code or a board with escaped newlines,
+/// and prints a compact answer that fits in a single chat message, hidden behind a spoiler if `--spoiler` is given.
+fn run_oneshot(args: &[String]) {
+    let mut args = args.to_vec();
+    let spoiler = match cli::take_option(&mut args, "--spoiler")
+        .and_then(|name| name.map(|name| cli::parse_spoiler(&name)).transpose())
+    {
+        Ok(spoiler) => spoiler,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [board] = args.as_slice() else {
+        say!("Usage: wayout-solver oneshot [--spoiler rot13|block] <board or share code>");
+        return;
+    };
+    let board = match oneshot::parse_board_argument(board) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    // Spoiler markers take up room in the message, so the answer is shortened to leave space for them.
+    let answer = oneshot::answer(&board, oneshot::MAX_ANSWER_LENGTH - 4);
+    match spoiler {
+        None => println!("{}", answer),
+        // Even encoded, the grid would give the presses away at a glance, so only the list of presses is kept.
+        Some(Spoiler::Rot13) => println!("{}", spoiler::rot13(answer.lines().next().unwrap_or(""))),
+        Some(Spoiler::Block) => println!("{}", Spoiler::Block.hide(&answer)),
+    }
+}
+
+/// Runs the `schema` command. Prints the JSON Schema of the board or result format, or the current schema version.
+fn run_schema(args: &[String]) {
+    match args {
+        [] => say!(
+            "Board schema version {}, result schema version {}. Available schemas: board, result.",
+            schema::SCHEMA_VERSION,
+            schema::RESULT_SCHEMA_VERSION
+        ),
+        [name] => match schema::schema(name) {
+            Some(schema) => print!("{}", schema),
+            None => say!(
+                "Unknown schema \"{}\". Available schemas: board, result.",
+                name
+            ),
+        },
+        _ => say!("Usage: wayout-solver schema [board|result]"),
+    }
+}
+
+/// Runs the `to-toml` command. Prints a board file, in either format, in the TOML format.
+fn run_to_toml(args: &[String]) {
+    let [path] = args else {
+        say!("Usage: wayout-solver to-toml <board>");
+        return;
+    };
+    match read_level_file(path) {
+        Ok(level) => match convert::render(&level, BoardFormat::Toml) {
+            Ok(text) => print!("{}", text),
+            Err(e) => println!("{}", e),
+        },
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Runs the `to-text` command. Prints a board file, in either format, in the plain format. Fails for boards with a target other
+/// than every cell lit, which the plain format cannot express.
+fn run_to_text(args: &[String]) {
+    let [path] = args else {
+        say!("Usage: wayout-solver to-text <board>");
+        return;
+    };
+    match read_level_file(path) {
+        Ok(level) if !level.board.has_default_target() => {
+            say!("The plain format cannot express the target of this board.")
+        }
+        Ok(level) => print!("{}", level.to_text()),
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Runs the `convert` command. Converts board files to the format chosen with `--to text|toml|json|code`, keeping their modifiers,
+/// target and par where the format can hold them. The format of each input is detected from its extension and contents, unless
+/// given with `--from`. A single board is printed, while several need `--out-dir`, where each is written under its own name.
+fn run_convert(args: &[String]) {
+    let mut args = args.to_vec();
+    let options = cli::take_option(&mut args, "--to").and_then(|to| {
+        Ok((
+            to,
+            cli::take_option(&mut args, "--from")?,
+            cli::take_option(&mut args, "--out-dir")?,
+        ))
+    });
+    let (to, from, out_dir) = match options {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let usage = "Usage: wayout-solver convert --to <text|toml|json|code> [--from <format>] [--out-dir <dir>] <board>...";
+    let Some(to) = to else {
+        println!("{}", usage);
+        return;
+    };
+    let (Some(to), Some(from)) = (
+        BoardFormat::from_name(&to),
+        from.as_deref()
+            .map_or(Some(None), |from| BoardFormat::from_name(from).map(Some)),
+    ) else {
+        say!("Unknown board format. Available formats: text, toml, json, code.");
+        return;
+    };
+    if args.is_empty() {
+        println!("{}", usage);
+        return;
+    }
+    if args.len() > 1 && out_dir.is_none() {
+        say!("Converting several boards needs --out-dir.");
+        return;
+    }
+
+    for path in &args {
+        let converted = std::fs::read_to_string(path)
+            .map_err(|e| tr!("Error reading board file {}: {}", path, e))
+            .and_then(|text| {
+                let format = from.unwrap_or_else(|| BoardFormat::detect(path, &text));
+                convert::parse(&text, format)
+                    .and_then(|level| convert::render(&apply_level_overrides(level), to))
+                    .map_err(|e| tr!("Error in board file {}: {}", path, e))
+            });
+        let converted = match converted {
+            Ok(converted) => converted,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        let Some(out_dir) = &out_dir else {
+            print!("{}", converted);
+            continue;
+        };
+        let stem = std::path::Path::new(path)
+            .file_stem()
+            .map_or_else(|| path.clone(), |stem| stem.to_string_lossy().into_owned());
+        let out_path = std::path::Path::new(out_dir).join(format!("{}.{}", stem, to.extension()));
+        match std::fs::write(&out_path, converted) {
+            Ok(()) => say!("Converted {} to {}.", path, out_path.display()),
+            Err(e) => say!("Error writing {}: {}", out_path.display(), e),
+        }
+    }
+}
+
+/// Runs the `export-matrix` command. Prints the augmented matrix of a board file, or with `--reduced` its reduced row-echelon
+/// form, in the format chosen with `--format text|market`. Columns follow the cells of the board in reading order.
+/// With `--inverse`, prints the inverse of the board matrix instead, augmented with the only solution of the board. Boards with
+/// unpressable cells have a pin row per such cell, so their matrix is not square and has no inverse.
+fn run_export_matrix(args: &[String]) {
+    let mut args = args.to_vec();
+    let reduced = cli::take_flag(&mut args, "--reduced");
+    let inverse = cli::take_flag(&mut args, "--inverse");
+    let format = match cli::take_option(&mut args, "--format") {
+        Ok(None) => MatrixFormat::Text,
+        Ok(Some(name)) => match MatrixFormat::from_name(&name) {
+            Some(format) => format,
+            None => {
+                say!(
+                    "Unknown matrix format \"{}\". Available formats: text, market.",
+                    name
+                );
+                return;
+            }
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [path] = args.as_slice() else {
+        say!(
+            "Usage: wayout-solver export-matrix [--format text|market] [--reduced | --inverse] <board>"
+        );
+        return;
+    };
+    let board = match read_board_file(path) {
+        Ok(board) => board,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let (mut matrix, _) = board.to_matrix();
+    if inverse {
+        // Drop the constant column, and append the particular solution as the new one.
+        let (square, constants) = split_constant_column(&matrix);
+        if square.rows != square.cols {
+            say!("Board matrix is not square, as some cells cannot be pressed, so it has no inverse.");
+            return;
+        }
+        let Some(inverse) = square.inverse() else {
+            say!("Board matrix is not invertible, as the board has free variables.");
+            return;
+        };
+        matrix = inverse.clone();
+        matrix.augment_column(&inverse.mul_vector(&constants));
+    } else if reduced {
+        matrix.eliminate();
+    }
+    print!("{}", matrix_io::export(&matrix, format));
+}
+
+/// Renders a list of indices separated by commas, or `-` if it is empty.
+fn join_indices(indices: &[usize]) -> String {
+    if indices.is_empty() {
+        return "-".to_string();
+    }
+    let indices: Vec<String> = indices.iter().map(usize::to_string).collect();
+    indices.join(", ")
+}
+
+/// Splits an augmented matrix into its variable part and its constant column, the first augmented one.
+fn split_constant_column(matrix: &Matrix) -> (Matrix, Vec<Bit>) {
+    let n = matrix.variable_cols();
+    let data = (0..matrix.rows)
+        .map(|row| matrix.row(row)[..n].to_vec())
+        .collect();
+    let constants = (0..matrix.rows).map(|row| matrix.get(row, n)).collect();
+    (
+        Matrix::new(data, matrix.rows, n).expect("Augmented matrices have a variable column"),
+        constants,
+    )
+}
+
+/// Runs the `solve-matrix` command. Solves an augmented matrix over GF(2), read from a file in either matrix format or from
+/// standard input if the path is `-`, without any board. Prints its reduced row-echelon form, rank, a basis of its kernel, and up to
+/// `--limit <N>` of its solutions in enumeration order, 16 by default.
+fn run_solve_matrix(args: &[String]) {
+    let mut args = args.to_vec();
+    let limit = match cli::take_option(&mut args, "--limit") {
+        Ok(None) => 16,
+        Ok(Some(limit)) => match limit.parse::<u128>() {
+            Ok(limit) => limit,
+            Err(_) => {
+                say!("The limit must be a non-negative integer.");
+                return;
+            }
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [path] = args.as_slice() else {
+        say!("Usage: wayout-solver solve-matrix [--limit <N>] <matrix>");
+        return;
+    };
+    let text = if path == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(path)
+    };
+    let matrix = match text
+        .map_err(|e| tr!("Error reading matrix file {}: {}", path, e))
+        .and_then(|text| {
+            matrix_io::import(&text).map_err(|e| tr!("Error in matrix file {}: {}", path, e))
+        }) {
+        Ok(matrix) => matrix,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let report = Report::new(&matrix);
+    let eqns = &report.equations;
+    say!("Reduced matrix:");
+    print!("{}", matrix_io::export(&report.reduced, MatrixFormat::Text));
+    say!(
+        "Rank {}, with {} variables and {} free variables.",
+        report.rank,
+        matrix.variable_cols(),
+        eqns.free_var_count()
+    );
+    say!("Pivot columns: {}", join_indices(&report.pivot_columns));
+    say!("Redundant rows: {}", join_indices(&report.redundant_rows));
+    print_solutions(eqns, limit);
+}
+
+/// Prints the kernel basis of `eqns`, its number of solutions, and the first `limit` of them as bit strings.
+fn print_solutions(eqns: &Equations, limit: u128) {
+    let basis = eqns.kernel_basis();
+    if !basis.is_empty() {
+        say!("Kernel basis:");
+        for vector in &basis {
+            println!(
+                "{}",
+                Assignment::from_bits(vector)
+                    .as_bitstring()
+                    .unwrap_or_default()
+            );
+        }
+    }
+    let count = eqns.solution_count();
+    match count {
+        Some(0) => {
+            say!("System has no solutions.");
+            return;
+        }
+        Some(count) => say!("System has {} solutions.", count),
+        None => say!("System has 2^{} solutions.", eqns.free_var_count()),
+    }
+    if count.is_none_or(|count| count > limit) {
+        say!("Showing the first {}:", limit);
+    }
+    for index in 0..count.map_or(limit, |count| count.min(limit)) {
+        if let Some(bits) = eqns
+            .solution_by_index(index)
+            .and_then(|solution| solution.as_bitstring())
+        {
+            println!("{}", bits);
+        }
+    }
+}
+
+/// Runs the `export-equations` command. Prints the system of equations of a board file as JSON, for `solve-equations` to query
+/// without eliminating again.
+fn run_export_equations(args: &[String]) {
+    let [path] = args else {
+        say!("Usage: wayout-solver export-equations <board>");
+        return;
+    };
+    match read_board_file(path) {
+        Ok(board) => {
+            let (matrix, _) = board.to_matrix();
+            println!("{}", Report::new(&matrix).equations.to_json());
+        }
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Runs the `solve-equations` command. Lists the solutions of a system of equations exported by `export-equations`, read from a
+/// file or from standard input when the path is `-`, like `solve-matrix` does. `--limit <N>` shows at most `N` solutions.
+fn run_solve_equations(args: &[String]) {
+    let mut args = args.to_vec();
+    let limit = match cli::take_option(&mut args, "--limit") {
+        Ok(None) => 16,
+        Ok(Some(limit)) => match limit.parse::<u128>() {
+            Ok(limit) => limit,
+            Err(_) => {
+                say!("The limit must be a non-negative integer.");
+                return;
+            }
+        },
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    let [path] = args.as_slice() else {
+        say!("Usage: wayout-solver solve-equations [--limit <N>] <equations>");
+        return;
+    };
+    let text = if path == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(path)
+    };
+    let eqns = match text
+        .map_err(|e| tr!("Error reading equations file {}: {}", path, e))
+        .and_then(|text| {
+            Equations::from_json(&text).map_err(|e| tr!("Error in equations file {}: {}", path, e))
+        }) {
+        Ok(eqns) => eqns,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+    say!(
+        "{} variables and {} free variables.",
+        eqns.var_count(),
+        eqns.free_var_count()
+    );
+    print_solutions(&eqns, limit);
+}
+
+/// Runs the `template` command, which saves, lists and removes named board shape templates.
+fn run_template(args: &[String]) {
+    let result = match args {
+        [action, name, path] if action == "save" => read_board_file(path).and_then(|board| {
+            let Some(decoder) = CosetDecoder::new(&board) else {
+                return Err(tr!(
+                    "Board has more than {} free variables, too many for a template.",
+                    decoder::MAX_DECODER_NULLITY
+                ));
+            };
+            template::save_template(name, &decoder)?;
+            say!(
+                "Saved template \"{}\" with {} free variables.",
+                name,
+                decoder.nullity()
+            );
+            Ok(())
+        }),
+        [action] if action == "list" => template::list_templates().map(|names| {
+            if names.is_empty() {
+                say!("No saved templates.");
+            }
+            for name in names {
+                println!("{}", name);
+            }
+        }),
+        [action, name] if action == "remove" => template::remove_template(name),
+        _ => Err(tr!(
+            "Usage: wayout-solver template save <name> <board> | list | remove <name>"
+        )),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+/// Runs the `target` command, which saves, lists and removes named targets for `--target-name`.
+fn run_target(args: &[String]) {
+    let result = match args {
+        [action, name, path] if action == "save" => std::fs::read_to_string(path)
+            .map_err(|e| tr!("Error reading target file {}: {}", path, e))
+            .and_then(|text| {
+                let rows: Vec<String> = text.lines().map(str::to_string).collect();
+                targets::save_target(name, &rows)?;
+                say!("Saved target \"{}\".", name);
+                Ok(())
+            }),
+        [action] if action == "list" => targets::list_targets().map(|names| {
+            if names.is_empty() {
+                say!("No saved targets.");
+            }
+            for name in names {
+                println!("{}", name);
+            }
+        }),
+        [action, name] if action == "remove" => targets::remove_target(name),
+        _ => Err(tr!(
+            "Usage: wayout-solver target save <name> <target> | list | remove <name>"
+        )),
+    };
+    if let Err(e) = result {
+        println!("{}", e);
+    }
+}
+
+/// Solves the board in `board_text` with the precomputed `decoder`, taking the holes and modifiers from the template shape.
+fn solve_with_template(decoder: &CosetDecoder, board_text: &str, options: &Options) {
+    let start = match board::parse_board_file(board_text) {
+        Ok(board) => board,
+        Err(e) => {
+            say!(
+                "Error parsing board with text {}.\nError: {}",
+                board_text,
+                e
+            );
+            return;
+        }
+    };
+    let Some(board) = start.with_shape_of(decoder.shape()) else {
+        say!("Board does not match the size and holes of the template.");
+        return;
+    };
+    match decoder.decode(&board) {
+        Ok(Some(solution)) => {
+            say!(
+                "Solution with {} button presses:\n{}",
+                solution.count_ones(),
+                render_solution(&solution, options)
+            );
+            if let Some(path) = &options.final_board {
+                write_final_board(path, &board, &solution);
+            }
+        }
+        Ok(None) => say!("Board has no solutions."),
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Reads and parses the board file at `path`.
+fn read_board_file(path: &str) -> Result<BoardDescription, String> {
+    if path.ends_with(".toml") {
+        return read_level_file(path).map(|level| level.board);
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| tr!("Error reading board file {}: {}", path, e))?;
+    board::parse_board_file(&text)
+        .map(apply_overrides)
+        .map_err(|e| tr!("Error in board file {}: {}", path, e))
+}
+
+/// Reads a level out of a board file, in the TOML format if its name ends in `.toml`, and in the plain format otherwise.
+fn read_level_file(path: &str) -> Result<Level, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| tr!("Error reading board file {}: {}", path, e))?;
+    let level = if path.ends_with(".toml") {
+        convert::parse(&text, BoardFormat::Toml)
+    } else {
+        Level::parse(&text)
+    };
+    let level = level.map_err(|e| tr!("Error in board file {}: {}", path, e))?;
+    Ok(apply_level_overrides(level))
+}
+
+/// Applies `apply_overrides` to the board of `level`. If that changes the puzzle, its par is computed again.
+fn apply_level_overrides(level: Level) -> Level {
+    let overridden = RIGID
+        .get()
+        .is_some_and(|&rigid| rigid != level.board.rigid_semantics())
+        || (target_off() && !level.board.has_uniform_target(Bit::Off));
+    if overridden {
+        Level::new(apply_overrides(level.board))
+    } else {
+        level
+    }
+}
+
+/// Gives `board` the rigid semantics chosen with `--rigid` and the all-off target chosen with `--target-off`, if any.
+fn apply_overrides(mut board: BoardDescription) -> BoardDescription {
+    if let Some(&rigid) = RIGID.get() {
+        board.set_rigid_semantics(rigid);
+    }
+    if target_off() {
+        board.set_uniform_target(Bit::Off);
+    }
+    board
+}
+
+/// Whether `--target-off` was given.
+fn target_off() -> bool {
+    TARGET_OFF.get().copied().unwrap_or(false)
+}
+
+/// Reads the text of the board given with `--input` or `--board`, or returns `None` if neither was given. The text of `--board`
+/// may separate its rows with the two characters `\n` instead of newlines.
+fn board_input(options: &Options) -> Option<Result<String, String>> {
+    if let Some(path) = &options.input {
+        return Some(
+            std::fs::read_to_string(path)
+                .map(|text| text.trim_end().to_string())
+                .map_err(|e| tr!("Error reading board file {}: {}", path, e)),
+        );
+    }
+    options
+        .board
+        .as_ref()
+        .map(|text| Ok(text.trim_end().replace("\\n", "\n")))
+}
+
+/// Parses a board given with `--input` or `--board` without asking anything. The modifiers come from the modifier grid of the
+/// board text, and the target from `target`, the saved target named `target_name`, or `--target-off`.
+fn parse_board_input(
+    board_text: &str,
+    target: Option<&[String]>,
+    target_name: Option<&str>,
+) -> Option<BoardDescription> {
+    let mut board = match board::parse_board_file(board_text) {
+        Ok(board) => apply_overrides(board),
+        Err(e) => {
+            say!(
+                "Error parsing board with text {}.\nError: {}",
+                board_text,
+                e
+            );
+            return None;
+        }
+    };
+    if let Some(target) = target {
+        if let Err(e) = board.apply_target(target) {
+            say!(
+                "Error in target \"{}\": {}",
+                target_name.unwrap_or_default(),
+                e
+            );
+            return None;
+        }
+    } else if target_off() {
+        board.set_uniform_target(Bit::Off);
+    }
+    Some(board)
+}
+
+/// Parses the board entered as `board_text`, then asks for its modifiers and, unless `target` is given, for its target. The
+/// target is the saved target named `target_name`.
+fn prompt_board(
+    board_text: &str,
+    target: Option<&[String]>,
+    target_name: Option<&str>,
+) -> Option<BoardDescription> {
+    // Boards of only holes are rejected here, before any prompt.
+    let mut board = match board::parse_board_file(board_text) {
+        Ok(board) => apply_overrides(board),
+        Err(e) => {
+            say!(
+                "Error parsing board with text {}.\nError: {}",
+                board_text,
+                e
+            );
+            return None;
+        }
+    };
+
+    say!("Does board contain modifiers? [yes/no]");
+
+    let has_modifiers = match read_yes_no() {
+        Ok(has_modifiers) => has_modifiers,
+        Err(e) => {
+            say!("Error reading yes/no answer: {}", e);
+            return None;
+        }
+    };
+
+    if has_modifiers {
+        say!("Enter modifiers as a grid of spaces, \"H\"s, \"V\"s and \"O\"s.");
+        let mut lines = vec![];
+        for line in std::io::stdin().lines().take(board.height()) {
+            match line {
+                Ok(line) => lines.push(line),
+                Err(e) => {
+                    say!("Could not read line: {}", e);
+                    return None;
+                }
+            }
+        }
+        for (row, line) in lines.iter().enumerate() {
+            for (col, ch) in (0..board.width()).zip(line.chars()) {
+                if ch == ' ' {
+                    continue;
+                }
+                let modifier = if MODIFIERS.contains(&ch) {
+                    ch
+                } else {
+                    let Some(suggestion) = closest_modifier(ch) else {
+                        say!(
+                            "Ignoring unknown modifier '{}' at row {}, column {}.",
+                            ch,
+                            coords::row_number(row, board.height()),
+                            coords::col_number(col)
+                        );
+                        continue;
+                    };
+                    say!(
+                        "Unknown modifier '{}' at row {}, column {}. Did you mean '{}'? [yes/no]",
+                        ch,
+                        coords::row_number(row, board.height()),
+                        coords::col_number(col),
+                        suggestion
+                    );
+                    match read_yes_no() {
+                        Ok(true) => suggestion,
+                        Ok(false) => {
+                            say!("Ignoring '{}'.", ch);
+                            continue;
+                        }
+                        Err(e) => {
+                            say!("Error reading yes/no answer: {}", e);
+                            return None;
+                        }
+                    }
+                };
+                match &mut board[Position { row, col }] {
+                    Some(cell) => {
+                        cell.apply_modifier(modifier);
+                    }
+                    None => {
+                        say!("Modifier applied to empty cell!");
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(target) = target {
+        if let Err(e) = board.apply_target(target) {
+            say!(
+                "Error in target \"{}\": {}",
+                target_name.unwrap_or_default(),
+                e
+            );
+            return None;
+        }
+    } else if !target_off() {
+        say!("Should every light end up off instead of on? [yes/no]");
+        match read_yes_no() {
+            Ok(true) => board.set_uniform_target(Bit::Off),
+            Ok(false) => {}
+            Err(e) => {
+                say!("Error reading yes/no answer: {}", e);
+                return None;
+            }
+        }
+    }
+
+    Some(board)
+}
+
+fn read_board_text() -> Result<String, Error> {
+    let mut board_text = String::new();
+
+    for line in std::io::stdin().lines() {
+        let line = line?;
+        if line == "done" || line == tr!("done") {
+            break;
+        }
+        if is_valid_line(&line) {
+            board_text.push_str(&line);
+            board_text.push('\n');
+        } else {
+            say!("Invalid line. Please only enter 0's 1's and spaces.");
+            continue;
+        }
+    }
+
+    board_text.pop();
+
+    Ok(board_text)
+}
+
+/// Trims the rows and columns of holes off the edges of `board`, and has coordinates count from the board as it was entered.
+fn crop_board(board: BoardDescription) -> BoardDescription {
+    let (cropped, offset) = board.cropped();
+    if cropped.height() == board.height() && cropped.width() == board.width() {
+        return board;
+    }
+    let margins = coords::Margins {
+        top: offset.row,
+        bottom: board.height() - cropped.height() - offset.row,
+        left: offset.col,
+    };
+    coords::set_margins(margins);
+    say!(
+        "Cropped the margins of holes off the board: {} rows at the top, {} at the bottom, {} columns at the left and {} at the right. Coordinates still count from the board as entered.",
+        margins.top,
+        margins.bottom,
+        margins.left,
+        board.width() - cropped.width() - offset.col
+    );
+    cropped
+}
+
+/// Reads the cost of pressing each cell of `board` from `stdin`, a row of digits at a time until "done", and asks again until they
+/// fit the board.
+fn read_costs(board: &BoardDescription) -> Result<Vec<u32>, Error> {
+    loop {
+        say!("Enter the cost of pressing each cell line by line, as a digit per cell and a space for each cell without a light. When finished, type 'done' on its own line.");
+        let mut rows = vec![];
+        let mut ended = true;
+        for line in std::io::stdin().lines() {
+            let line = line?;
+            if line == "done" || line == tr!("done") {
+                ended = false;
+                break;
+            }
+            rows.push(line);
+        }
+        match board.parse_costs(&rows) {
+            Ok(costs) => return Ok(costs),
+            Err(e) => println!("{}", e),
+        }
+        if ended {
+            return Err(Error::from(std::io::ErrorKind::UnexpectedEof));
+        }
+    }
+}
+
+/// Reads the costs of pressing the cells of `board` from a cost file, laid out like `BoardDescription::parse_costs` reads them.
+fn read_costs_file(path: &str, board: &BoardDescription) -> Result<Vec<u32>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| tr!("Error reading cost file {}: {}", path, e))?;
+    let rows: Vec<String> = text.lines().map(str::to_string).collect();
+    board
+        .parse_costs(&rows)
+        .map_err(|e| tr!("Error in cost file {}: {}", path, e))
+}
+
+/// Reads a single line from `stdin` and returns it as a `String`.
+fn read_single_line() -> Result<String, Error> {
+    match std::io::stdin().lines().next() {
+        Some(line) => line,
+        None => {
+            say!("Could not read line.");
+            panic!();
+        }
+    }
+}
+
+/// Reads a single `yes/no` response, and returns it as a `bool`.
+fn read_yes_no() -> Result<bool, Error> {
+    loop {
+        let line = read_single_line()?;
+        if line == "yes" || line == tr!("yes") {
+            return Ok(true);
+        } else if line == "no" || line == tr!("no") {
+            return Ok(false);
+        } else {
+            say!("Please input either \"yes\" or \"no\". [yes/no]");
+        }
+    }
+}
+
+/// Checks if `src` is a valid description of a board row.
+pub fn is_valid_line(src: &str) -> bool {
+    src.chars().all(|c| c == '0' || c == '1' || c == ' ')
 }
 
 /// Old monolithic function that did the entire process based on a string input. No longer used.
+#[allow(dead_code)]
 fn do_it_all(src: &str) {
-    let board = board::parse_board(src)
-        .expect("Could not parse board from input.")
-        .1;
+    let board = board::parse_board_file(src).expect("Could not parse board from input.");
     println!("Input: \n{}", board);
     let (mut matrix, indexed_locations) = board.to_matrix();
     println!("Indexed Locations:\n{:?}", &indexed_locations);
@@ -246,9 +3699,8 @@ fn do_it_all(src: &str) {
     println!("{}", &matrix);
     let eqns = Equations::new(matrix);
     println!("Equations:\n{}", &eqns);
-    let results = eqns.enumerate_all_results();
-    println!("Found {} solutions:", results.len());
-    for assignment in results {
+    println!("Found {} solutions:", eqns.solution_count().unwrap_or(0));
+    for assignment in eqns.results() {
         println!(
             "Assignment:\n{}\nResult:\n{}",
             assignment.clone(),
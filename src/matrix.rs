@@ -6,11 +6,41 @@ use crate::bit::Bit;
 ///
 /// We do not use Grid as the backing structure as we have many matrix operations that are most effectively
 /// expressed in terms of row operations, which are easier to perform, on a vector of rows, rather than a monolithic row-major vector.
+/// Each row is packed 64 bits to a word by `pack_row`, so adding one row to another takes a single XOR for every 64 columns.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Matrix {
     pub rows: usize,
     pub cols: usize,
-    pub data: Vec<Vec<Bit>>,
+    /// How many of the rightmost columns were added with `augment_column` or `augment_columns`, and hold right-hand sides rather
+    /// than variables.
+    pub augmented_cols: usize,
+    /// The rows, packed by `pack_row`. The bits past the last column are always zero.
+    data: Vec<Vec<u64>>,
+}
+
+/// How `Matrix::eliminate_with` picks the next pivot column. Pivot columns become the dependent variables of the system, and the
+/// remaining ones its free variables, so the strategy decides which cells the equations are written in terms of.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum PivotStrategy {
+    /// The leftmost column with an active bit, as `eliminate` does.
+    #[default]
+    FirstNonZero,
+    /// The column with the fewest active bits in the rows that have not been pivoted on yet.
+    Sparsest,
+    /// The column with the most active bits in the rows that have not been pivoted on yet.
+    Heaviest,
+}
+
+impl PivotStrategy {
+    /// Finds the strategy for a name, `first`, `sparsest` or `heaviest`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "first" => Some(PivotStrategy::FirstNonZero),
+            "sparsest" => Some(PivotStrategy::Sparsest),
+            "heaviest" => Some(PivotStrategy::Heaviest),
+            _ => None,
+        }
+    }
 }
 
 /// Encodes a position within a matrix.
@@ -26,52 +56,142 @@ impl BoundedPosition {
     /// Steps to the right, unless at the edge. Returns true if the operation was successful, false if it would lead to an out of bounds position.
     pub fn step_right(&mut self) -> bool {
         if self.col + 1 == self.width {
-            return false;
+            false
         } else {
             self.col += 1;
-            return true;
+            true
         }
     }
 
     /// Steps downwards, unless at the edge. Returns true if the operation was successful, false if it would lead to an out of bounds position.
     pub fn step_down(&mut self) -> bool {
         if self.row + 1 == self.height {
-            return false;
+            false
         } else {
             self.row += 1;
-            return true;
+            true
         }
     }
 }
 
 /// Finds the next active bit in `row`, starting at index `since`.
-pub fn first_active_column_since(row: &[Bit], since: usize) -> Option<usize> {
-    for i in since..row.len() {
-        if row[i] == Bit::On {
-            return Some(i);
-        }
-    }
-    return None;
+pub(crate) fn first_active_column_since(row: &[Bit], since: usize) -> Option<usize> {
+    (since..row.len()).find(|&i| row[i] == Bit::On)
 }
 
 /// Finds the first active bit in `row`.
-pub fn get_leading_column(row: &[Bit]) -> Option<usize> {
+pub(crate) fn get_leading_column(row: &[Bit]) -> Option<usize> {
     first_active_column_since(row, 0)
 }
 
+/// The number of columns packed into each word by `pack_row`.
+pub(crate) const WORD_BITS: usize = u64::BITS as usize;
+
+/// Packs `row` into words, where bit `i % 64` of word `i / 64` holds the bit at index `i`.
+pub(crate) fn pack_row(row: &[Bit]) -> Vec<u64> {
+    let mut words = vec![0; row.len().div_ceil(WORD_BITS)];
+    for (i, &bit) in row.iter().enumerate() {
+        if bit == Bit::On {
+            words[i / WORD_BITS] |= 1 << (i % WORD_BITS);
+        }
+    }
+    words
+}
+
+/// Reads the bit at index `col` of a row packed by `pack_row`.
+pub(crate) fn packed_bit(words: &[u64], col: usize) -> bool {
+    words[col / WORD_BITS] >> (col % WORD_BITS) & 1 == 1
+}
+
+/// Finds the first active bit of a row packed by `pack_row`.
+fn packed_leading_column(words: &[u64]) -> Option<usize> {
+    let (index, word) = words.iter().enumerate().find(|(_, &word)| word != 0)?;
+    Some(index * WORD_BITS + word.trailing_zeros() as usize)
+}
+
+/// Adds the packed row `source` onto the packed row `target`, word by word.
+fn add_packed(target: &mut [u64], source: &[u64]) {
+    for (word, &source_word) in target.iter_mut().zip(source) {
+        *word ^= source_word;
+    }
+}
+
+/// Unpacks the first `len` bits of a row packed by `pack_row`.
+pub(crate) fn unpack_row(words: &[u64], len: usize) -> Vec<Bit> {
+    (0..len)
+        .map(|col| {
+            if packed_bit(words, col) {
+                Bit::On
+            } else {
+                Bit::Off
+            }
+        })
+        .collect()
+}
+
 impl Display for Matrix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for row in 0..self.rows {
             for col in 0..self.cols {
-                write!(f, "{}", self.data[row][col])?;
+                if col == self.variable_cols() {
+                    write!(f, " | ")?;
+                }
+                write!(f, "{}", self.get(row, col))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A matrix displayed under a header of column labels. Built by `Matrix::labeled`.
+pub struct LabeledMatrix<'a, F> {
+    matrix: &'a Matrix,
+    label: F,
+}
+
+impl<F: Fn(usize) -> String> Display for LabeledMatrix<'_, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let matrix = self.matrix;
+        let labels: Vec<String> = (0..matrix.cols)
+            .map(|col| {
+                if col < matrix.variable_cols() {
+                    (self.label)(col)
+                } else {
+                    "c".to_string()
+                }
+            })
+            .collect();
+        let write_line = |f: &mut std::fmt::Formatter<'_>,
+                          cells: &mut dyn Iterator<Item = String>| {
+            for (col, cell) in cells.enumerate() {
+                if col == matrix.variable_cols() {
+                    write!(f, " | ")?;
+                } else if col > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{:>width$}", cell, width = labels[col].chars().count())?;
             }
-            write!(f, "\n")?;
+            writeln!(f)
+        };
+        write_line(f, &mut labels.iter().cloned())?;
+        for row in 0..matrix.rows {
+            write_line(f, &mut matrix.row(row).iter().map(Bit::to_string))?;
         }
         Ok(())
     }
 }
 
 impl Matrix {
+    /// Displays `self` under a header that names every variable column by `label`, and the augmented columns `c`, so a reduced
+    /// matrix can be read against the board.
+    pub fn labeled<F: Fn(usize) -> String>(&self, label: F) -> LabeledMatrix<'_, F> {
+        LabeledMatrix {
+            matrix: self,
+            label,
+        }
+    }
+
     /// Creates a new matrix from a vec of rows. `data` must have exactly `rows` entries, and each row in `data` must have `cols` elements.
     /// If not, returns None. Furthermore, `rows` and `cols` must both be non-zero, otherwise returns `None`.
     pub fn new(data: Vec<Vec<Bit>>, rows: usize, cols: usize) -> Option<Self> {
@@ -87,7 +207,37 @@ impl Matrix {
             }
         }
 
-        Some(Matrix { rows, cols, data })
+        Some(Matrix {
+            rows,
+            cols,
+            augmented_cols: 0,
+            data: data.iter().map(|row| pack_row(row)).collect(),
+        })
+    }
+
+    /// Returns the value at row `row` and column `col`. Panics if out of bounds of `self`.
+    pub fn get(&self, row: usize, col: usize) -> Bit {
+        assert!(col < self.cols, "Column {} is out of bounds", col);
+        if packed_bit(&self.data[row], col) {
+            Bit::On
+        } else {
+            Bit::Off
+        }
+    }
+
+    /// Unpacks the row at index `row`.
+    pub fn row(&self, row: usize) -> Vec<Bit> {
+        unpack_row(&self.data[row], self.cols)
+    }
+
+    /// Finds the leading column of the row at index `row`, its first active bit, or `None` if the row is zero.
+    pub fn leading_column(&self, row: usize) -> Option<usize> {
+        packed_leading_column(&self.data[row])
+    }
+
+    /// Counts the columns holding variables, which are all but the augmented ones.
+    pub fn variable_cols(&self) -> usize {
+        self.cols - self.augmented_cols
     }
 
     /// Attempts to augment self with a new column. `col` must have as many elements as `self` has rows. Returns true if successful,
@@ -96,26 +246,42 @@ impl Matrix {
         if col.len() != self.rows {
             return false;
         }
-        for row in 0..self.rows {
-            self.data[row].push(col[row]);
+        let index = self.cols;
+        for (row, &bit) in self.data.iter_mut().zip(col) {
+            if index.is_multiple_of(WORD_BITS) {
+                row.push(0);
+            }
+            if bit == Bit::On {
+                row[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+            }
         }
         self.cols += 1;
-        return true;
+        self.augmented_cols += 1;
+        true
+    }
+
+    /// Attempts to augment self with several columns, in order. Every column must have as many elements as `self` has rows.
+    /// Returns true if successful, false otherwise. If false is returned, self remains untouched.
+    pub fn augment_columns(&mut self, cols: &[Vec<Bit>]) -> bool {
+        if cols.iter().any(|col| col.len() != self.rows) {
+            return false;
+        }
+        for col in cols {
+            self.augment_column(col);
+        }
+        true
     }
 
     /// Returns the value at position `pos`. Panics if out of bounds of `self`.
     pub fn get_at(&self, pos: BoundedPosition) -> Bit {
-        self.data[pos.row][pos.col]
+        self.get(pos.row, pos.col)
     }
 
     /// Sorts rows based on how deep its leading column is. A row with a leftmost leading column is considered earlier in the ordering.
     /// Further active bits in the column do not affect the order.
     pub fn sort_rows_by_leading_column(&mut self) {
         self.data
-            .sort_unstable_by_key(|row| match get_leading_column(row) {
-                Some(col) => col,
-                None => self.cols,
-            });
+            .sort_unstable_by_key(|row| packed_leading_column(row).unwrap_or(self.cols));
     }
 
     /// Swaps two rows at indices `row1` and `row2`. If both indices are the same, nothing happens.
@@ -125,10 +291,19 @@ impl Matrix {
 
     /// Adds the rows at indices `source_row` and `target_row`, storing the result in `target_row`.
     pub fn elementary_add_row_to(&mut self, source_row: usize, target_row: usize) {
-        let row = self.data[source_row].clone();
-        for col in 0..self.cols {
-            self.data[target_row][col] += row[col];
+        if source_row == target_row {
+            // A row plus itself is zero.
+            self.data[target_row].fill(0);
+            return;
         }
+        let (source, target) = if source_row < target_row {
+            let (before, after) = self.data.split_at_mut(target_row);
+            (&before[source_row], &mut after[0])
+        } else {
+            let (before, after) = self.data.split_at_mut(source_row);
+            (&after[0], &mut before[target_row])
+        };
+        add_packed(target, source);
     }
 
     /// Adds `source_row` onto every row whose `column` bit is `On`. Does not affect row at `source_row` itself.
@@ -138,7 +313,7 @@ impl Matrix {
             if row == source_row {
                 continue;
             }
-            if self.data[row][column] == Bit::On {
+            if packed_bit(&self.data[row], column) {
                 self.elementary_add_row_to(source_row, row);
             }
         }
@@ -146,138 +321,461 @@ impl Matrix {
 
     /// Performs Gauss-Jordan elimination on `self` over the field of bits. Once complete, `self` will be in reduced row-echelon form.
     pub fn eliminate(&mut self) {
-        self.sort_rows_by_leading_column();
+        let mut pivot_row = 0;
+        for col in 0..self.cols {
+            if pivot_row == self.rows {
+                break;
+            }
+            let Some(found) = (pivot_row..self.rows).find(|&row| packed_bit(&self.data[row], col))
+            else {
+                continue;
+            };
+            self.data.swap(pivot_row, found);
+            let (before, rest) = self.data.split_at_mut(pivot_row);
+            let (pivot, after) = rest.split_first_mut().expect("The pivot row is in bounds");
+            // The pivot row is zero left of `col`, so the words before the one holding `col` are left as they are.
+            let first_word = col / WORD_BITS;
+            for row in before.iter_mut().chain(after) {
+                if packed_bit(row, col) {
+                    add_packed(&mut row[first_word..], &pivot[first_word..]);
+                }
+            }
+            pivot_row += 1;
+        }
+    }
 
-        let mut current_cell = BoundedPosition {
-            width: self.cols,
-            height: self.rows,
-            row: 0,
-            col: 0,
-        };
+    /// Performs Gauss-Jordan elimination on `self`, picking pivot columns according to `strategy`. Returns the order of the columns
+    /// of the result: its column `i` is column `order[i]` of the original matrix.
+    ///
+    /// The pivot columns are chosen first, and then moved to the front so that `self` ends up in reduced row-echelon form, with its
+    /// pivot columns leading. Augmented columns always stay last. With the default strategy, this is `eliminate`, and the order is
+    /// unchanged.
+    pub fn eliminate_with(&mut self, strategy: PivotStrategy) -> Vec<usize> {
+        if strategy == PivotStrategy::FirstNonZero {
+            self.eliminate();
+            return (0..self.cols).collect();
+        }
+        let pivots = self.choose_pivots(strategy);
+        let order: Vec<usize> = pivots
+            .iter()
+            .copied()
+            .chain((0..self.cols).filter(|col| !pivots.contains(col)))
+            .collect();
+        self.permute_columns(&order);
+        self.eliminate();
+        order
+    }
 
+    /// Eliminates a copy of `self` one pivot at a time, picking each pivot column among the variable columns by `strategy`.
+    /// Returns the pivot columns in the order they were picked. Ties go to the leftmost column.
+    fn choose_pivots(&self, strategy: PivotStrategy) -> Vec<usize> {
+        let mut remaining = self.data.clone();
+        let mut pivots = vec![];
         loop {
-            match self.get_at(current_cell) {
-                Bit::Off => {
-                    if !current_cell.step_right() {
-                        // We've reached the outside of the grid. We quit here.
-                        break;
-                    } else {
-                        // We are still within the grid, pointing at a new cell. We loop again.
-                        continue;
-                    }
+            let weights = (0..self.variable_cols())
+                .filter(|col| !pivots.contains(col))
+                .map(|col| {
+                    let weight = remaining.iter().filter(|row| packed_bit(row, col)).count();
+                    (col, weight)
+                })
+                .filter(|&(_, weight)| weight > 0);
+            let pivot = match strategy {
+                PivotStrategy::FirstNonZero => weights.map(|(col, _)| col).next(),
+                PivotStrategy::Sparsest => weights
+                    .min_by_key(|&(col, weight)| (weight, col))
+                    .map(|(col, _)| col),
+                PivotStrategy::Heaviest => weights
+                    .min_by_key(|&(col, weight)| (std::cmp::Reverse(weight), col))
+                    .map(|(col, _)| col),
+            };
+            let Some(pivot) = pivot else {
+                break;
+            };
+            let index = remaining
+                .iter()
+                .position(|row| packed_bit(row, pivot))
+                .expect("Pivot columns have an active bit");
+            let pivot_row = remaining.swap_remove(index);
+            for row in &mut remaining {
+                if packed_bit(row, pivot) {
+                    add_packed(row, &pivot_row);
                 }
-                Bit::On => {
-                    //We found a leading column.
-                    self.decimate_column_with_row(current_cell.row, current_cell.col);
-                    // At this point, every row has a 0 at the current column. Now we sort rows again.
-                    self.sort_rows_by_leading_column();
-                    // All the rows with proper leading bits should still be above the current row,
-                    // which means the row we were decimating with should still be the current row.
-                    // Now, we step down.
-                    if !current_cell.step_down() {
-                        // We've reached the outside of the grid. We quit here.
-                        break;
+            }
+            pivots.push(pivot);
+        }
+        pivots
+    }
+
+    /// Reorders the columns of `self`, so that its column `i` becomes what was column `order[i]`.
+    pub fn permute_columns(&mut self, order: &[usize]) {
+        for row in &mut self.data {
+            let bits: Vec<Bit> = order
+                .iter()
+                .map(|&col| {
+                    if packed_bit(row, col) {
+                        Bit::On
                     } else {
-                        // We are still within the grid, pointing at a new cell. We loop again.
-                        // Something to consider is to assert the new position necessarily holds
-                        // an off bit, as a sanity check.
-                        //assert_eq!(self.get_at(current_cell), Bit::Off);
-                        continue;
+                        Bit::Off
                     }
-                }
-            }
+                })
+                .collect();
+            *row = pack_row(&bits);
         }
     }
 
     /// Returns whether or not the entire row at index `row` is `Off`.
     pub fn is_row_zero(&self, row: usize) -> bool {
-        for b in &self.data[row] {
-            if *b == Bit::On {
-                return false;
-            }
-        }
-        return true;
+        self.data[row].iter().all(|&word| word == 0)
     }
 
     /// Counts the number of non-zero rows. This is equal to the rank of the matrix
     /// if the matrix is in reduced row echelon form.
     pub fn non_zero_row_count(&self) -> usize {
-        fn is_zero(row: &[Bit]) -> bool {
-            for bit in row {
-                if *bit == Bit::On {
-                    return false;
+        (0..self.rows)
+            .find(|&row| self.is_row_zero(row))
+            .unwrap_or(self.rows)
+    }
+
+    /// Counts the rows that lead in a variable column, which is the rank of the variable columns if `self` is in row-echelon form.
+    /// Unlike `non_zero_row_count`, a row that reads `0 = 1` in an augmented column is not counted.
+    pub fn rank(&self) -> usize {
+        let variables = self.variable_cols();
+        self.pivot_columns()
+            .into_iter()
+            .filter(|&col| col < variables)
+            .count()
+    }
+
+    /// Collects the indices of the variable columns which do not contain a leading 1, if `self` is in row-echelon form.
+    /// These correspond to the free parameters of the system. Augmented columns are never included.
+    pub fn non_leading_columns(&self) -> Vec<usize> {
+        let pivot_cols = self.pivot_columns();
+        (0..self.variable_cols())
+            .filter(|col| !pivot_cols.contains(col))
+            .collect()
+    }
+
+    /// Lists the leading column of every non-zero row, in order. These are the pivot columns when `self` is in row-echelon form,
+    /// and every other column is free, as listed by `non_leading_columns`.
+    pub fn pivot_columns(&self) -> Vec<usize> {
+        self.data
+            .iter()
+            .map_while(|row| packed_leading_column(row))
+            .collect()
+    }
+
+    /// Lists the rows of `self` that are sums of some of the rows before them. Elimination reduces exactly this many rows to zero,
+    /// so the remaining rows are independent and as many as the rank. Does not require `self` to be eliminated.
+    pub fn redundant_rows(&self) -> Vec<usize> {
+        // Rows seen so far, reduced against each other, along with their leading column.
+        let mut basis: Vec<(usize, Vec<u64>)> = vec![];
+        let mut redundant = vec![];
+        for (index, row) in self.data.iter().enumerate() {
+            let mut row = row.clone();
+            for (pivot, basis_row) in &basis {
+                if packed_bit(&row, *pivot) {
+                    add_packed(&mut row, basis_row);
                 }
             }
-            return true;
-        }
-        for row in 0..self.rows {
-            if is_zero(&self.data[row]) {
-                return row;
+            match packed_leading_column(&row) {
+                Some(pivot) => basis.push((pivot, row)),
+                None => redundant.push(index),
             }
         }
-        return self.rows;
+        redundant
     }
 
-    /// Collects the column indices which do not contain a leading 1.
-    /// These correspond to the free parameters of the system.
-    pub fn non_leading_columns(&self) -> Vec<usize> {
-        let mut current_cell = BoundedPosition {
-            width: self.cols,
-            height: self.rows,
-            row: 0,
-            col: 0,
-        };
+    /// Lists, for every row of the reduced row-echelon form of `self`, the rows of `self` that were added together to make it.
+    /// Rows are listed in the order `eliminate` leaves them, so the zero rows come last, and their provenance shows which rows
+    /// of `self` sum to zero.
+    ///
+    /// Eliminates `self` augmented with the identity matrix, whose right half then records every row operation.
+    pub fn row_provenance(&self) -> Vec<Vec<usize>> {
+        let mut tracked = self.with_identity(self.cols);
+        tracked.eliminate();
+        tracked
+            .data
+            .iter()
+            .map(|row| {
+                (0..self.rows)
+                    .filter(|&col| packed_bit(row, self.cols + col))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Keeps the first `cols` columns of `self`, and appends the identity matrix to their right.
+    pub fn with_identity(&self, cols: usize) -> Matrix {
+        let data = (0..self.rows)
+            .map(|row| {
+                let mut augmented = self.row(row);
+                augmented.truncate(cols);
+                augmented
+                    .extend((0..self.rows).map(|col| if col == row { Bit::On } else { Bit::Off }));
+                augmented
+            })
+            .collect();
+        Matrix::new(data, self.rows, cols + self.rows).expect("Could not form matrix")
+    }
 
-        let mut free_cols = vec![];
+    /// Computes the inverse of `self` over the field of bits. Returns `None` unless `self` is square and has full rank.
+    ///
+    /// Eliminates `self` augmented with the identity matrix. When the left half reduces to the identity, the right half holds the inverse.
+    pub fn inverse(&self) -> Option<Matrix> {
+        if self.rows != self.cols {
+            return None;
+        }
+        let n = self.rows;
+        let mut augmented = self.with_identity(n);
+        augmented.eliminate();
+        // In reduced row-echelon form, full rank means every row leads on the diagonal of the left half.
+        if (0..n).any(|row| augmented.leading_column(row) != Some(row)) {
+            return None;
+        }
+        let data = (0..n).map(|row| augmented.row(row)[n..].to_vec()).collect();
+        Matrix::new(data, n, n)
+    }
 
-        loop {
-            match self.get_at(current_cell) {
-                Bit::Off => {
-                    free_cols.push(current_cell.col);
-                    if !current_cell.step_right() {
-                        break;
-                    } else {
-                        continue;
-                    }
+    /// Multiplies `self` by the column vector `vector`, which must have as many elements as `self` has columns.
+    pub fn mul_vector(&self, vector: &[Bit]) -> Vec<Bit> {
+        let vector = pack_row(vector);
+        self.data
+            .iter()
+            .map(|row| {
+                let ones: u32 = row
+                    .iter()
+                    .zip(&vector)
+                    .map(|(a, b)| (a & b).count_ones())
+                    .sum();
+                if ones % 2 == 1 {
+                    Bit::On
+                } else {
+                    Bit::Off
                 }
-                Bit::On => {
-                    if !current_cell.step_down() {
-                        break;
-                    } else {
-                        if !current_cell.step_right() {
-                            break;
+            })
+            .collect()
+    }
+
+    /// Debug function. Eliminates `self`, and renders the matrix before and after, its rank, and its free columns.
+    pub fn report_elimination(&mut self) -> String {
+        let input = self.to_string();
+        self.eliminate();
+        let non_l_cols: Vec<usize> = self.non_leading_columns();
+        format!(
+            "Input:\n{}\nOutput:\n{}\nRank: {}\nFree columns:\n{}",
+            input,
+            self,
+            self.rank(),
+            self.display_selected_columns(&non_l_cols)
+        )
+    }
+
+    /// Debug function. Displays the matrix, but only the columns indexed in `col_nums`. Other entries are displayed as a `.`.
+    pub fn display_selected_columns<'a>(&'a self, col_nums: &'a [usize]) -> SelectedColumns<'a> {
+        SelectedColumns {
+            matrix: self,
+            col_nums,
+        }
+    }
+}
+
+/// Displays a matrix, showing only a selection of its columns. Built by `Matrix::display_selected_columns`.
+pub struct SelectedColumns<'a> {
+    matrix: &'a Matrix,
+    col_nums: &'a [usize],
+}
+
+impl Display for SelectedColumns<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.matrix.rows {
+            for (col, bit) in self.matrix.row(row).iter().enumerate() {
+                if self.col_nums.contains(&col) {
+                    write!(f, "{}", bit)?;
+                } else {
+                    write!(f, ".")?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    /// The matrix of a plain `size` by `size` Lights Out board: pressing a cell toggles it and its four neighbors.
+    fn lights_out(size: usize) -> Matrix {
+        let n = size * size;
+        let data = (0..n)
+            .map(|cell| {
+                let (row, col) = (cell / size, cell % size);
+                (0..n)
+                    .map(|press| {
+                        let (r, c) = (press / size, press % size);
+                        if row.abs_diff(r) + col.abs_diff(c) <= 1 {
+                            Bit::On
                         } else {
-                            continue;
+                            Bit::Off
                         }
+                    })
+                    .collect()
+            })
+            .collect();
+        Matrix::new(data, n, n).unwrap()
+    }
+
+    /// The vector of length `n` with a single `On` at `index`.
+    fn unit(n: usize, index: usize) -> Vec<Bit> {
+        (0..n)
+            .map(|i| if i == index { Bit::On } else { Bit::Off })
+            .collect()
+    }
+
+    #[test]
+    fn inverse_undoes_the_matrix() {
+        for size in [1, 2, 3, 6] {
+            let matrix = lights_out(size);
+            let inverse = matrix.inverse().unwrap();
+            let n = matrix.rows;
+            for index in 0..n {
+                let column = matrix.mul_vector(&unit(n, index));
+                assert_eq!(inverse.mul_vector(&column), unit(n, index));
+                assert_eq!(
+                    matrix.mul_vector(&inverse.mul_vector(&unit(n, index))),
+                    unit(n, index)
+                );
+            }
+        }
+    }
+
+    /// Adds up the rows of `matrix` at `rows`.
+    fn sum_rows(matrix: &Matrix, rows: &[usize]) -> Vec<Bit> {
+        rows.iter().fold(vec![Bit::Off; matrix.cols], |sum, &row| {
+            sum.iter()
+                .zip(matrix.row(row))
+                .map(|(&a, b)| a + b)
+                .collect()
+        })
+    }
+
+    #[test]
+    fn row_provenance_adds_up_to_the_reduced_rows() {
+        for size in [3, 4, 5] {
+            let matrix = lights_out(size);
+            let mut reduced = matrix.clone();
+            reduced.eliminate();
+            let provenance = matrix.row_provenance();
+            assert_eq!(provenance.len(), matrix.rows);
+            for (row, sources) in provenance.iter().enumerate() {
+                assert_eq!(sum_rows(&matrix, sources), reduced.row(row));
+            }
+        }
+        // The 4x4 board has 4 free variables, so its last 4 rows come from sums of input rows that cancel out.
+        let provenance = lights_out(4).row_provenance();
+        assert!(provenance[12..].iter().all(|sources| !sources.is_empty()));
+    }
+
+    /// A `rows` by `cols` matrix of random bits.
+    fn random_matrix(rows: usize, cols: usize, rng: &mut Rng) -> Vec<Vec<Bit>> {
+        (0..rows)
+            .map(|_| (0..cols).map(|_| rng.next_bit()).collect())
+            .collect()
+    }
+
+    /// Reduces `data` to reduced row-echelon form one bit at a time, as a reference for the packed elimination.
+    fn naive_eliminate(data: &mut [Vec<Bit>]) {
+        let cols = data.first().map_or(0, Vec::len);
+        let mut pivot_row = 0;
+        for col in 0..cols {
+            let Some(found) = (pivot_row..data.len()).find(|&row| data[row][col] == Bit::On) else {
+                continue;
+            };
+            data.swap(pivot_row, found);
+            let pivot = data[pivot_row].clone();
+            for (row, target) in data.iter_mut().enumerate() {
+                if row != pivot_row && target[col] == Bit::On {
+                    for (bit, &source) in target.iter_mut().zip(&pivot) {
+                        *bit += source;
                     }
                 }
             }
+            pivot_row += 1;
         }
+    }
 
-        return free_cols;
+    /// Multiplies `data` by `vector` one bit at a time.
+    fn naive_mul_vector(data: &[Vec<Bit>], vector: &[Bit]) -> Vec<Bit> {
+        data.iter()
+            .map(|row| {
+                row.iter().zip(vector).fold(
+                    Bit::Off,
+                    |sum, (&a, &b)| if a == Bit::On { sum + b } else { sum },
+                )
+            })
+            .collect()
     }
 
-    /// Debug function. Reports effects of calling `eliminate`.
-    pub fn report_elimination(&mut self) {
-        println!("Input:\n{}", self);
-        self.eliminate();
-        println!("Output:\n{}\nRank: {}", self, self.non_zero_row_count());
-        let non_l_cols: Vec<usize> = self.non_leading_columns().into_iter().collect();
-        println!("Free columns:");
-        self.display_selected_columns(&non_l_cols);
+    #[test]
+    fn wide_matrices_match_the_bitwise_reference() {
+        let mut rng = Rng::new(64);
+        for (rows, cols) in [(70, 130), (65, 65), (3, 200), (130, 70)] {
+            let data = random_matrix(rows, cols, &mut rng);
+            let matrix = Matrix::new(data.clone(), rows, cols).unwrap();
+            let vector: Vec<Bit> = (0..cols).map(|_| rng.next_bit()).collect();
+            assert_eq!(matrix.mul_vector(&vector), naive_mul_vector(&data, &vector));
+
+            let mut reduced = matrix.clone();
+            reduced.eliminate();
+            let mut expected = data;
+            naive_eliminate(&mut expected);
+            for (row, bits) in expected.iter().enumerate() {
+                assert_eq!(&reduced.row(row), bits);
+            }
+            let rank = expected
+                .iter()
+                .filter(|bits| bits.contains(&Bit::On))
+                .count();
+            assert_eq!(reduced.non_zero_row_count(), rank);
+        }
     }
 
-    /// Debug function. Prints matrix, but only the columns indexed in `col_nums`. Other entries are displayed as a `.`.
-    pub fn display_selected_columns(&self, col_nums: &[usize]) {
-        for row in 0..self.rows {
-            for col in 0..self.cols {
-                if col_nums.contains(&col) {
-                    print!("{}", self.data[row][col]);
-                } else {
-                    print!(".");
+    #[test]
+    fn wide_inverses_undo_the_matrix() {
+        let mut rng = Rng::new(65);
+        let mut inverted = 0;
+        for n in [65, 70, 100, 130] {
+            for _ in 0..4 {
+                let data = random_matrix(n, n, &mut rng);
+                let matrix = Matrix::new(data.clone(), n, n).unwrap();
+                let mut reduced = data.clone();
+                naive_eliminate(&mut reduced);
+                let full_rank = reduced.iter().all(|bits| bits.contains(&Bit::On));
+                let Some(inverse) = matrix.inverse() else {
+                    assert!(!full_rank);
+                    continue;
+                };
+                assert!(full_rank);
+                inverted += 1;
+                let inverse_rows: Vec<Vec<Bit>> = (0..n).map(|row| inverse.row(row)).collect();
+                for index in 0..n {
+                    let column = naive_mul_vector(&data, &unit(n, index));
+                    assert_eq!(naive_mul_vector(&inverse_rows, &column), unit(n, index));
                 }
             }
-            println!();
         }
+        // A random square matrix over bits is invertible about 29% of the time, so some of the 16 must be.
+        assert!(inverted > 0);
+    }
+
+    #[test]
+    fn singular_matrices_have_no_inverse() {
+        // The 4x4 and 5x5 boards have 4 and 2 free variables.
+        assert!(lights_out(4).inverse().is_none());
+        assert!(lights_out(5).inverse().is_none());
+        let wide = Matrix::new(vec![vec![Bit::On, Bit::Off]], 1, 2).unwrap();
+        assert!(wide.inverse().is_none());
     }
 }
@@ -0,0 +1,217 @@
+use crate::{bit::Bit, matrix::Matrix, memory};
+
+/// The header line of a Matrix Market file.
+const MARKET_HEADER: &str = "%%MatrixMarket matrix coordinate integer general";
+
+/// The formats matrices can be exported to and imported from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MatrixFormat {
+    /// One row per line as a string of `0`s and `1`s, with the augmented columns after a `|`. Rows without a `|` have a single
+    /// constant column last. Lines starting with `#` are comments.
+    Text,
+    /// The coordinate format of Matrix Market, listing the 1-based row and column of every `1` entry.
+    Market,
+}
+
+impl MatrixFormat {
+    /// Finds the format for a name, `text` or `market`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(MatrixFormat::Text),
+            "market" => Some(MatrixFormat::Market),
+            _ => None,
+        }
+    }
+}
+
+/// Renders the augmented `matrix` in `format`.
+pub fn export(matrix: &Matrix, format: MatrixFormat) -> String {
+    match format {
+        MatrixFormat::Text => export_text(matrix),
+        MatrixFormat::Market => export_market(matrix),
+    }
+}
+
+/// Parses an augmented matrix, detecting Matrix Market files by their header and reading anything else as text.
+pub fn import(text: &str) -> Result<Matrix, String> {
+    if text.trim_start().starts_with("%%MatrixMarket") {
+        import_market(text)
+    } else {
+        import_text(text)
+    }
+}
+
+fn export_text(matrix: &Matrix) -> String {
+    let mut res = String::new();
+    for row in 0..matrix.rows {
+        let row = matrix.row(row);
+        let (vars, augmented) = row.split_at(matrix.variable_cols());
+        for bit in vars {
+            res.push_str(&bit.to_string());
+        }
+        if !augmented.is_empty() {
+            res.push_str(" | ");
+            for bit in augmented {
+                res.push_str(&bit.to_string());
+            }
+        }
+        res.push('\n');
+    }
+    res
+}
+
+fn export_market(matrix: &Matrix) -> String {
+    let entries: Vec<(usize, usize)> = (0..matrix.rows)
+        .flat_map(|row| (0..matrix.cols).map(move |col| (row, col)))
+        .filter(|&(row, col)| matrix.get(row, col) == Bit::On)
+        .collect();
+    let mut res = format!(
+        "{}\n% Augmented system over GF(2). The last column is the constant column.\n{} {} {}\n",
+        MARKET_HEADER,
+        matrix.rows,
+        matrix.cols,
+        entries.len()
+    );
+    for (row, col) in entries {
+        res.push_str(&format!("{} {} 1\n", row + 1, col + 1));
+    }
+    res
+}
+
+fn import_text(text: &str) -> Result<Matrix, String> {
+    let mut data = vec![];
+    let mut augmented_cols = None;
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let row = line
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '|')
+            .map(|c| match c {
+                '0' => Ok(Bit::Off),
+                '1' => Ok(Bit::On),
+                _ => Err(format!("Invalid character '{}' on line {}.", c, number + 1)),
+            })
+            .collect::<Result<Vec<Bit>, String>>()?;
+        let augmented = line.split_once('|').map_or(1, |(_, augmented)| {
+            augmented.chars().filter(|c| !c.is_whitespace()).count()
+        });
+        if *augmented_cols.get_or_insert(augmented) != augmented {
+            return Err(format!(
+                "Line {} has a different number of augmented columns.",
+                number + 1
+            ));
+        }
+        data.push(row);
+    }
+    let cols = data.first().map_or(0, Vec::len);
+    if cols < 2 {
+        return Err(
+            "A matrix needs at least one variable column and the constant column.".to_string(),
+        );
+    }
+    let rows = data.len();
+    let mut matrix = Matrix::new(data, rows, cols)
+        .ok_or_else(|| "Every row must have the same length.".to_string())?;
+    matrix.augmented_cols = augmented_cols.unwrap_or(1);
+    if matrix.augmented_cols == 0 || matrix.augmented_cols >= cols {
+        return Err(
+            "A matrix needs at least one variable column and the constant column.".to_string(),
+        );
+    }
+    Ok(matrix)
+}
+
+fn import_market(text: &str) -> Result<Matrix, String> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('%'));
+    let numbers = |line: &str| -> Result<Vec<usize>, String> {
+        line.split_whitespace()
+            .map(|n| n.parse().map_err(|_| format!("Invalid number \"{}\".", n)))
+            .collect()
+    };
+    let size = numbers(lines.next().ok_or("Missing size line.")?)?;
+    let &[rows, cols, _] = size.as_slice() else {
+        return Err("The size line must hold the rows, columns and number of entries.".to_string());
+    };
+    if rows == 0 || cols < 2 {
+        return Err(
+            "A matrix needs at least one variable column and the constant column.".to_string(),
+        );
+    }
+    let budget = memory::budget(None)?;
+    memory::check("The matrix", memory::matrix_bytes(rows, cols), budget)?;
+    let mut data = vec![vec![Bit::Off; cols]; rows];
+    for line in lines {
+        let entry = numbers(line)?;
+        let (row, col, value) = match *entry.as_slice() {
+            [row, col] => (row, col, 1),
+            [row, col, value] => (row, col, value),
+            _ => return Err(format!("Invalid entry \"{}\".", line)),
+        };
+        if row == 0 || row > rows || col == 0 || col > cols {
+            return Err(format!("Entry \"{}\" is out of bounds.", line));
+        }
+        if value % 2 == 1 {
+            data[row - 1][col - 1] += Bit::On;
+        }
+    }
+    let mut matrix =
+        Matrix::new(data, rows, cols).ok_or_else(|| "Invalid matrix size.".to_string())?;
+    matrix.augmented_cols = 1;
+    Ok(matrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::parse_board_file;
+
+    /// The augmented matrix of a 2x3 board with a hole.
+    fn board_matrix() -> Matrix {
+        parse_board_file("101\n1 0").unwrap().to_matrix().0
+    }
+
+    #[test]
+    fn text_export_round_trips() {
+        let matrix = board_matrix();
+        let text = export(&matrix, MatrixFormat::Text);
+        assert_eq!(
+            text,
+            "11010 | 0\n11100 | 1\n01101 | 0\n10010 | 0\n00101 | 1\n"
+        );
+        assert_eq!(export(&import(&text).unwrap(), MatrixFormat::Text), text);
+    }
+
+    #[test]
+    fn market_export_round_trips() {
+        let matrix = board_matrix();
+        let text = export(&matrix, MatrixFormat::Market);
+        assert!(text.starts_with(MARKET_HEADER));
+        assert!(text.contains("\n5 6 15\n1 1 1\n"));
+        assert_eq!(
+            export(&import(&text).unwrap(), MatrixFormat::Text),
+            export(&matrix, MatrixFormat::Text)
+        );
+    }
+
+    #[test]
+    fn market_entries_add_up() {
+        let text = format!("{}\n2 3 4\n1 1 1\n1 1\n2 2 3\n2 3 2\n", MARKET_HEADER);
+        let matrix = import(&text).unwrap();
+        assert_eq!(export(&matrix, MatrixFormat::Text), "00 | 0\n01 | 0\n");
+    }
+
+    #[test]
+    fn malformed_matrices_are_rejected() {
+        assert!(import("101 | 1\n11 | 0\n").is_err());
+        assert!(import("# no rows\n").is_err());
+        assert!(import("10x | 1\n").is_err());
+        let out_of_bounds = format!("{}\n2 2 1\n3 1 1\n", MARKET_HEADER);
+        assert!(import(&out_of_bounds).is_err());
+    }
+}
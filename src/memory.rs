@@ -0,0 +1,88 @@
+use std::mem::size_of;
+
+use crate::{
+    bit::Bit,
+    board::{BoardAssignment, BoardDescription},
+};
+
+/// The memory budget used when none is configured: 1 GiB.
+pub const DEFAULT_BUDGET: usize = 1 << 30;
+
+/// The environment variable holding the memory budget, as a size like `512M`.
+pub const BUDGET_VARIABLE: &str = "WAYOUT_SOLVER_MEMORY_BUDGET";
+
+/// Parses a size in bytes, optionally followed by a `K`, `M` or `G` suffix for binary multiples.
+pub fn parse_size(text: &str) -> Option<usize> {
+    let text = text.trim();
+    let (digits, shift) = match text.char_indices().last()? {
+        (i, 'K' | 'k') => (&text[..i], 10),
+        (i, 'M' | 'm') => (&text[..i], 20),
+        (i, 'G' | 'g') => (&text[..i], 30),
+        _ => (text, 0),
+    };
+    digits.parse::<usize>().ok()?.checked_mul(1 << shift)
+}
+
+/// Finds the memory budget: `configured` if given, then the value of `BUDGET_VARIABLE`, then `DEFAULT_BUDGET`.
+pub fn budget(configured: Option<usize>) -> Result<usize, String> {
+    if let Some(budget) = configured {
+        return Ok(budget);
+    }
+    match std::env::var(BUDGET_VARIABLE) {
+        Ok(value) => parse_size(&value).ok_or_else(|| {
+            format!(
+                "Invalid memory budget \"{}\" in {}.",
+                value, BUDGET_VARIABLE
+            )
+        }),
+        Err(_) => Ok(DEFAULT_BUDGET),
+    }
+}
+
+/// Estimates the bytes taken by a dense matrix with `rows` rows and `cols` columns.
+pub fn matrix_bytes(rows: usize, cols: usize) -> Option<usize> {
+    let row = cols.checked_mul(size_of::<Bit>())? + size_of::<Vec<Bit>>();
+    rows.checked_mul(row)
+}
+
+/// Estimates the bytes taken by the augmented matrix of `board`, along with the copy that gets eliminated.
+pub fn board_matrix_bytes(board: &BoardDescription) -> Option<usize> {
+    let cells = board.cell_count();
+    matrix_bytes(cells, cells + 1)?.checked_mul(2)
+}
+
+/// Estimates the bytes taken by keeping `solutions` solutions of `board` around at once.
+pub fn solutions_bytes(board: &BoardDescription, solutions: u128) -> Option<usize> {
+    let grid = (board.width() * board.height()).checked_mul(size_of::<Option<Bit>>())?;
+    let solution = grid + size_of::<BoardAssignment>();
+    usize::try_from(solutions).ok()?.checked_mul(solution)
+}
+
+/// Renders a size in bytes in the largest binary unit it has at least one of, to one decimal.
+pub fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..0x10_0000 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        0x10_0000..0x4000_0000 => format!("{:.1} MiB", bytes as f64 / 1024.0 / 1024.0),
+        _ => format!("{:.1} GiB", bytes as f64 / 1024.0 / 1024.0 / 1024.0),
+    }
+}
+
+/// Checks that `bytes` fit in `budget`, where `None` stands for a size too large to even compute. Fails with a message naming
+/// `what` would have been built otherwise.
+pub fn check(what: &str, bytes: Option<usize>, budget: usize) -> Result<(), String> {
+    match bytes {
+        Some(bytes) if bytes <= budget => Ok(()),
+        Some(bytes) => Err(format!(
+            "{} would take about {}, over the memory budget of {}. Set {} or --memory-budget to raise it.",
+            what,
+            format_size(bytes),
+            format_size(budget),
+            BUDGET_VARIABLE
+        )),
+        None => Err(format!(
+            "{} would take more memory than can be addressed.",
+            what
+        )),
+    }
+}
@@ -0,0 +1,76 @@
+use wayout_solver::{
+    bit::Bit,
+    board::{self, BoardAssignment, BoardDescription},
+    coords,
+    grid::Position,
+    i18n::tr,
+    sharecode, solver,
+};
+
+/// The longest answer `answer` produces, which fits in a single chat message on common platforms.
+pub const MAX_ANSWER_LENGTH: usize = 2000;
+
+/// Reads a board given as a single argument: either a share code, or a board file with rows separated by newlines or by the two
+/// characters `\n`.
+pub fn parse_board_argument(text: &str) -> Result<BoardDescription, String> {
+    let text = text.trim();
+    if text.contains(':') {
+        return sharecode::decode(text);
+    }
+    board::parse_board_file(&text.replace("\\n", "\n"))
+}
+
+/// Solves `board` with as few presses as possible, and renders the answer compactly as a single message of at most `max_len` bytes.
+///
+/// The answer lists the presses as `row,column` pairs numbered by `coords`, followed by a grid of the presses, with `x` for a press and
+/// `.` for any other cell. The grid is dropped if the answer would not fit, and then the list of presses is cut short.
+pub fn answer(board: &BoardDescription, max_len: usize) -> String {
+    let Some(solution) = solver::minimum_solution(board) else {
+        return tr!("No solution.");
+    };
+    let presses = solution.presses();
+    if presses.is_empty() {
+        return tr!("Already solved.");
+    }
+    let coordinates: Vec<String> = presses
+        .iter()
+        .map(|pos| {
+            format!(
+                "{},{}",
+                coords::row_number(pos.row, solution.height()),
+                coords::col_number(pos.col)
+            )
+        })
+        .collect();
+    let summary = tr!("{} presses: {}", presses.len(), coordinates.join(" "));
+    let with_grid = format!("{}\n{}", summary, press_grid(&solution));
+    if with_grid.len() <= max_len {
+        return with_grid;
+    }
+    if summary.len() <= max_len {
+        return summary;
+    }
+    let mut cut = max_len.saturating_sub('…'.len_utf8());
+    while !summary.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!("{}…", &summary[..cut])
+}
+
+/// Renders the presses of `solution` as a grid of `x`s, `.`s for cells left alone, and spaces for holes.
+fn press_grid(solution: &BoardAssignment) -> String {
+    let rows: Vec<String> = (0..solution.height())
+        .map(|row| {
+            (0..solution.width())
+                .map(|col| match solution[Position::new(row, col)] {
+                    Some(Bit::On) => 'x',
+                    Some(Bit::Off) => '.',
+                    None => ' ',
+                })
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+    rows.join("\n")
+}
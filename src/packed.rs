@@ -0,0 +1,291 @@
+use crate::{
+    bit::Bit,
+    board::{BoardAssignment, BoardDescription, Cell, RigidSemantics},
+    grid::Position,
+};
+
+/// The version of the packed board format, stored in the first byte of every packed board.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// The flag of the packed board header marking a target grid after the modifier table.
+const HAS_TARGET: u8 = 0b100;
+
+/// Packs `board` into a compact binary form, which unlike board files keeps every detail of it.
+///
+/// The board starts with `FORMAT_VERSION`, the width and height as variable-length integers, and a flags byte holding the index
+/// of its rigid semantics in `RigidSemantics::ALL` and whether a target grid follows. Next come two bits per cell in reading
+/// order: `00` for a hole, `10` for an unlit cell and `11` for a lit one. A modifier table lists the cells whose connections
+/// differ from a plain cell, as the distance from the previous listed cell and a byte of flags. Boards whose target is not
+/// every cell lit end with one target bit per cell that is not a hole.
+pub fn pack_board(board: &BoardDescription) -> Vec<u8> {
+    let positions: Vec<Position> =
+        Position::iter_row_major(board.width(), board.height()).collect();
+    let mut bytes = vec![FORMAT_VERSION];
+    write_varint(&mut bytes, board.width());
+    write_varint(&mut bytes, board.height());
+    let rigid = RigidSemantics::ALL
+        .iter()
+        .position(|&rigid| rigid == board.rigid_semantics())
+        .unwrap_or(0) as u8;
+    let has_target = !board.has_default_target();
+    bytes.push(rigid | if has_target { HAS_TARGET } else { 0 });
+
+    let cell_bits = positions.iter().flat_map(|&pos| match board[pos] {
+        None => [false, false],
+        Some(cell) => [true, cell.starting_value == Bit::On],
+    });
+    bytes.extend(pack_bits(cell_bits));
+
+    let modified: Vec<(usize, u8)> = positions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &pos)| {
+            let flags = modifier_flags(board[pos]?);
+            (flags != modifier_flags(Cell::new_basic(Bit::Off))).then_some((index, flags))
+        })
+        .collect();
+    write_varint(&mut bytes, modified.len());
+    let mut previous = 0;
+    for (index, flags) in modified {
+        write_varint(&mut bytes, index - previous);
+        bytes.push(flags);
+        previous = index;
+    }
+
+    if has_target {
+        let target_bits = positions
+            .iter()
+            .filter_map(|&pos| board[pos])
+            .map(|cell| cell.target_value == Bit::On);
+        bytes.extend(pack_bits(target_bits));
+    }
+    bytes
+}
+
+/// Unpacks a board packed by `pack_board`.
+pub fn unpack_board(bytes: &[u8]) -> Result<BoardDescription, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.byte()? != FORMAT_VERSION {
+        return Err("Unsupported packed board version.".to_string());
+    }
+    let (width, height) = (reader.varint()?, reader.varint()?);
+    let flags = reader.byte()?;
+    let rigid = *RigidSemantics::ALL
+        .get((flags & 0b11) as usize)
+        .ok_or("Invalid packed board.")?;
+    let cells = width.checked_mul(height).ok_or("Invalid packed board.")?;
+
+    let cell_bits = reader.bits(cells.checked_mul(2).ok_or("Invalid packed board.")?)?;
+    let mut slots: Vec<Option<Cell>> = cell_bits
+        .chunks(2)
+        .map(|pair| match pair {
+            [false, false] => Ok(None),
+            [true, lit] => Ok(Some(Cell::new_basic(if *lit { Bit::On } else { Bit::Off }))),
+            _ => Err("Invalid packed board."),
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut index = 0;
+    for _ in 0..reader.varint()? {
+        index += reader.varint()?;
+        let flags = reader.byte()?;
+        let Some(Some(cell)) = slots.get_mut(index) else {
+            return Err("Invalid packed board.".to_string());
+        };
+        cell.affects_up = flags & 0b1 != 0;
+        cell.affects_down = flags & 0b10 != 0;
+        cell.affects_left = flags & 0b100 != 0;
+        cell.affects_right = flags & 0b1000 != 0;
+        cell.is_rigid = flags & 0b10000 != 0;
+    }
+
+    if flags & HAS_TARGET != 0 {
+        let present = slots.iter().filter(|slot| slot.is_some()).count();
+        let target_bits = reader.bits(present)?;
+        for (cell, lit) in slots.iter_mut().flatten().zip(target_bits) {
+            cell.target_value = if lit { Bit::On } else { Bit::Off };
+        }
+    }
+    if reader.pos != bytes.len() {
+        return Err("Invalid packed board.".to_string());
+    }
+
+    let rows = slots.chunks(width.max(1)).map(<[_]>::to_vec).collect();
+    let mut board = BoardDescription::from_rows(rows).ok_or("Invalid packed board.")?;
+    if board.width() != width || board.height() != height {
+        return Err("Invalid packed board.".to_string());
+    }
+    board.set_rigid_semantics(rigid);
+    Ok(board)
+}
+
+/// Packs `assignment` into its width and height as variable-length integers, followed by two bits per cell in reading order:
+/// `00` for a hole, `10` for a cell left alone and `11` for a pressed one.
+pub fn pack_assignment(assignment: &BoardAssignment) -> Vec<u8> {
+    let mut bytes = vec![];
+    write_varint(&mut bytes, assignment.width());
+    write_varint(&mut bytes, assignment.height());
+    let bits = Position::iter_row_major(assignment.width(), assignment.height()).flat_map(|pos| {
+        match assignment[pos] {
+            None => [false, false],
+            Some(bit) => [true, bit == Bit::On],
+        }
+    });
+    bytes.extend(pack_bits(bits));
+    bytes
+}
+
+/// Unpacks an assignment packed by `pack_assignment`.
+pub fn unpack_assignment(bytes: &[u8]) -> Result<BoardAssignment, String> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let (width, height) = (reader.varint()?, reader.varint()?);
+    let cells = width
+        .checked_mul(height)
+        .ok_or("Invalid packed assignment.")?;
+    let slots: Vec<Option<Bit>> = reader
+        .bits(cells.checked_mul(2).ok_or("Invalid packed assignment.")?)?
+        .chunks(2)
+        .map(|pair| match pair {
+            [false, false] => Ok(None),
+            [true, pressed] => Ok(Some(if *pressed { Bit::On } else { Bit::Off })),
+            _ => Err("Invalid packed assignment."),
+        })
+        .collect::<Result<_, _>>()?;
+    if reader.pos != bytes.len() {
+        return Err("Invalid packed assignment.".to_string());
+    }
+    let rows = slots.chunks(width.max(1)).map(<[_]>::to_vec).collect();
+    BoardAssignment::from_rows(rows)
+        .filter(|assignment| assignment.width() == width && assignment.height() == height)
+        .ok_or_else(|| "Invalid packed assignment.".to_string())
+}
+
+/// The connections of `cell` as a byte of flags: up, down, left and right from the lowest bit, then rigid.
+fn modifier_flags(cell: Cell) -> u8 {
+    [
+        cell.affects_up,
+        cell.affects_down,
+        cell.affects_left,
+        cell.affects_right,
+        cell.is_rigid,
+    ]
+    .iter()
+    .enumerate()
+    .fold(0, |flags, (i, &set)| flags | (set as u8) << i)
+}
+
+/// Packs `bits` eight to a byte, the first bit in the highest position, padding the last byte with zeros.
+fn pack_bits(bits: impl Iterator<Item = bool>) -> Vec<u8> {
+    let bits: Vec<bool> = bits.collect();
+    bits.chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (i, &bit)| byte | (bit as u8) << (7 - i))
+        })
+        .collect()
+}
+
+/// Writes `value` in seven-bit groups, lowest first, with the high bit of every byte but the last set.
+fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads packed data front to back.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or("Packed data is cut short.")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<usize, String> {
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid variable-length integer in packed data.".to_string())
+    }
+
+    /// Reads `count` bits packed by `pack_bits`.
+    fn bits(&mut self, count: usize) -> Result<Vec<bool>, String> {
+        let len = count.div_ceil(8);
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or("Packed data is cut short.")?;
+        self.pos += len;
+        Ok((0..count)
+            .map(|i| bytes[i / 8] >> (7 - i % 8) & 1 == 1)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generator, rng::Rng};
+
+    #[test]
+    fn boards_survive_packing() {
+        let mut rng = Rng::new(1020);
+        for _ in 0..200 {
+            let width = 1 + rng.below(12) as usize;
+            let height = 1 + rng.below(12) as usize;
+            let board = generator::random_variant(width, height, &mut rng);
+            let bytes = pack_board(&board);
+            let unpacked = unpack_board(&bytes).unwrap();
+            assert_eq!(unpacked.to_board_text(), board.to_board_text());
+            assert_eq!(pack_board(&unpacked), bytes);
+        }
+    }
+
+    #[test]
+    fn assignments_survive_packing() {
+        let mut rng = Rng::new(1020);
+        for _ in 0..200 {
+            let width = 1 + rng.below(12) as usize;
+            let height = 1 + rng.below(12) as usize;
+            let rows = (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| (rng.below(4) != 0).then(|| rng.next_bit()))
+                        .collect()
+                })
+                .collect();
+            let assignment = BoardAssignment::from_rows(rows).unwrap();
+            let bytes = pack_assignment(&assignment);
+            let unpacked = unpack_assignment(&bytes).unwrap();
+            assert_eq!(unpacked.to_string(), assignment.to_string());
+            assert_eq!(pack_assignment(&unpacked), bytes);
+        }
+    }
+
+    #[test]
+    fn truncated_boards_are_rejected() {
+        let mut rng = Rng::new(1020);
+        let board = generator::random_variant(5, 4, &mut rng);
+        let bytes = pack_board(&board);
+        for len in 0..bytes.len() {
+            assert!(unpack_board(&bytes[..len]).is_err());
+        }
+    }
+}
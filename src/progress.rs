@@ -0,0 +1,140 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::config;
+
+const PROGRESS_FILE: &str = "levels.txt";
+
+/// The best results on a completed level. The fewest presses and the shortest time need not come from the same play-through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub presses: usize,
+    pub time: Duration,
+}
+
+/// The levels the user has completed, keyed by the absolute path of their level file.
+///
+/// Stored in the `progress` subdirectory of the configuration directory, one level per line: the fewest presses, the shortest
+/// time in whole seconds, and the path, separated by tabs.
+#[derive(Clone, Debug, Default)]
+pub struct Progress {
+    records: BTreeMap<String, Record>,
+}
+
+impl Progress {
+    /// Loads the saved progress. A missing progress file is no progress at all.
+    pub fn load() -> Result<Self, String> {
+        let path = progress_path()?;
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Progress::default()),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+        };
+        let mut records = BTreeMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let mut fields = line.splitn(3, '\t');
+            let record = match (fields.next(), fields.next(), fields.next()) {
+                (Some(presses), Some(seconds), Some(level)) => presses
+                    .parse()
+                    .ok()
+                    .zip(seconds.parse().ok())
+                    .map(|(presses, seconds)| {
+                        (
+                            level.to_string(),
+                            Record {
+                                presses,
+                                time: Duration::from_secs(seconds),
+                            },
+                        )
+                    }),
+                _ => None,
+            };
+            let Some((level, record)) = record else {
+                return Err(format!(
+                    "Invalid line {} in {}.",
+                    number + 1,
+                    path.display()
+                ));
+            };
+            records.insert(level, record);
+        }
+        Ok(Progress { records })
+    }
+
+    /// Writes the progress to the progress file, replacing what was saved.
+    pub fn save(&self) -> Result<(), String> {
+        let path = progress_path()?;
+        let text: String = self
+            .records
+            .iter()
+            .map(|(level, record)| {
+                format!("{}\t{}\t{}\n", record.presses, record.time.as_secs(), level)
+            })
+            .collect();
+        std::fs::write(&path, text)
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+
+    /// The best results on the level at `path`, or `None` if it was never completed.
+    pub fn get(&self, path: &Path) -> Option<Record> {
+        self.records.get(&level_key(path)).copied()
+    }
+
+    /// Records a completion of the level at `path` in `presses` presses and `time`, keeping the best of each. Returns whether
+    /// either of them improved.
+    pub fn complete(&mut self, path: &Path, presses: usize, time: Duration) -> bool {
+        let time = Duration::from_secs(time.as_secs());
+        match self.records.get_mut(&level_key(path)) {
+            Some(record) => {
+                let improved = presses < record.presses || time < record.time;
+                record.presses = record.presses.min(presses);
+                record.time = record.time.min(time);
+                improved
+            }
+            None => {
+                self.records
+                    .insert(level_key(path), Record { presses, time });
+                true
+            }
+        }
+    }
+
+    /// Lists every completed level, by the path it was recorded under, along with its best results.
+    pub fn records(&self) -> impl Iterator<Item = (&str, Record)> {
+        self.records
+            .iter()
+            .map(|(level, record)| (level.as_str(), *record))
+    }
+}
+
+/// Lists the level files of the level pack in the directory `dir`, in alphabetical order. Hidden files are skipped.
+pub fn pack_levels(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Could not read directory {}: {}", dir.display(), e))?;
+    let mut levels: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    levels.sort();
+    Ok(levels)
+}
+
+fn progress_path() -> Result<PathBuf, String> {
+    Ok(config::config_subdir("progress")?.join(PROGRESS_FILE))
+}
+
+/// The key of the level at `path`: its absolute path, so the same level is found from any working directory.
+fn level_key(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
@@ -0,0 +1,137 @@
+use std::fmt::Display;
+
+use crate::{
+    equations::Equations,
+    matrix::{Matrix, PivotStrategy},
+    trace,
+};
+
+/// Aggregates every intermediate step of solving a system: the input matrix, its reduced row-echelon form, and the resulting equations.
+///
+/// Lets callers render or export the whole process without it being printed along the way.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub input: Matrix,
+    pub reduced: Matrix,
+    pub rank: usize,
+    pub pivot_columns: Vec<usize>,
+    pub free_columns: Vec<usize>,
+    /// Rows of the input that are sums of earlier rows, and so were reduced to zero.
+    pub redundant_rows: Vec<usize>,
+    pub equations: Equations,
+}
+
+impl Report {
+    /// Eliminates a copy of `matrix`, and collects every intermediate result along the way.
+    pub fn new(matrix: &Matrix) -> Self {
+        Report::with_strategy(matrix, PivotStrategy::default())
+    }
+
+    /// Eliminates a copy of `matrix` picking pivots by `strategy`, and collects every intermediate result along the way.
+    ///
+    /// The reduced matrix keeps the columns of `matrix` in their original order, so with a strategy other than the default its rows
+    /// need not be in row-echelon order. Every pivot column still holds a single active bit.
+    pub fn with_strategy(matrix: &Matrix, strategy: PivotStrategy) -> Self {
+        let input = matrix.clone();
+        let mut reduced = matrix.clone();
+        let order = {
+            let _span = trace::span("eliminate");
+            reduced.eliminate_with(strategy)
+        };
+        // A row reading `0 = 1` leads in the constant column, and adds to neither the rank nor the pivots.
+        let rank = reduced.rank();
+        let variables = reduced.variable_cols();
+        let pivot_columns = reduced
+            .pivot_columns()
+            .into_iter()
+            .filter(|&col| col < variables)
+            .map(|col| order[col])
+            .collect();
+        let mut free_columns: Vec<usize> = reduced
+            .non_leading_columns()
+            .into_iter()
+            .map(|col| order[col])
+            .collect();
+        free_columns.sort_unstable();
+        let redundant_rows = matrix.redundant_rows();
+        let equations = Equations::with_column_order(reduced.clone(), &order);
+
+        let mut original_order = vec![0; order.len()];
+        for (index, &col) in order.iter().enumerate() {
+            original_order[col] = index;
+        }
+        reduced.permute_columns(&original_order);
+        Report {
+            input,
+            reduced,
+            rank,
+            pivot_columns,
+            free_columns,
+            redundant_rows,
+            equations,
+        }
+    }
+
+    /// Finds rows of the input that contradict each other: their variable columns sum to zero, but their constant terms sum to
+    /// one. Returns `None` if the system is consistent.
+    pub fn contradiction(&self) -> Option<Vec<usize>> {
+        let variables = self.reduced.variable_cols();
+        let row = (0..self.reduced.rows).position(|row| {
+            self.reduced
+                .leading_column(row)
+                .is_some_and(|col| col >= variables)
+        })?;
+        self.input.row_provenance().into_iter().nth(row)
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Input:\n{}", self.input)?;
+        writeln!(f, "Reduced:\n{}", self.reduced)?;
+        writeln!(f, "Rank: {}", self.rank)?;
+        writeln!(f, "Pivot columns: {:?}", self.pivot_columns)?;
+        writeln!(f, "Redundant rows: {:?}", self.redundant_rows)?;
+        writeln!(
+            f,
+            "Free columns:\n{}",
+            self.reduced.display_selected_columns(&self.free_columns)
+        )?;
+        write!(f, "{}", self.equations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bit::Bit, board::parse_board_file};
+
+    /// The report for the board in `text`.
+    fn report(text: &str) -> Report {
+        Report::new(&parse_board_file(text).unwrap().to_matrix().0)
+    }
+
+    #[test]
+    fn contradiction_rows_sum_to_zero_equals_one() {
+        // A plain 4x4 board with a single light on in a corner cannot be lit up completely.
+        let report = report("1000\n0000\n0000\n0000");
+        let rows = report.contradiction().unwrap();
+        let sum = rows
+            .iter()
+            .fold(vec![Bit::Off; report.input.cols], |sum, &row| {
+                sum.iter()
+                    .zip(report.input.row(row))
+                    .map(|(&a, b)| a + b)
+                    .collect()
+            });
+        let (variables, constant) = sum.split_at(report.input.variable_cols());
+        assert!(variables.iter().all(|&bit| bit == Bit::Off));
+        assert_eq!(constant, [Bit::On]);
+    }
+
+    #[test]
+    fn solvable_boards_have_no_contradiction() {
+        assert_eq!(report("000\n000\n000").contradiction(), None);
+        assert_eq!(report("0000\n0000\n0000\n0000").contradiction(), None);
+    }
+}
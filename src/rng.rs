@@ -0,0 +1,56 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::bit::Bit;
+
+/// A small SplitMix64 pseudo-random number generator.
+///
+/// We use our own generator rather than an external crate so that a given seed produces the same sequence on every platform and version.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Creates a generator seeded from the current time.
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Rng::new(nanos)
+    }
+
+    /// Produces the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Produces a uniformly random bit.
+    pub fn next_bit(&mut self) -> Bit {
+        if self.next_u64() >> 63 == 1 {
+            Bit::On
+        } else {
+            Bit::Off
+        }
+    }
+
+    /// Produces a uniformly random number in `0..bound`. `bound` must be non-zero.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        // Rejection sampling, to avoid the bias of a plain modulo.
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
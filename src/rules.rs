@@ -0,0 +1,360 @@
+//! Press rules read from a rule file at runtime, for game variants the built in modifiers cannot express.
+//!
+//! A rule file holds one `key: value` setting per line, and lines starting with `#` are comments:
+//!
+//! ```text
+//! # Presses reach the diagonal neighbors, across the edges of the board.
+//! stencil: -1,-1 -1,1 1,-1 1,1
+//! self: yes
+//! wrap: yes
+//! glyph X: -1,0 1,0 0,-1 0,1 -1,-1 -1,1 1,-1 1,1
+//! ```
+//!
+//! A stencil lists the offsets a press toggles, as `rows,columns` down and to the right of the pressed cell. `stencil` applies to
+//! cells without a glyph, and every `glyph <char>` line gives the stencil of the cells marked with that glyph. `self` decides
+//! whether a press toggles the pressed cell too, and `wrap` whether offsets past an edge continue from the opposite edge.
+//! Boards for a rule file are written like board files, with the glyphs of the rule file in place of the modifier grid.
+
+use std::collections::HashMap;
+
+use crate::{
+    bit::Bit,
+    board::{self, BoardDescription},
+    equations::Assignment,
+    grid::Position,
+    matrix::Matrix,
+    report::Report,
+    solver::{self, SolveResult, Strategy},
+};
+
+/// The offset of a cell from the pressed cell, in rows down and columns right.
+pub type Offset = (isize, isize);
+
+/// The press rules of a game variant. The default rules are plain Lights-Out: a press toggles the cell and its four neighbors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PressRules {
+    /// The offsets toggled by a press on a cell without a glyph.
+    pub stencil: Vec<Offset>,
+    /// Whether a press toggles the pressed cell itself.
+    pub self_toggle: bool,
+    /// Whether offsets past an edge of the board continue from the opposite edge.
+    pub wrap: bool,
+    /// The stencils of the cells marked with each glyph, used in place of `stencil`.
+    pub glyphs: Vec<(char, Vec<Offset>)>,
+}
+
+impl Default for PressRules {
+    fn default() -> Self {
+        PressRules {
+            stencil: vec![(-1, 0), (1, 0), (0, -1), (0, 1)],
+            self_toggle: true,
+            wrap: false,
+            glyphs: vec![],
+        }
+    }
+}
+
+/// A board played under a set of press rules, with the glyph of every cell. A space marks a cell without a glyph.
+#[derive(Clone, Debug)]
+pub struct RuleBoard {
+    pub board: BoardDescription,
+    glyphs: Vec<Vec<char>>,
+}
+
+impl RuleBoard {
+    /// The glyph of the cell at `pos`, or a space if it has none.
+    pub fn glyph(&self, pos: Position) -> char {
+        self.glyphs
+            .get(pos.row)
+            .and_then(|line| line.get(pos.col))
+            .copied()
+            .unwrap_or(' ')
+    }
+}
+
+impl PressRules {
+    /// Parses the contents of a rule file. Settings left out keep their default values.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rules = PressRules::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(format!("Line {} is not a setting.", number + 1));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key.split_whitespace().collect::<Vec<_>>()[..] {
+                ["stencil"] => rules.stencil = parse_stencil(value, number)?,
+                ["self"] => rules.self_toggle = parse_flag(value, number)?,
+                ["wrap"] => rules.wrap = parse_flag(value, number)?,
+                ["glyph", glyph] => {
+                    let mut chars = glyph.chars();
+                    let (Some(glyph), None) = (chars.next(), chars.next()) else {
+                        return Err(format!(
+                            "Glyph \"{}\" on line {} is not a single character.",
+                            glyph,
+                            number + 1
+                        ));
+                    };
+                    if rules.glyphs.iter().any(|(g, _)| *g == glyph) {
+                        return Err(format!(
+                            "Glyph '{}' on line {} is already defined.",
+                            glyph,
+                            number + 1
+                        ));
+                    }
+                    rules.glyphs.push((glyph, parse_stencil(value, number)?));
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown setting \"{}\" on line {}.",
+                        key,
+                        number + 1
+                    ))
+                }
+            }
+        }
+        Ok(rules)
+    }
+
+    /// The stencil of a cell marked with `glyph`, where a space stands for no glyph. Returns `None` for an unknown glyph.
+    pub fn stencil_for(&self, glyph: char) -> Option<&[Offset]> {
+        if glyph == ' ' {
+            return Some(&self.stencil);
+        }
+        self.glyphs
+            .iter()
+            .find(|(g, _)| *g == glyph)
+            .map(|(_, stencil)| stencil.as_slice())
+    }
+
+    /// Parses a board file for these rules: a board, optionally followed by an empty line and a grid of glyphs. Fails on glyphs
+    /// the rules do not define, and on glyphs over holes.
+    pub fn parse_board(&self, text: &str) -> Result<RuleBoard, String> {
+        let text = text.replace("\r\n", "\n");
+        let text = board::strip_metadata(&text);
+        let (board_text, glyph_text) = text.split_once("\n\n").unwrap_or((text, ""));
+        let board = match board::parse_board_rows(board_text) {
+            Ok((_, board)) => board,
+            Err(e) => return Err(format!("Could not parse board: {}", e)),
+        };
+        if board.cell_count() == 0 {
+            return Err("Board has no pressable cells.".to_string());
+        }
+        let glyphs: Vec<Vec<char>> = glyph_text
+            .lines()
+            .take(board.height())
+            .map(|line| line.chars().take(board.width()).collect())
+            .collect();
+        for (row, line) in glyphs.iter().enumerate() {
+            for (col, &glyph) in line.iter().enumerate() {
+                if glyph == ' ' {
+                    continue;
+                }
+                if self.stencil_for(glyph).is_none() {
+                    return Err(format!(
+                        "Unknown glyph '{}' at row {}, column {}.",
+                        glyph, row, col
+                    ));
+                }
+                if !matches!(board.get(Position::new(row, col)), Some(Some(_))) {
+                    return Err(format!(
+                        "Glyph applied to empty cell at row {}, column {}.",
+                        row, col
+                    ));
+                }
+            }
+        }
+        Ok(RuleBoard { board, glyphs })
+    }
+
+    /// Lists the cells of `board` that a press on `pos` toggles, in reading order. Offsets that land on holes, or off the board
+    /// without `wrap`, toggle nothing, and a cell reached by several offsets toggles once.
+    pub fn affected(&self, board: &RuleBoard, pos: Position) -> Vec<Position> {
+        let (width, height) = (board.board.width(), board.board.height());
+        let stencil = self.stencil_for(board.glyph(pos)).unwrap_or(&self.stencil);
+        let own = self.self_toggle.then_some((0, 0));
+        let mut cells: Vec<Position> = stencil
+            .iter()
+            .copied()
+            .chain(own)
+            .filter_map(|(drow, dcol)| {
+                let row = step(pos.row, drow, height, self.wrap)?;
+                let col = step(pos.col, dcol, width, self.wrap)?;
+                Some(Position::new(row, col))
+            })
+            .filter(|&cell| matches!(board.board.get(cell), Some(Some(_))))
+            .collect();
+        cells.sort_by_key(|cell| (cell.row, cell.col));
+        cells.dedup();
+        cells
+    }
+
+    /// Computes the matrix of `board` under these rules, like `BoardDescription::to_matrix` does under the built in ones.
+    pub fn to_matrix(&self, board: &RuleBoard) -> (Matrix, HashMap<usize, Position>) {
+        let (width, height) = (board.board.width(), board.board.height());
+        let cells: Vec<Position> = Position::iter_row_major(width, height)
+            .filter(|&pos| matches!(board.board.get(pos), Some(Some(_))))
+            .collect();
+        let index_of: HashMap<Position, usize> =
+            cells.iter().enumerate().map(|(i, &pos)| (pos, i)).collect();
+
+        let count = cells.len();
+        let mut matrix_data = vec![vec![Bit::Off; count]; count];
+        for (pressed, &pos) in cells.iter().enumerate() {
+            for affected in self.affected(board, pos) {
+                matrix_data[index_of[&affected]][pressed] = Bit::On;
+            }
+        }
+        let mut matrix = Matrix::new(matrix_data, count, count).expect("Could not form matrix");
+        matrix.augment_column(&board.board.constant_column());
+
+        (matrix, cells.into_iter().enumerate().collect())
+    }
+
+    /// Finds a solution of `board` under these rules with as few presses as possible, picking the strategy like `Strategy::Auto`.
+    pub fn minimum_solution(&self, board: &RuleBoard) -> SolveResult {
+        let (matrix, indexed_locations) = self.to_matrix(board);
+        let eqns = Report::new(&matrix).equations;
+        let nullity = eqns.free_var_count();
+        let bits = solver::auto_minimum(&eqns);
+        SolveResult {
+            solution: bits.map(|bits| {
+                board
+                    .board
+                    .assign_assignment(Assignment::from_bits(&bits), &indexed_locations)
+            }),
+            exact: Strategy::Auto.is_exact(nullity),
+        }
+    }
+}
+
+/// Moves `from` by `delta` along an axis of length `len`, wrapping around it with `wrap`.
+fn step(from: usize, delta: isize, len: usize, wrap: bool) -> Option<usize> {
+    if wrap {
+        Some((from as isize + delta).rem_euclid(len as isize) as usize)
+    } else {
+        from.checked_add_signed(delta).filter(|&to| to < len)
+    }
+}
+
+/// Parses a stencil, a list of `rows,columns` offsets separated by whitespace.
+fn parse_stencil(value: &str, number: usize) -> Result<Vec<Offset>, String> {
+    value
+        .split_whitespace()
+        .map(|offset| {
+            offset
+                .split_once(',')
+                .and_then(|(row, col)| Some((row.trim().parse().ok()?, col.trim().parse().ok()?)))
+                .ok_or_else(|| format!("Invalid offset \"{}\" on line {}.", offset, number + 1))
+        })
+        .collect()
+}
+
+/// Parses a `yes` or `no` setting.
+fn parse_flag(value: &str, number: usize) -> Result<bool, String> {
+    match value {
+        "yes" => Ok(true),
+        "no" => Ok(false),
+        _ => Err(format!(
+            "Expected yes or no on line {}, found \"{}\".",
+            number + 1,
+            value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_match_the_built_in_presses() {
+        for text in ["000\n000\n000", "10 1\n0110\n1 01", "01\n10\n11"] {
+            let board = board::parse_board_file(text).unwrap();
+            let rule_board = PressRules::default().parse_board(text).unwrap();
+            assert_eq!(
+                PressRules::default().to_matrix(&rule_board).0.to_string(),
+                board.to_matrix().0.to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn parses_every_setting() {
+        let rules = PressRules::parse(
+            "# Knight moves.\nstencil: -2,1 2,-1\nself: no\nwrap: yes\n\nglyph X: 0,1 0,-1\n",
+        )
+        .unwrap();
+        assert_eq!(rules.stencil, [(-2, 1), (2, -1)]);
+        assert!(!rules.self_toggle);
+        assert!(rules.wrap);
+        assert_eq!(rules.glyphs, [('X', vec![(0, 1), (0, -1)])]);
+        assert_eq!(rules.stencil_for('X'), Some(&[(0, 1), (0, -1)][..]));
+        assert_eq!(rules.stencil_for('Y'), None);
+    }
+
+    #[test]
+    fn rejects_invalid_rule_files() {
+        assert!(PressRules::parse("stencil 0,1").is_err());
+        assert!(PressRules::parse("stencil: 0;1").is_err());
+        assert!(PressRules::parse("self: maybe").is_err());
+        assert!(PressRules::parse("reach: 2").is_err());
+        assert!(PressRules::parse("glyph XY: 0,1").is_err());
+        assert!(PressRules::parse("glyph X: 0,1\nglyph X: 1,0").is_err());
+    }
+
+    #[test]
+    fn glyphs_must_be_known_and_on_cells() {
+        let rules = PressRules::parse("glyph X: 0,1").unwrap();
+        assert!(rules.parse_board("00\n00\n\nX \n X").is_ok());
+        assert!(rules.parse_board("00\n00\n\nY ").is_err());
+        assert!(rules.parse_board("0 \n00\n\n X").is_err());
+    }
+
+    #[test]
+    fn presses_wrap_around_the_edges() {
+        let rules = PressRules::parse("wrap: yes").unwrap();
+        let board = rules.parse_board("000\n000").unwrap();
+        let affected = rules.affected(&board, Position::new(0, 0));
+        // Up and down both reach the other row, and left wraps to the last column.
+        assert_eq!(
+            affected,
+            [
+                Position::new(0, 0),
+                Position::new(0, 1),
+                Position::new(0, 2),
+                Position::new(1, 0)
+            ]
+        );
+        let mut rules = rules;
+        rules.self_toggle = false;
+        assert!(!rules
+            .affected(&board, Position::new(1, 1))
+            .contains(&Position::new(1, 1)));
+    }
+
+    #[test]
+    fn glyph_stencils_replace_the_default_one() {
+        // Cells marked with X only toggle their right neighbor and themselves.
+        let rules = PressRules::parse("glyph X: 0,1").unwrap();
+        let board = rules.parse_board("000\n000\n\nX").unwrap();
+        assert_eq!(
+            rules.affected(&board, Position::new(0, 0)),
+            [Position::new(0, 0), Position::new(0, 1)]
+        );
+        let solution = rules.minimum_solution(&board);
+        assert!(solution.exact);
+        let solution = solution.solution.unwrap();
+        let mut pressed = vec![vec![Bit::Off; 3]; 2];
+        for pos in solution.presses() {
+            for cell in rules.affected(&board, pos) {
+                pressed[cell.row][cell.col] += Bit::On;
+            }
+        }
+        // Every cell starts off, so every cell is toggled an odd number of times.
+        assert!(pressed.iter().flatten().all(|&bit| bit == Bit::On));
+    }
+}
@@ -0,0 +1,104 @@
+/// The version of the JSON format described by `BOARD_SCHEMA`. Every board document carries it in its `schema_version` field.
+/// Backwards compatible additions keep the version, and any other change increments it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The version of the JSON format described by `RESULT_SCHEMA`, carried in the `schema_version` field of result documents.
+/// Version 2 added `final_board`.
+pub const RESULT_SCHEMA_VERSION: u32 = 2;
+
+/// JSON Schema of a board. Rows use the characters of board files, and the optional modifier grid uses those of modifier grids.
+/// The optional target, rigid semantics and par hold the same as in TOML board files. The target convention is a shorthand for a
+/// target of every cell lit or unlit, which a target grid overrides.
+pub const BOARD_SCHEMA: &str = r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$id": "urn:wayout-solver:schema:board:1",
+  "title": "Board",
+  "type": "object",
+  "required": ["schema_version", "rows"],
+  "properties": {
+    "schema_version": { "const": 1 },
+    "rows": {
+      "description": "The starting state, one string per row: 0 for an unlit cell, 1 for a lit cell, and a space for a hole.",
+      "type": "array",
+      "minItems": 1,
+      "items": { "type": "string", "pattern": "^[01 ]+$" }
+    },
+    "modifiers": {
+      "description": "The modifier grid, one string per row: H, V, O, or a space for a cell without modifiers.",
+      "type": "array",
+      "items": { "type": "string", "pattern": "^[HVO ]*$" }
+    },
+    "target": {
+      "description": "The value each cell must end up with, one string per row, with spaces over holes. Defaults to every cell lit.",
+      "type": "array",
+      "items": { "type": "string", "pattern": "^[01 ]*$" }
+    },
+    "target_convention": {
+      "description": "Whether every cell must end up lit (on) or unlit (off). A target grid takes precedence.",
+      "enum": ["on", "off"]
+    },
+    "rigid": {
+      "description": "How rigid cells behave. Defaults to shielded.",
+      "enum": ["shielded", "frozen", "unpressable", "inert"]
+    },
+    "par": { "description": "The minimum number of presses that solve the board.", "type": "integer", "minimum": 0 }
+  }
+}
+"##;
+
+/// JSON Schema of the result of solving a board. Positions count rows and columns from 0, top to bottom and left to right.
+pub const RESULT_SCHEMA: &str = r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$id": "urn:wayout-solver:schema:result:2",
+  "title": "Result",
+  "type": "object",
+  "required": ["schema_version", "board", "rank", "free_variables", "solution_count", "solutions"],
+  "properties": {
+    "schema_version": { "const": 2 },
+    "board": { "$ref": "urn:wayout-solver:schema:board:1" },
+    "rank": { "description": "Rank of the board matrix.", "type": "integer", "minimum": 0 },
+    "free_variables": { "description": "Number of free variables.", "type": "integer", "minimum": 0 },
+    "solution_count": {
+      "description": "Number of solutions as a decimal string, as it may exceed the range of JSON numbers.",
+      "type": "string",
+      "pattern": "^[0-9]+$"
+    },
+    "solutions": {
+      "description": "The solutions that were asked for, which need not be all of them.",
+      "type": "array",
+      "items": { "$ref": "#/$defs/solution" }
+    },
+    "final_board": {
+      "description": "The board after pressing the buttons of the first listed solution. Absent if none is listed.",
+      "$ref": "urn:wayout-solver:schema:board:1"
+    }
+  },
+  "$defs": {
+    "position": {
+      "type": "object",
+      "required": ["row", "col"],
+      "properties": {
+        "row": { "type": "integer", "minimum": 0 },
+        "col": { "type": "integer", "minimum": 0 }
+      }
+    },
+    "solution": {
+      "type": "object",
+      "required": ["press_count", "presses"],
+      "properties": {
+        "press_count": { "type": "integer", "minimum": 0 },
+        "presses": { "type": "array", "items": { "$ref": "#/$defs/position" } }
+      }
+    }
+  }
+}
+"##;
+
+/// Finds a schema by name, `board` or `result`.
+pub fn schema(name: &str) -> Option<&'static str> {
+    match name {
+        "board" => Some(BOARD_SCHEMA),
+        "result" => Some(RESULT_SCHEMA),
+        _ => None,
+    }
+}
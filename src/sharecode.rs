@@ -0,0 +1,107 @@
+use crate::{
+    bit::Bit,
+    board::{BoardDescription, Cell},
+    packed,
+};
+
+/// The URL-safe base64 alphabet, so that codes can be pasted anywhere.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes `board` as a short share code of the form `v2:<data>`, where the data is the board packed by `packed::pack_board`
+/// and written in URL-safe base64. Unlike board files, share codes keep every detail of the board, targets included.
+pub fn encode(board: &BoardDescription) -> String {
+    format!("v2:{}", base64_encode(&packed::pack_board(board)))
+}
+
+/// Decodes a share code produced by `encode`, or an older code of the form `<width>x<height>:<data>`.
+pub fn decode(code: &str) -> Result<BoardDescription, String> {
+    let invalid = || format!("Invalid share code \"{}\".", code);
+    let (prefix, data) = code.trim().split_once(':').ok_or_else(invalid)?;
+    if prefix == "v2" {
+        let bytes = base64_decode(data).ok_or_else(invalid)?;
+        return packed::unpack_board(&bytes).map_err(|_| invalid());
+    }
+    decode_v1(prefix, data).ok_or_else(invalid)
+}
+
+/// Decodes the size and data of a first version share code, which has four bits per cell: the highest marks a cell that is not
+/// a hole, the next its starting value, and the lowest two its modifier (none, `H`, `V` or `O`). Cells are packed two per byte
+/// in reading order.
+fn decode_v1(size: &str, data: &str) -> Option<BoardDescription> {
+    let (width, height) = size.split_once('x')?;
+    let width: usize = width.parse().ok()?;
+    let height: usize = height.parse().ok()?;
+    let bytes = base64_decode(data)?;
+    let cells = width.checked_mul(height)?;
+    if width == 0 || height == 0 || bytes.len() != cells.div_ceil(2) {
+        return None;
+    }
+
+    let mut rows = vec![];
+    for row in 0..height {
+        let mut line = vec![];
+        for col in 0..width {
+            let index = row * width + col;
+            let nibble = if index.is_multiple_of(2) {
+                bytes[index / 2] >> 4
+            } else {
+                bytes[index / 2] & 0b1111
+            };
+            if nibble & 0b1000 == 0 {
+                line.push(None);
+                continue;
+            }
+            let value = if nibble & 0b100 == 0 {
+                Bit::Off
+            } else {
+                Bit::On
+            };
+            let mut cell = Cell::new_basic(value);
+            match nibble & 0b11 {
+                1 => cell.apply_modifier('H'),
+                2 => cell.apply_modifier('V'),
+                3 => cell.apply_modifier('O'),
+                _ => true,
+            };
+            line.push(Some(cell));
+        }
+        rows.push(line);
+    }
+    BoardDescription::from_rows(rows)
+}
+
+/// Encodes `bytes` in URL-safe base64, without padding.
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut res = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (chunk.get(1).copied().unwrap_or(0) as u32) << 8
+            | chunk.get(2).copied().unwrap_or(0) as u32;
+        for i in 0..chunk.len() + 1 {
+            res.push(ALPHABET[(n >> (18 - 6 * i) & 0b111111) as usize] as char);
+        }
+    }
+    res
+}
+
+/// Decodes URL-safe base64 without padding. Returns `None` on invalid input.
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let values: Vec<u32> = text
+        .bytes()
+        .map(|b| ALPHABET.iter().position(|&a| a == b).map(|p| p as u32))
+        .collect::<Option<_>>()?;
+    let mut bytes = vec![];
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, &v)| acc | v << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(bytes)
+}
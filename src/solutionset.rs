@@ -0,0 +1,172 @@
+use crate::{bit::Bit, equations::Equations, kernel};
+
+/// The solutions of a system of equations, as a coset: one particular solution plus every combination of a basis of the kernel.
+///
+/// Solutions are vectors of bits, where the `i`th bit is the value of the variable at index `i`. The basis is kept in reduced
+/// echelon form, so every basis vector has a pivot variable that no other basis vector sets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolutionSet {
+    vars: usize,
+    /// A solution of the set, or `None` if the set is empty.
+    particular: Option<Vec<Bit>>,
+    /// The basis vectors, along with their pivot variables.
+    basis: Vec<(usize, Vec<Bit>)>,
+}
+
+impl SolutionSet {
+    /// Collects the solutions of `eqns`.
+    pub fn new(eqns: &Equations) -> Self {
+        let vars = eqns.var_count();
+        let particular = eqns
+            .particular_solution()
+            .and_then(|assignment| assignment.to_bits(vars));
+        SolutionSet::from_parts(vars, particular, eqns.kernel_basis())
+    }
+
+    /// Builds the set of `particular` plus every combination of `spanning`, whose vectors need not be independent. A `particular`
+    /// of `None` builds the empty set. Every vector must have `vars` bits.
+    pub fn from_parts(vars: usize, particular: Option<Vec<Bit>>, spanning: Vec<Vec<Bit>>) -> Self {
+        let mut basis: Vec<(usize, Vec<Bit>)> = vec![];
+        if particular.is_some() {
+            for mut vector in spanning {
+                for (pivot, basis_vector) in &basis {
+                    if vector[*pivot] == Bit::On {
+                        vector = kernel::add(&vector, basis_vector);
+                    }
+                }
+                let Some(pivot) = vector.iter().position(|&bit| bit == Bit::On) else {
+                    continue;
+                };
+                for (_, basis_vector) in &mut basis {
+                    if basis_vector[pivot] == Bit::On {
+                        *basis_vector = kernel::add(basis_vector, &vector);
+                    }
+                }
+                basis.push((pivot, vector));
+            }
+        }
+        SolutionSet {
+            vars,
+            particular,
+            basis,
+        }
+    }
+
+    /// The number of variables of every solution.
+    pub fn var_count(&self) -> usize {
+        self.vars
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particular.is_none()
+    }
+
+    /// A solution of the set, or `None` if the set is empty.
+    pub fn particular(&self) -> Option<&[Bit]> {
+        self.particular.as_deref()
+    }
+
+    /// A basis of the kernel, in reduced echelon form.
+    pub fn basis(&self) -> impl Iterator<Item = &[Bit]> {
+        self.basis.iter().map(|(_, vector)| vector.as_slice())
+    }
+
+    /// The number of basis vectors, which is the number of free variables of a non-empty set.
+    pub fn dimension(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// Counts the solutions in the set. Returns `None` if the count does not fit in a `u128`.
+    pub fn cardinality(&self) -> Option<u128> {
+        match self.particular {
+            None => Some(0),
+            Some(_) => 1u128.checked_shl(self.dimension() as u32),
+        }
+    }
+
+    /// Checks whether `bits` is a solution in the set.
+    pub fn contains(&self, bits: &[Bit]) -> bool {
+        let Some(particular) = &self.particular else {
+            return false;
+        };
+        if bits.len() != self.vars {
+            return false;
+        }
+        let mut difference = kernel::add(bits, particular);
+        for (pivot, vector) in &self.basis {
+            if difference[*pivot] == Bit::On {
+                difference = kernel::add(&difference, vector);
+            }
+        }
+        kernel::weight(&difference) == 0
+    }
+
+    /// The solution at `index`, which adds the `j`th basis vector to the particular solution when the `j`th lowest bit of `index`
+    /// is set. Returns `None` if `index` is not below the cardinality of the set.
+    pub fn nth(&self, index: u128) -> Option<Vec<Bit>> {
+        if self.cardinality().is_some_and(|count| index >= count) {
+            return None;
+        }
+        let mut solution = self.particular.clone()?;
+        for (j, (_, vector)) in self.basis.iter().enumerate() {
+            if index >> j & 1 == 1 {
+                solution = kernel::add(&solution, vector);
+            }
+        }
+        Some(solution)
+    }
+
+    /// Intersects the set with the constraint that the values of the variables in `vars` add up to `value`.
+    pub fn with_constraint(&self, vars: &[usize], value: Bit) -> Self {
+        let Some(particular) = &self.particular else {
+            return self.clone();
+        };
+        let evaluate = |vector: &[Bit]| vars.iter().fold(Bit::Off, |sum, &var| sum + vector[var]);
+        let Some(j) = self
+            .basis
+            .iter()
+            .position(|(_, vector)| evaluate(vector) == Bit::On)
+        else {
+            // The constraint has the same value on every solution.
+            return if evaluate(particular) == value {
+                self.clone()
+            } else {
+                SolutionSet::from_parts(self.vars, None, vec![])
+            };
+        };
+        // Adding the `j`th basis vector flips the constraint, so it fixes the particular solution and every other basis vector.
+        let pivot_vector = &self.basis[j].1;
+        let particular = if evaluate(particular) == value {
+            particular.clone()
+        } else {
+            kernel::add(particular, pivot_vector)
+        };
+        let spanning = self
+            .basis
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != j)
+            .map(|(_, (_, vector))| match evaluate(vector) {
+                Bit::On => kernel::add(vector, pivot_vector),
+                Bit::Off => vector.clone(),
+            })
+            .collect();
+        SolutionSet::from_parts(self.vars, Some(particular), spanning)
+    }
+
+    /// Intersects the set with the constraint that the variable `var` has `value`.
+    pub fn with_value(&self, var: usize, value: Bit) -> Self {
+        self.with_constraint(&[var], value)
+    }
+
+    /// Projects the set onto the variables in `region`: the `i`th variable of the result is `region[i]`. Different solutions
+    /// that agree on `region` project to the same one.
+    pub fn project(&self, region: &[usize]) -> Self {
+        let restrict = |vector: &[Bit]| region.iter().map(|&var| vector[var]).collect();
+        SolutionSet::from_parts(
+            region.len(),
+            self.particular.as_deref().map(restrict),
+            self.basis().map(restrict).collect(),
+        )
+    }
+}
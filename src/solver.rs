@@ -0,0 +1,543 @@
+use std::cmp::Ordering;
+
+use crate::{
+    bit::Bit,
+    bitslice,
+    board::{BoardAssignment, BoardDescription},
+    crosscheck::MAX_CROSSCHECK_CELLS,
+    decoder::{CosetDecoder, MAX_DECODER_NULLITY},
+    equations::{Assignment, Equations},
+    grid::Position,
+    kernel,
+    matrix::PivotStrategy,
+    report::Report,
+    trace,
+};
+
+/// The largest number of free variables `Strategy::Auto` enumerates every solution for. Bit-sliced enumeration takes `2^n / 64`
+/// steps, and no memory beyond a block of solutions.
+pub const MAX_ENUMERATED_NULLITY: usize = 24;
+
+/// The largest number of free variables `Strategy::Search` searches. Above that even a pruned search has no hope of finishing, and
+/// it falls back to `Greedy`.
+pub const MAX_SEARCHED_NULLITY: usize = 128;
+
+/// How to search for a solution with as few presses as possible.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Picks one of the other strategies from the number of free variables of the board, as described by `resolve`.
+    #[default]
+    Auto,
+    /// Evaluates every solution, 64 at a time. Exact.
+    Enumerate,
+    /// Searches the coset of a particular solution with a `CosetDecoder`. Exact, and fast for repeated queries on one shape, but
+    /// falls back to `Greedy` above `MAX_DECODER_NULLITY` free variables.
+    Coset,
+    /// Greedily improves a particular solution with a reduced kernel basis. Usually close to the minimum, but not guaranteed to
+    /// reach it.
+    Greedy,
+    /// Searches the solutions by branch and bound with `Equations::minimum_weight_solution`. Exact, and usually far faster than
+    /// `Enumerate` on boards with many free variables, but its worst case still doubles with every free variable. Falls back to
+    /// `Greedy` above `MAX_SEARCHED_NULLITY` free variables.
+    Search,
+}
+
+impl Strategy {
+    /// Finds the strategy for a name, `auto`, `enumerate`, `coset`, `greedy` or `search`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Strategy::Auto),
+            "enumerate" => Some(Strategy::Enumerate),
+            "coset" => Some(Strategy::Coset),
+            "greedy" => Some(Strategy::Greedy),
+            "search" => Some(Strategy::Search),
+            _ => None,
+        }
+    }
+
+    /// Picks the strategy to run for a board with `nullity` free variables. Every strategy but `Auto` is kept.
+    ///
+    /// `Auto` enumerates up to `MAX_ENUMERATED_NULLITY` free variables, where bit-sliced enumeration is fast and needs no pruning,
+    /// and searches by branch and bound above that. Both are exact, so `Auto` only falls back to `Greedy` where `Search` does,
+    /// above `MAX_SEARCHED_NULLITY` free variables.
+    pub fn resolve(self, nullity: usize) -> Strategy {
+        match self {
+            Strategy::Auto if nullity <= MAX_ENUMERATED_NULLITY => Strategy::Enumerate,
+            Strategy::Auto => Strategy::Search,
+            strategy => strategy,
+        }
+    }
+
+    /// Whether the strategy always finds a solution with the fewest presses for a board with `nullity` free variables.
+    pub fn is_exact(self, nullity: usize) -> bool {
+        match self.resolve(nullity) {
+            Strategy::Enumerate => true,
+            Strategy::Coset => nullity <= MAX_DECODER_NULLITY,
+            Strategy::Search => nullity <= MAX_SEARCHED_NULLITY,
+            _ => false,
+        }
+    }
+}
+
+/// How a `Solve` backend should search for a solution.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveOptions {
+    /// How to search for the solution with the fewest presses.
+    pub strategy: Strategy,
+    /// How to pick pivot columns during elimination, for backends that eliminate.
+    pub pivot: PivotStrategy,
+}
+
+/// The answer of a `Solve` backend.
+pub struct SolveResult {
+    /// A solution with as few presses as the backend could find, or `None` if it found no solution.
+    pub solution: Option<BoardAssignment>,
+    /// Whether the solution is guaranteed to have the fewest presses.
+    pub exact: bool,
+}
+
+/// A way of finding a solution of a board with as few presses as possible.
+pub trait Solve {
+    fn solve(&self, board: &BoardDescription, opts: &SolveOptions) -> SolveResult;
+
+    /// The largest number of cells the backend accepts, or `None` if it accepts boards of any size.
+    fn max_cells(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// The backends that can be picked with `--backend`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// Gaussian elimination, with `Gaussian`.
+    #[default]
+    Gaussian,
+    /// Pressing every set of cells, with `BruteForce`.
+    BruteForce,
+}
+
+impl Backend {
+    /// Finds the backend for a name, `gaussian` or `brute-force`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gaussian" => Some(Backend::Gaussian),
+            "brute-force" => Some(Backend::BruteForce),
+            _ => None,
+        }
+    }
+
+    /// The name of the backend, as accepted by `from_name`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Gaussian => "gaussian",
+            Backend::BruteForce => "brute-force",
+        }
+    }
+
+    /// The solver behind the backend.
+    pub fn solver(self) -> &'static dyn Solve {
+        match self {
+            Backend::Gaussian => &Gaussian,
+            Backend::BruteForce => &BruteForce,
+        }
+    }
+}
+
+/// Solves the board by Gaussian elimination over GF(2), then searches the solutions by `SolveOptions::strategy`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Gaussian;
+
+impl Solve for Gaussian {
+    fn solve(&self, board: &BoardDescription, opts: &SolveOptions) -> SolveResult {
+        let (matrix, indexed_locations) = board.to_matrix();
+        let eqns = Report::with_strategy(&matrix, opts.pivot).equations;
+        let nullity = eqns.free_var_count();
+        let bits = match opts.strategy.resolve(nullity) {
+            Strategy::Enumerate => bitslice::minimum(&eqns),
+            Strategy::Coset => match CosetDecoder::new(board) {
+                Some(decoder) => {
+                    return SolveResult {
+                        solution: decoder.decode(board).ok().flatten(),
+                        exact: true,
+                    }
+                }
+                None => greedy_minimum(&eqns),
+            },
+            Strategy::Search => search_minimum(&eqns),
+            Strategy::Greedy | Strategy::Auto => greedy_minimum(&eqns),
+        };
+        SolveResult {
+            solution: bits.map(|bits| {
+                board.assign_assignment(Assignment::from_bits(&bits), &indexed_locations)
+            }),
+            exact: opts.strategy.is_exact(nullity),
+        }
+    }
+}
+
+/// Solves the board by pressing every set of its cells with `BoardDescription::press`, which shares none of the matrix code. Always
+/// exact, ignores `SolveOptions`, and accepts boards with up to `MAX_CROSSCHECK_CELLS` cells. Among several minimums, it returns
+/// the first by `kernel::cmp_presses`, like the exact strategies of `Gaussian`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BruteForce;
+
+impl Solve for BruteForce {
+    fn solve(&self, board: &BoardDescription, _opts: &SolveOptions) -> SolveResult {
+        let cells: Vec<Position> = Position::iter_row_major(board.width(), board.height())
+            .filter(|&pos| board[pos].is_some())
+            .collect();
+        let mut best: Option<Vec<Bit>> = None;
+        if cells.len() <= MAX_CROSSCHECK_CELLS {
+            for mask in 0..1u32 << cells.len() {
+                let presses: Vec<Bit> = (0..cells.len())
+                    .map(|i| {
+                        if mask >> i & 1 == 1 {
+                            Bit::On
+                        } else {
+                            Bit::Off
+                        }
+                    })
+                    .collect();
+                if best
+                    .as_ref()
+                    .is_some_and(|best| kernel::cmp_presses(&presses, best).is_ge())
+                {
+                    continue;
+                }
+                let mut played = board.clone();
+                for (&pos, &bit) in cells.iter().zip(&presses) {
+                    if bit == Bit::On {
+                        played.press(pos);
+                    }
+                }
+                if played.is_solved() {
+                    best = Some(presses);
+                }
+            }
+        }
+        let indexed_locations = cells.into_iter().enumerate().collect();
+        SolveResult {
+            solution: best.map(|bits| {
+                board.assign_assignment(Assignment::from_bits(&bits), &indexed_locations)
+            }),
+            exact: true,
+        }
+    }
+
+    fn max_cells(&self) -> Option<usize> {
+        Some(MAX_CROSSCHECK_CELLS)
+    }
+}
+
+/// Solves `board` with the default backend and options: builds its matrix, eliminates it, and searches the solutions of the
+/// resulting equations for one with as few presses as possible. The solution is `None` if `board` has no solutions, and
+/// `SolveResult::exact` tells whether it is guaranteed to have the fewest presses.
+pub fn solve(board: &BoardDescription) -> SolveResult {
+    Backend::default()
+        .solver()
+        .solve(board, &SolveOptions::default())
+}
+
+/// Finds a solution of `board` with as few presses as possible, picking the strategy automatically. Returns `None` if `board`
+/// has no solutions.
+///
+/// The result is exact for boards with up to `MAX_SEARCHED_NULLITY` free variables, and greedily approximated above that.
+pub fn minimum_solution(board: &BoardDescription) -> Option<BoardAssignment> {
+    minimum_solution_with(board, Strategy::Auto)
+}
+
+/// Finds a solution of `board` with as few presses as possible using `strategy`. Returns `None` if `board` has no solutions.
+/// Among several exact minimums, `Enumerate`, `Coset` and `Search` all return the first by `kernel::cmp_presses`.
+pub fn minimum_solution_with(
+    board: &BoardDescription,
+    strategy: Strategy,
+) -> Option<BoardAssignment> {
+    let opts = SolveOptions {
+        strategy,
+        ..SolveOptions::default()
+    };
+    Gaussian.solve(board, &opts).solution
+}
+
+/// Finds a solution of `board` whose presses cost as little as possible in total, where pressing the cell of variable `i` costs
+/// `costs[i]`. Among several cheapest solutions, returns the first by `kernel::cmp_costs`. Returns no solution if `board` has none.
+///
+/// Walks every solution for boards with up to `MAX_ENUMERATED_NULLITY` free variables, and greedily improves the particular
+/// solution above that, in which case the result is not exact.
+pub fn cheapest_solution(board: &BoardDescription, costs: &[u32]) -> SolveResult {
+    let (matrix, indexed_locations) = board.to_matrix();
+    let eqns = Report::new(&matrix).equations;
+    let mut basis = eqns.kernel_basis();
+    let exact = basis.len() <= MAX_ENUMERATED_NULLITY;
+    let Some(mut current) = eqns
+        .particular_solution()
+        .and_then(|solution| solution.to_bits(eqns.var_count()))
+    else {
+        return SolveResult {
+            solution: None,
+            exact,
+        };
+    };
+
+    let mut best = current.clone();
+    if exact {
+        let _span = trace::span("enumerate");
+        for i in 1..(1usize << basis.len()) {
+            // Consecutive Gray codes differ in the bit at the position of the lowest set bit of `i`.
+            for (bit, &step) in current.iter_mut().zip(&basis[i.trailing_zeros() as usize]) {
+                *bit += step;
+            }
+            if kernel::cmp_costs(&current, &best, costs) == Ordering::Less {
+                best.clone_from(&current);
+            }
+        }
+    } else {
+        kernel::reduce_basis(&mut basis);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for vector in &basis {
+                let candidate = kernel::add(&best, vector);
+                if kernel::cmp_costs(&candidate, &best, costs) == Ordering::Less {
+                    best = candidate;
+                    improved = true;
+                }
+            }
+        }
+    }
+    SolveResult {
+        solution: Some(board.assign_assignment(Assignment::from_bits(&best), &indexed_locations)),
+        exact,
+    }
+}
+
+/// Finds a solution of `eqns` with as few presses as possible, picking the strategy like `Strategy::Auto`. The result is exact
+/// whenever `Strategy::Auto.is_exact` holds for the free variables of `eqns`.
+pub(crate) fn auto_minimum(eqns: &Equations) -> Option<Vec<Bit>> {
+    match Strategy::Auto.resolve(eqns.free_var_count()) {
+        Strategy::Enumerate => bitslice::minimum(eqns),
+        _ => search_minimum(eqns),
+    }
+}
+
+/// Searches for a solution of `eqns` with as few presses as possible like `Strategy::Search`, greedily above
+/// `MAX_SEARCHED_NULLITY` free variables.
+fn search_minimum(eqns: &Equations) -> Option<Vec<Bit>> {
+    match eqns.minimum_weight_solution() {
+        Some(solution) => solution.to_bits(eqns.var_count()),
+        None => greedy_minimum(eqns),
+    }
+}
+
+/// Greedily improves the particular solution of `eqns` by adding the vectors of a reduced kernel basis while that removes presses.
+fn greedy_minimum(eqns: &Equations) -> Option<Vec<Bit>> {
+    let mut best = eqns.particular_solution()?.to_bits(eqns.var_count())?;
+    let mut basis = eqns.kernel_basis();
+    kernel::reduce_basis(&mut basis);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for vector in &basis {
+            let candidate = kernel::add(&best, vector);
+            if kernel::weight(&candidate) < kernel::weight(&best) {
+                best = candidate;
+                improved = true;
+            }
+        }
+    }
+    Some(best)
+}
+
+/// Finds a solution of `board` with as few presses as possible that does not press the cell at `pos`. Returns `None` if `board` has
+/// no solutions, and `Some(None)` if every solution presses that cell.
+///
+/// Fixes the cell to `Off` with `Equations::substitute`, and searches the remaining solutions with `auto_minimum`.
+pub fn solution_avoiding(
+    board: &BoardDescription,
+    pos: Position,
+) -> Option<Option<BoardAssignment>> {
+    let (matrix, indexed_locations) = board.to_matrix();
+    let eqns = Report::new(&matrix).equations;
+    if !eqns.is_consistent() {
+        return None;
+    }
+    let var = (0..indexed_locations.len()).find(|var| indexed_locations[var] == pos)?;
+    let constrained = eqns.substitute(var, Bit::Off);
+    let bits = auto_minimum(&constrained);
+    Some(bits.map(|bits| board.assign_assignment(Assignment::from_bits(&bits), &indexed_locations)))
+}
+
+/// Finds a solution of `board` with as few presses as possible that only presses cells in `region`. Returns `None` if `board`
+/// has no solutions, and `Some(None)` if every solution presses a cell outside of `region`. Also counts the solutions that stay
+/// inside `region`, or `None` if there are too many to count.
+///
+/// Fixes every cell outside of `region` to `Off` with `Equations::substitute`, and searches the remaining solutions like
+/// `solution_avoiding` does.
+pub fn solution_within(
+    board: &BoardDescription,
+    region: &[Position],
+) -> Option<(Option<BoardAssignment>, Option<u128>)> {
+    let (matrix, indexed_locations) = board.to_matrix();
+    let eqns = Report::new(&matrix).equations;
+    if !eqns.is_consistent() {
+        return None;
+    }
+    let constrained = (0..indexed_locations.len())
+        .filter(|var| !region.contains(&indexed_locations[var]))
+        .fold(eqns, |eqns, var| eqns.substitute(var, Bit::Off));
+    let bits = auto_minimum(&constrained);
+    Some((
+        bits.map(|bits| board.assign_assignment(Assignment::from_bits(&bits), &indexed_locations)),
+        constrained.solution_count(),
+    ))
+}
+
+/// Computes the par of `board`: the true minimum number of presses that solve it. Returns `None` if `board` has no solutions, or
+/// more than `MAX_DECODER_NULLITY` free variables, where the minimum cannot be guaranteed.
+pub fn par(board: &BoardDescription) -> Option<usize> {
+    let decoder = CosetDecoder::new(board)?;
+    decoder
+        .decode(board)
+        .ok()
+        .flatten()
+        .map(|solution| solution.count_ones())
+}
+
+/// Solves a staged puzzle on `board`, where every stage asks to reach a new pattern from the one reached before it. `stages` holds
+/// the target of each stage as rows of `0`s and `1`s, with spaces over holes, and the first stage starts from the starting state of
+/// `board`. Returns a solution with as few presses as possible for every stage, in order.
+///
+/// Fails naming the first stage, counting from 1, whose target does not fit the board or cannot be reached.
+pub fn solve_chain(
+    board: &BoardDescription,
+    stages: &[Vec<String>],
+) -> Result<Vec<BoardAssignment>, String> {
+    let mut current = board.clone();
+    let mut solutions = vec![];
+    for (stage, target) in stages.iter().enumerate() {
+        current
+            .apply_target(target)
+            .map_err(|e| format!("Stage {}: {}", stage + 1, e))?;
+        let solution = minimum_solution(&current).ok_or_else(|| {
+            format!(
+                "Stage {} cannot be reached from the pattern before it.",
+                stage + 1
+            )
+        })?;
+        current = current.apply_assignment(&solution);
+        solutions.push(solution);
+    }
+    Ok(solutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::parse_board_file, generator, rng::Rng};
+
+    /// Scrambles the solved board `text` by pressing half of its cells at random, so that it stays solvable.
+    fn scrambled(text: &str, rng: &mut Rng) -> BoardDescription {
+        let board = parse_board_file(text).unwrap();
+        generator::scramble(&board, board.cell_count() / 2, rng).0
+    }
+
+    /// A random solvable `size` by `size` board, where about one cell in six is a hole.
+    fn random_board(size: usize, rng: &mut Rng) -> BoardDescription {
+        let text: Vec<String> = (0..size)
+            .map(|_| {
+                (0..size)
+                    .map(|_| if rng.below(6) == 0 { ' ' } else { '1' })
+                    .collect()
+            })
+            .collect();
+        scrambled(&text.join("\n"), rng)
+    }
+
+    /// A random solvable board with `4 + pairs` free variables: a 4x4 board, and `pairs` pairs of cells that holes keep apart.
+    fn pairs_board(pairs: usize, rng: &mut Rng) -> BoardDescription {
+        let mut lines = vec![format!("{:11}", "1111"); 4];
+        for row in 0..pairs.div_ceil(4) {
+            lines.push(" ".repeat(11));
+            let in_row = (pairs - row * 4).min(4);
+            lines.push(format!("{:11}", vec!["11"; in_row].join(" ")));
+        }
+        scrambled(&lines.join("\n"), rng)
+    }
+
+    fn presses(board: &BoardDescription, strategy: Strategy) -> Option<usize> {
+        minimum_solution_with(board, strategy).map(|solution| solution.count_ones())
+    }
+
+    fn nullity(board: &BoardDescription) -> usize {
+        Report::new(&board.to_matrix().0).equations.free_var_count()
+    }
+
+    #[test]
+    fn search_matches_enumeration() {
+        let mut rng = Rng::new(991);
+        for size in 4..10 {
+            for _ in 0..20 {
+                let board = random_board(size, &mut rng);
+                let enumerated = presses(&board, Strategy::Enumerate);
+                assert!(enumerated.is_some());
+                assert_eq!(presses(&board, Strategy::Search), enumerated);
+                assert_eq!(presses(&board, Strategy::Auto), enumerated);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_strategies_break_ties_alike() {
+        let mut rng = Rng::new(1022);
+        for size in 4..10 {
+            for _ in 0..20 {
+                let board = random_board(size, &mut rng);
+                let solution = |strategy| {
+                    minimum_solution_with(&board, strategy).map(|solution| solution.to_string())
+                };
+                let enumerated = solution(Strategy::Enumerate);
+                assert_eq!(solution(Strategy::Search), enumerated);
+                assert_eq!(solution(Strategy::Coset), enumerated);
+            }
+        }
+    }
+
+    #[test]
+    fn enumeration_finds_the_lightest_solution() {
+        let mut rng = Rng::new(991);
+        for _ in 0..50 {
+            let board = random_board(4, &mut rng);
+            let eqns = Report::new(&board.to_matrix().0).equations;
+            let lightest = (0..)
+                .map_while(|index| eqns.nth_solution(index))
+                .map(|solution| kernel::weight(&solution.to_bits(eqns.var_count()).unwrap()))
+                .min();
+            let found = bitslice::minimum(&eqns).map(|bits| kernel::weight(&bits));
+            assert!(found.is_some());
+            assert_eq!(found, lightest);
+        }
+    }
+
+    #[test]
+    fn auto_matches_search_around_the_enumeration_limit() {
+        let mut rng = Rng::new(991);
+        for free in [MAX_ENUMERATED_NULLITY, MAX_ENUMERATED_NULLITY + 1] {
+            let board = pairs_board(free - 4, &mut rng);
+            assert_eq!(nullity(&board), free);
+            assert!(Strategy::Auto.is_exact(free));
+            assert!(presses(&board, Strategy::Search).is_some());
+            assert_eq!(
+                presses(&board, Strategy::Auto),
+                presses(&board, Strategy::Search)
+            );
+        }
+        assert_eq!(
+            Strategy::Auto.resolve(MAX_ENUMERATED_NULLITY),
+            Strategy::Enumerate
+        );
+        assert_eq!(
+            Strategy::Auto.resolve(MAX_ENUMERATED_NULLITY + 1),
+            Strategy::Search
+        );
+    }
+}
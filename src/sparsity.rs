@@ -0,0 +1,44 @@
+use wayout_solver::{bit::Bit, matrix::Matrix};
+
+/// The dots of a braille character by row and column within its 2 by 4 cell, as offsets from `BRAILLE_BASE`.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// The blank braille character, with no dots raised.
+const BRAILLE_BASE: u32 = 0x2800;
+
+/// Renders the sparsity pattern of the variable columns of `matrix` in braille, with a raised dot for every `On` entry. Each
+/// character covers 4 rows and 2 columns, so even large matrices fit in a terminal and structure such as bands or decoupled
+/// blocks stands out.
+pub fn braille(matrix: &Matrix) -> String {
+    let cols = matrix.variable_cols();
+    let mut res = String::new();
+    for top in (0..matrix.rows).step_by(4) {
+        for left in (0..cols).step_by(2) {
+            let mut code = BRAILLE_BASE;
+            for (dy, dots) in BRAILLE_DOTS.iter().enumerate() {
+                for (dx, dot) in dots.iter().enumerate() {
+                    let (row, col) = (top + dy, left + dx);
+                    if row < matrix.rows && col < cols && matrix.get(row, col) == Bit::On {
+                        code += dot;
+                    }
+                }
+            }
+            res.push(char::from_u32(code).expect("Braille patterns are valid characters"));
+        }
+        res.push('\n');
+    }
+    res
+}
+
+/// Counts the `On` entries of the variable columns of `matrix`, and their share of all entries.
+pub fn density(matrix: &Matrix) -> (usize, f64) {
+    let cols = matrix.variable_cols();
+    let count = (0..matrix.rows)
+        .map(|row| {
+            (0..cols)
+                .filter(|&col| matrix.get(row, col) == Bit::On)
+                .count()
+        })
+        .sum();
+    (count, count as f64 / (matrix.rows * cols).max(1) as f64)
+}
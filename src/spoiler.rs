@@ -0,0 +1,44 @@
+/// Ways of hiding solutions, so that shared write-ups do not give the answer away at a glance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spoiler {
+    /// Encode the text with `rot13`, which decodes it again.
+    Rot13,
+    /// Put the text behind a spoiler block: `||` markers in chat messages, and a collapsed section in HTML reports.
+    Block,
+}
+
+impl Spoiler {
+    /// Finds the spoiler style for a name, `rot13` or `block`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rot13" => Some(Spoiler::Rot13),
+            "block" => Some(Spoiler::Block),
+            _ => None,
+        }
+    }
+
+    /// Hides `text` in a chat message.
+    pub fn hide(self, text: &str) -> String {
+        match self {
+            Spoiler::Rot13 => rot13(text),
+            Spoiler::Block => format!("||{}||", text),
+        }
+    }
+}
+
+/// Rotates the letters of `text` by 13 places and its digits by 5, so that coordinates are hidden along with words. Applying it
+/// twice gives back `text`.
+pub fn rot13(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='z' => rotate(c, 'a', 26, 13),
+            'A'..='Z' => rotate(c, 'A', 26, 13),
+            '0'..='9' => rotate(c, '0', 10, 5),
+            c => c,
+        })
+        .collect()
+}
+
+fn rotate(c: char, first: char, len: u32, by: u32) -> char {
+    char::from_u32((c as u32 - first as u32 + by) % len + first as u32).unwrap_or(c)
+}
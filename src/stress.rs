@@ -0,0 +1,34 @@
+use wayout_solver::{board::BoardDescription, crosscheck, report::Report, solver};
+
+/// The largest number of cells of a board `check` also compares against brute force, which plays through `2^n` sets of presses.
+pub const MAX_BRUTE_FORCE_CELLS: usize = 10;
+
+/// Checks the solver on `board`. Any solution it finds must solve the board when its presses are played, and it must find one
+/// exactly when the system of the board is consistent. Boards with up to `MAX_BRUTE_FORCE_CELLS` cells are also compared with
+/// brute force by `crosscheck::crosscheck`. Fails describing the first problem found.
+pub fn check(board: &BoardDescription) -> Result<(), String> {
+    let (matrix, _) = board.to_matrix();
+    let consistent = Report::new(&matrix).equations.is_consistent();
+    match solver::minimum_solution(board) {
+        Some(solution) if !board.apply_assignment(&solution).is_solved() => {
+            return Err("The solution found does not solve the board when played.".to_string());
+        }
+        Some(_) if !consistent => {
+            return Err("A solution was found for an inconsistent system.".to_string());
+        }
+        None if consistent => {
+            return Err("The system is consistent, but no solution was found.".to_string());
+        }
+        _ => {}
+    }
+    if board.cell_count() <= MAX_BRUTE_FORCE_CELLS {
+        let discrepancies = crosscheck::crosscheck(board)?;
+        if !discrepancies.is_empty() {
+            return Err(format!(
+                "Brute force disagrees with linear algebra on {} sets of presses.",
+                discrepancies.len()
+            ));
+        }
+    }
+    Ok(())
+}
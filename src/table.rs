@@ -0,0 +1,98 @@
+use std::fmt::Display;
+
+/// How a `Table` is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableStyle {
+    /// Columns separated by box-drawing characters.
+    Pretty,
+    /// Columns separated by spaces only, for terminals without Unicode support and for easy parsing.
+    Plain,
+}
+
+/// A table of text cells with a header row, rendered with aligned columns.
+#[derive(Clone, Debug)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    style: TableStyle,
+}
+
+impl Table {
+    pub fn new<S: ToString>(headers: &[S]) -> Self {
+        Table {
+            headers: headers.iter().map(ToString::to_string).collect(),
+            rows: vec![],
+            style: TableStyle::Pretty,
+        }
+    }
+
+    /// Sets the style `self` is drawn with.
+    pub fn with_style(mut self, style: TableStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Adds a row to the bottom of `self`. Missing cells are left empty, and extra cells are dropped.
+    pub fn add_row<S: ToString>(&mut self, row: &[S]) {
+        let mut row: Vec<String> = row.iter().map(ToString::to_string).collect();
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    /// Computes the width of every column, in characters.
+    fn column_widths(&self) -> Vec<usize> {
+        (0..self.headers.len())
+            .map(|col| {
+                std::iter::once(&self.headers)
+                    .chain(&self.rows)
+                    .map(|row| row[col].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+}
+
+/// Pads `text` with spaces up to `width` characters. Numbers are aligned to the right, and everything else to the left.
+fn pad(text: &str, width: usize) -> String {
+    if !text.is_empty() && text.parse::<f64>().is_ok() {
+        format!("{:>width$}", text, width = width)
+    } else {
+        format!("{:<width$}", text, width = width)
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths = self.column_widths();
+        let line = |left: &str, mid: &str, right: &str| {
+            let segments: Vec<String> = widths.iter().map(|&w| "─".repeat(w + 2)).collect();
+            format!("{}{}{}", left, segments.join(mid), right)
+        };
+        let cells = |row: &[String]| -> Vec<String> {
+            row.iter()
+                .zip(&widths)
+                .map(|(text, &width)| pad(text, width))
+                .collect()
+        };
+
+        match self.style {
+            TableStyle::Pretty => {
+                writeln!(f, "{}", line("┌", "┬", "┐"))?;
+                writeln!(f, "│ {} │", cells(&self.headers).join(" │ "))?;
+                writeln!(f, "{}", line("├", "┼", "┤"))?;
+                for row in &self.rows {
+                    writeln!(f, "│ {} │", cells(row).join(" │ "))?;
+                }
+                writeln!(f, "{}", line("└", "┴", "┘"))
+            }
+            TableStyle::Plain => {
+                writeln!(f, "{}", cells(&self.headers).join("  ").trim_end())?;
+                for row in &self.rows {
+                    writeln!(f, "{}", cells(row).join("  ").trim_end())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use crate::config;
+
+const TARGET_EXTENSION: &str = "target";
+
+/// Finds the path of the target called `name`, checking that the name is a plain file name.
+fn target_path(name: &str) -> Result<PathBuf, String> {
+    config::named_file("target", "targets", name, TARGET_EXTENSION)
+}
+
+/// Stores the rows of `target` as the target called `name`, replacing any target of the same name. Targets hold a row of `0`s and
+/// `1`s per row of the board, with spaces over the holes, as read by `BoardDescription::apply_target`.
+pub fn save_target(name: &str, target: &[String]) -> Result<(), String> {
+    if target
+        .iter()
+        .flat_map(|row| row.chars())
+        .any(|c| !matches!(c, '0' | '1' | ' '))
+    {
+        return Err("Targets may only hold 0s, 1s and spaces.".to_string());
+    }
+    let path = target_path(name)?;
+    let mut text = target.join("\n");
+    text.push('\n');
+    std::fs::write(&path, text)
+        .map_err(|e| format!("Could not write target {}: {}", path.display(), e))
+}
+
+/// Loads the rows of the target called `name`.
+pub fn load_target(name: &str) -> Result<Vec<String>, String> {
+    let path = target_path(name)?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read target \"{}\": {}", name, e))?;
+    Ok(text.lines().map(str::to_string).collect())
+}
+
+/// Deletes the target called `name`.
+pub fn remove_target(name: &str) -> Result<(), String> {
+    let path = target_path(name)?;
+    std::fs::remove_file(&path).map_err(|e| format!("Could not remove target \"{}\": {}", name, e))
+}
+
+/// Lists the names of every saved target, in alphabetical order.
+pub fn list_targets() -> Result<Vec<String>, String> {
+    config::list_named("targets", TARGET_EXTENSION)
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use wayout_solver::decoder::CosetDecoder;
+
+use crate::config;
+
+const TEMPLATE_EXTENSION: &str = "template";
+
+/// Finds the path of the template called `name`, checking that the name is a plain file name.
+fn template_path(name: &str) -> Result<PathBuf, String> {
+    config::named_file("template", "templates", name, TEMPLATE_EXTENSION)
+}
+
+/// Stores `decoder` as the template called `name`, replacing any template of the same name.
+pub fn save_template(name: &str, decoder: &CosetDecoder) -> Result<(), String> {
+    let path = template_path(name)?;
+    std::fs::write(&path, decoder.to_text())
+        .map_err(|e| format!("Could not write template {}: {}", path.display(), e))
+}
+
+/// Loads the template called `name`.
+pub fn load_template(name: &str) -> Result<CosetDecoder, String> {
+    let path = template_path(name)?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read template \"{}\": {}", name, e))?;
+    CosetDecoder::from_text(&text).map_err(|e| format!("Invalid template \"{}\": {}", name, e))
+}
+
+/// Deletes the template called `name`.
+pub fn remove_template(name: &str) -> Result<(), String> {
+    let path = template_path(name)?;
+    std::fs::remove_file(&path)
+        .map_err(|e| format!("Could not remove template \"{}\": {}", name, e))
+}
+
+/// Lists the names of every saved template, in alphabetical order.
+pub fn list_templates() -> Result<Vec<String>, String> {
+    config::list_named("templates", TEMPLATE_EXTENSION)
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bit::Bit,
+    board::{self, RigidSemantics, MODIFIERS},
+    grid::Position,
+    level::Level,
+};
+
+/// The version of the TOML board format, stored in the `version` key of every file.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A board file in the TOML format, which unlike the plain format can also express a target and metadata.
+///
+/// ```toml
+/// version = 1
+/// state = ["010", "111", "010"]
+/// modifiers = ["   ", " O ", "   "]
+/// target = ["000", "000", "000"]
+/// rigid = "frozen"
+/// par = 5
+/// ```
+///
+/// `state` uses the characters of board files, `modifiers` those of modifier grids, and `target` is a grid of the values each cell
+/// must end up with, with spaces over holes. `rigid` names the `RigidSemantics` of the board. Only `version` and `state` are
+/// required. The target defaults to every cell lit, and the rigid semantics to the default ones.
+/// Unknown keys are rejected, so that files written for newer features are not silently solved as something else.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlBoard {
+    version: u32,
+    state: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modifiers: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rigid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    par: Option<usize>,
+}
+
+/// Parses a board file in the TOML format. A par missing from the file is computed from the board.
+pub fn parse(text: &str) -> Result<Level, String> {
+    let file: TomlBoard = toml::from_str(text).map_err(|e| e.message().to_string())?;
+    if file.version != FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported TOML board format version {}. Supported version: {}.",
+            file.version, FORMAT_VERSION
+        ));
+    }
+
+    let mut board = match board::parse_board_rows(&file.state.join("\n")) {
+        Ok((_, board)) => board,
+        Err(e) => return Err(format!("Could not parse state: {}", e)),
+    };
+    if board.cell_count() == 0 {
+        return Err("Board has no pressable cells.".to_string());
+    }
+    if let Some(modifiers) = &file.modifiers {
+        board.apply_modifier_text(&modifiers.join("\n"))?;
+    }
+    if let Some(target) = &file.target {
+        board.apply_target(target)?;
+    }
+    if let Some(rigid) = &file.rigid {
+        board.set_rigid_semantics(
+            RigidSemantics::from_name(rigid)
+                .ok_or_else(|| format!("Unknown rigid semantics \"{}\".", rigid))?,
+        );
+    }
+
+    Ok(Level::with_par(board, file.par))
+}
+
+/// Renders `level` in the TOML format. The modifiers, target and rigid semantics are only included if they differ from the defaults.
+pub fn to_text(level: &Level) -> String {
+    let board = &level.board;
+    let grid = |f: &dyn Fn(Position) -> char| -> Vec<String> {
+        (0..board.height())
+            .map(|row| {
+                (0..board.width())
+                    .map(|col| f(Position::new(row, col)))
+                    .collect()
+            })
+            .collect()
+    };
+    let state = grid(&|pos| board[pos].map_or(' ', |cell| bit_char(cell.starting_value)));
+    let modifiers = grid(&|pos| {
+        board[pos].map_or(' ', |cell| {
+            cell.modifiers()
+                .chars()
+                .find(|c| MODIFIERS.contains(c))
+                .unwrap_or(' ')
+        })
+    });
+    let target = grid(&|pos| board[pos].map_or(' ', |cell| bit_char(cell.target_value)));
+
+    let file = TomlBoard {
+        version: FORMAT_VERSION,
+        state,
+        modifiers: modifiers
+            .iter()
+            .any(|row| row.chars().any(|c| MODIFIERS.contains(&c)))
+            .then_some(modifiers),
+        target: (!board.has_default_target()).then_some(target),
+        rigid: (board.rigid_semantics() != RigidSemantics::default())
+            .then(|| board.rigid_semantics().name().to_string()),
+        par: level.par,
+    };
+    toml::to_string(&file).expect("Board files always serialize")
+}
+
+fn bit_char(bit: Bit) -> char {
+    match bit {
+        Bit::Off => '0',
+        Bit::On => '1',
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::json::Value;
+
+/// Whether spans are recorded. Off unless `enable` is called, in which case a span costs a single atomic load.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// When recording started. Span times are measured from it.
+static START: OnceLock<Instant> = OnceLock::new();
+/// Every span finished so far, in the order they finished.
+static EVENTS: Mutex<Vec<Event>> = Mutex::new(vec![]);
+/// The number given to the next thread that finishes a span.
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// The number of the current thread in the trace, or 0 before it finishes a span.
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A finished span.
+struct Event {
+    name: &'static str,
+    thread: u64,
+    start: Duration,
+    duration: Duration,
+}
+
+/// Starts recording spans, for as long as the program runs.
+pub fn enable() {
+    START.get_or_init(Instant::now);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Times a stage of solving until the returned span is dropped. Spans opened while another is open nest inside it. Does nothing
+/// unless recording was started with `enable`.
+///
+/// The stages of the solver are `parse`, `index` (building the board matrix), `eliminate`, `extract` (reading the equations off the
+/// reduced matrix) and `enumerate` (searching the solutions).
+pub fn span(name: &'static str) -> Span {
+    Span {
+        name,
+        start: ENABLED.load(Ordering::Relaxed).then(Instant::now),
+    }
+}
+
+/// A stage being timed, created by `span`.
+#[must_use = "A span is recorded when dropped, so it must be kept for as long as the stage runs."]
+pub struct Span {
+    name: &'static str,
+    /// When the span was opened, or `None` if spans are not recorded.
+    start: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let (Some(start), Some(&epoch)) = (self.start, START.get()) else {
+            return;
+        };
+        let thread = THREAD.with(|thread| {
+            if thread.get() == 0 {
+                thread.set(NEXT_THREAD.fetch_add(1, Ordering::Relaxed));
+            }
+            thread.get()
+        });
+        let event = Event {
+            name: self.name,
+            thread,
+            start: start.duration_since(epoch),
+            duration: start.elapsed(),
+        };
+        if let Ok(mut events) = EVENTS.lock() {
+            events.push(event);
+        }
+    }
+}
+
+/// Renders the spans recorded so far in the Chrome trace event format, as complete events timed in microseconds. Perfetto,
+/// `chrome://tracing` and speedscope open it as a flame chart.
+pub fn to_chrome_json() -> Value {
+    let micros = |duration: Duration| Value::Number(duration.as_nanos() as f64 / 1000.0);
+    let events = EVENTS.lock().map_or(vec![], |events| {
+        events
+            .iter()
+            .map(|event| {
+                Value::Object(vec![
+                    ("name".to_string(), Value::from(event.name)),
+                    ("cat".to_string(), Value::from("solver")),
+                    ("ph".to_string(), Value::from("X")),
+                    ("ts".to_string(), micros(event.start)),
+                    ("dur".to_string(), micros(event.duration)),
+                    ("pid".to_string(), Value::from(1)),
+                    ("tid".to_string(), Value::from(event.thread as usize)),
+                ])
+            })
+            .collect()
+    });
+    Value::Object(vec![
+        ("traceEvents".to_string(), Value::Array(events)),
+        ("displayTimeUnit".to_string(), Value::from("ms")),
+    ])
+}
+
+/// Writes the spans recorded so far to the file at `path`, as rendered by `to_chrome_json`.
+pub fn write(path: &str) -> Result<(), String> {
+    std::fs::write(path, to_chrome_json().to_string())
+        .map_err(|e| format!("Could not write {}: {}", path, e))
+}
@@ -0,0 +1,143 @@
+use std::io::BufRead;
+
+use wayout_solver::{
+    board::{self, BoardAssignment, BoardDescription},
+    coords,
+    i18n::{say, tr},
+    report::Report,
+};
+
+/// The board used when the user does not want to type their own.
+const EXAMPLE_BOARD: &str = "010\n111\n010";
+
+/// A tiny board with a free variable: both cells toggle each other, so pressing either one lights up the board.
+const FREE_VARIABLE_BOARD: &str = "00";
+
+/// Walks a new user through solving a tiny board, explaining each step of the real solving pipeline as it happens.
+pub fn run() {
+    let mut lines = std::io::stdin().lock().lines();
+
+    say!("Welcome to the tutorial! We will solve a tiny 3x3 board together, one step at a time.");
+    say!("A board is written one row per line: 1 is a lit cell, 0 is an unlit cell, and a space is a cell without a light.");
+    say!("Pressing a cell toggles it and its four neighbors. The goal is to light up every cell.");
+    println!();
+
+    let board = loop {
+        say!(
+            "Type a 3x3 board as three lines of 0s and 1s, or press Enter to use an example board."
+        );
+        let Some(Ok(first)) = lines.next() else {
+            return;
+        };
+        if first.is_empty() {
+            break parse(EXAMPLE_BOARD);
+        }
+        let mut rows = vec![first];
+        for _ in 0..2 {
+            let Some(Ok(line)) = lines.next() else {
+                return;
+            };
+            rows.push(line);
+        }
+        if rows
+            .iter()
+            .all(|row| row.len() == 3 && row.chars().all(|c| c == '0' || c == '1'))
+        {
+            break parse(&rows.join("\n"));
+        }
+        say!("Each line must have exactly three 0s or 1s. Let's try again.");
+    };
+
+    say!("Here is your board. W is its width, H its height, and C the number of lit cells:");
+    print!("{}", board);
+    pause(&mut lines);
+
+    let (matrix, indexed_locations) = board.to_matrix();
+    say!("Every cell is a variable: 1 if we press it, 0 if we don't. Cells are numbered from 0, row by row.");
+    say!("Each row of the matrix below is an equation for one cell. A 1 in column j means pressing cell j toggles that cell.");
+    say!("The last column says whether the cell needs to change: 1 if it starts unlit.");
+    print!("{}", matrix);
+    pause(&mut lines);
+
+    let report = Report::new(&matrix);
+    say!("Now we simplify the equations with Gauss-Jordan elimination. Adding two equations is allowed, since 1 + 1 = 0 here.");
+    say!(
+        "This is the reduced matrix. Its rank, the number of useful equations, is {}:",
+        report.rank
+    );
+    print!("{}", report.reduced);
+    pause(&mut lines);
+
+    explain_free_variables(&report);
+    pause(&mut lines);
+
+    let solution = report
+        .equations
+        .enumerate_all_results()
+        .into_iter()
+        .map(|assignment| board.assign_assignment(assignment, &indexed_locations))
+        .min_by_key(BoardAssignment::count_ones);
+    let Some(solution) = solution else {
+        say!("Every 3x3 board is solvable, so this should not happen!");
+        return;
+    };
+    say!("Here is the solution with the fewest presses. Press every cell marked with a 1:");
+    print!("{}", solution);
+    for pos in solution.presses() {
+        say!(
+            "Press row {}, column {}.",
+            coords::row_number(pos.row, solution.height()),
+            coords::col_number(pos.col)
+        );
+    }
+    say!("Simulating those presses gives this board, with every cell lit:");
+    print!("{}", board.apply_assignment(&solution));
+    pause(&mut lines);
+
+    say!("Some boards have free variables. Take this board of two unlit cells next to each other:");
+    let small = parse(FREE_VARIABLE_BOARD);
+    print!("{}", small);
+    let (small_matrix, small_locations) = small.to_matrix();
+    let small_report = Report::new(&small_matrix);
+    explain_free_variables(&small_report);
+    for (count, assignment) in small_report
+        .equations
+        .enumerate_all_results()
+        .into_iter()
+        .enumerate()
+    {
+        say!(
+            "Solution #{}:\n{}",
+            count + 1,
+            small.assign_assignment(assignment, &small_locations)
+        );
+    }
+    say!("That's it! Run the solver without arguments to solve your own boards.");
+}
+
+/// Explains the free variables of the system in `report` in plain language.
+fn explain_free_variables(report: &Report) {
+    let eqns = &report.equations;
+    say!("These are the equations we get, each one solving for a single cell:");
+    print!("{}", eqns);
+    match eqns.free_var_count() {
+        0 => say!("There are no free variables: every cell is decided by its equation, so there is exactly one solution."),
+        count => say!(
+            "There are {} free variables. They are cells we can choose to press or not, and the equations decide everything else. Each choice gives a different solution, {} in total.",
+            count,
+            eqns.solution_count().map_or(tr!("too many to count"), |c| c.to_string())
+        ),
+    }
+}
+
+/// Waits for the user to press Enter.
+fn pause(lines: &mut impl Iterator<Item = std::io::Result<String>>) {
+    say!("(Press Enter to continue.)");
+    let _ = lines.next();
+    println!();
+}
+
+/// Parses a board known to be valid.
+fn parse(text: &str) -> BoardDescription {
+    board::parse_board_file(text).unwrap() // Only called with valid boards.
+}
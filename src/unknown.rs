@@ -0,0 +1,192 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{
+    bit::Bit,
+    board::{self, BoardDescription},
+    grid::Position,
+    matrix::{get_leading_column, Matrix},
+};
+
+/// The character marking a cell whose starting state is unknown.
+pub const UNKNOWN: char = '?';
+
+/// A board where the starting state of some cells is unknown.
+#[derive(Clone, Debug)]
+pub struct PartialBoard {
+    /// The board, with every unknown cell starting `Off`.
+    pub board: BoardDescription,
+    /// The unknown cells, in reading order. The unknown at index `k` is called `u{k+1}` in conditions.
+    pub unknowns: Vec<Position>,
+}
+
+/// A linear condition on the unknowns of a `PartialBoard`: the sum of the listed unknowns must equal `value`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condition {
+    /// Indices into `PartialBoard::unknowns`.
+    pub unknowns: Vec<usize>,
+    pub value: Bit,
+}
+
+/// For which values of its unknowns a `PartialBoard` is solvable.
+///
+/// Each unknown is an extra symbolic variable in the constant column, which elimination carries along in its own column. Every row
+/// left without a pivot among the presses then states a condition on the unknowns alone, and the board is solvable exactly when all
+/// of them hold.
+#[derive(Clone, Debug)]
+pub struct Solvability {
+    unknown_count: usize,
+    /// The conditions, in reduced row-echelon form over the unknowns. `None` if no values of the unknowns make the board solvable.
+    conditions: Option<Vec<Condition>>,
+}
+
+impl PartialBoard {
+    /// Parses a board file where `?` marks a cell with unknown starting state.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.replace("\r\n", "\n");
+        let body = board::strip_metadata(&text);
+        let board_text = body
+            .split_once("\n\n")
+            .map_or(body, |(board_text, _)| board_text);
+        let unknowns = board_text
+            .lines()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.char_indices()
+                    .filter(|&(_, c)| c == UNKNOWN)
+                    .map(move |(col, _)| Position::new(row, col))
+            })
+            .collect();
+        let board = board::parse_board_file(&text.replace(UNKNOWN, "0"))?;
+        Ok(PartialBoard { board, unknowns })
+    }
+
+    /// Produces the board where the unknown cells take `values`, in the order of `self.unknowns`.
+    pub fn resolve(&self, values: &[Bit]) -> BoardDescription {
+        let mut board = self.board.clone();
+        for (&pos, &value) in self.unknowns.iter().zip(values) {
+            if let Some(cell) = &mut board[pos] {
+                cell.starting_value = value;
+            }
+        }
+        board
+    }
+
+    /// Settles the starting value of the unknown at index `k` of `self.unknowns`, which is then no longer unknown.
+    pub fn fix(&mut self, k: usize, value: Bit) {
+        let pos = self.unknowns.remove(k);
+        if let Some(cell) = &mut self.board[pos] {
+            cell.starting_value = value;
+        }
+    }
+
+    /// Computes for which values of the unknowns `self` is solvable.
+    pub fn solvability(&self) -> Solvability {
+        let (matrix, _) = self.board.to_matrix();
+        let n = matrix.variable_cols();
+        let k = self.unknowns.len();
+        let indices: Vec<usize> = self
+            .unknowns
+            .iter()
+            .map(|&unknown| {
+                Position::iter_row_major(self.board.width(), self.board.height())
+                    .filter(|&pos| self.board[pos].is_some())
+                    .position(|pos| pos == unknown)
+                    .expect("Unknown cells are never holes")
+            })
+            .collect();
+        let data = (0..matrix.rows)
+            .map(|row| {
+                let bits = matrix.row(row);
+                let mut augmented = bits[..n].to_vec();
+                augmented.extend(
+                    indices
+                        .iter()
+                        .map(|&index| if index == row { Bit::On } else { Bit::Off }),
+                );
+                augmented.push(bits[n]);
+                augmented
+            })
+            .collect();
+        let mut reduced = Matrix::new(data, matrix.rows, n + k + 1).expect("Could not form matrix");
+        reduced.eliminate();
+
+        let mut conditions = vec![];
+        for row in (0..reduced.rows).map(|row| reduced.row(row)) {
+            if get_leading_column(&row[..n]).is_some() {
+                continue;
+            }
+            let unknowns: Vec<usize> = (0..k).filter(|&u| row[n + u] == Bit::On).collect();
+            let value = row[n + k];
+            if unknowns.is_empty() {
+                if value == Bit::On {
+                    return Solvability {
+                        unknown_count: k,
+                        conditions: None,
+                    };
+                }
+                continue;
+            }
+            conditions.push(Condition { unknowns, value });
+        }
+        Solvability {
+            unknown_count: k,
+            conditions: Some(conditions),
+        }
+    }
+}
+
+impl Solvability {
+    /// The conditions the unknowns must meet for the board to be solvable. `None` if no values meet them.
+    pub fn conditions(&self) -> Option<&[Condition]> {
+        self.conditions.as_deref()
+    }
+
+    /// Checks whether the board is solvable when its unknowns take `values`.
+    pub fn is_solvable_with(&self, values: &[Bit]) -> bool {
+        self.conditions.as_ref().is_some_and(|conditions| {
+            conditions.iter().all(|condition| {
+                condition
+                    .unknowns
+                    .iter()
+                    .fold(Bit::Off, |sum, &u| sum + values[u])
+                    == condition.value
+            })
+        })
+    }
+
+    /// Lists every combination of values of the unknowns that makes the board solvable, in lexicographic order.
+    /// There are `2^k` combinations for `k` unknowns, so this is only practical for a handful of them.
+    pub fn solvable_values(&self) -> Vec<Vec<Bit>> {
+        let k = self.unknown_count;
+        (0..1u128 << k)
+            .map(|combination| {
+                (0..k)
+                    .map(|u| match combination >> (k - 1 - u) & 1 {
+                        1 => Bit::On,
+                        _ => Bit::Off,
+                    })
+                    .collect::<Vec<Bit>>()
+            })
+            .filter(|values| self.is_solvable_with(values))
+            .collect()
+    }
+
+    /// Counts the values of the unknowns that make the board solvable. Returns `None` if the count does not fit in a `u128`.
+    pub fn solvable_count(&self) -> Option<u128> {
+        match &self.conditions {
+            None => Some(0),
+            Some(conditions) => 1u128.checked_shl((self.unknown_count - conditions.len()) as u32),
+        }
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let terms: Vec<String> = self
+            .unknowns
+            .iter()
+            .map(|u| format!("u{}", u + 1))
+            .collect();
+        write!(f, "{} = {}", terms.join(" + "), self.value)
+    }
+}
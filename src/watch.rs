@@ -0,0 +1,65 @@
+use std::{path::Path, time::SystemTime};
+
+use wayout_solver::{
+    board::{BoardAssignment, BoardDescription},
+    decoder::CosetDecoder,
+    solver,
+};
+
+/// Re-solves a board that keeps changing as it is played, reusing the elimination work as long as its shape stays the same.
+///
+/// While the holes and modifiers of the board do not change, every re-solve is a single `CosetDecoder::decode`. A new decoder is
+/// only built when the shape changes. Shapes with too many free variables for a decoder fall back to `solver::minimum_solution`.
+#[derive(Default)]
+pub struct Resolver {
+    decoder: Option<CosetDecoder>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver::default()
+    }
+
+    /// Finds a solution of `board` with as few presses as possible. Returns `None` if `board` has no solutions.
+    pub fn solve(&mut self, board: &BoardDescription) -> Option<BoardAssignment> {
+        if !self
+            .decoder
+            .as_ref()
+            .is_some_and(|decoder| board.same_shape(decoder.shape()))
+        {
+            self.decoder = CosetDecoder::new(board);
+        }
+        match &self.decoder {
+            Some(decoder) => decoder.decode(board).ok().flatten(),
+            None => solver::minimum_solution(board),
+        }
+    }
+}
+
+/// Tracks the modification time of a file, to notice when it is rewritten.
+pub struct FileWatch<'a> {
+    path: &'a Path,
+    modified: Option<SystemTime>,
+}
+
+impl<'a> FileWatch<'a> {
+    pub fn new(path: &'a Path) -> Self {
+        FileWatch {
+            path,
+            modified: None,
+        }
+    }
+
+    /// Checks whether the file changed since the last call. The first call always reports a change, if the file exists.
+    pub fn changed(&mut self) -> bool {
+        let modified = std::fs::metadata(self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified.is_some() && modified != self.modified {
+            self.modified = modified;
+            true
+        } else {
+            false
+        }
+    }
+}